@@ -1,19 +1,65 @@
-use std::{path::PathBuf, sync::Mutex};
+use std::{path::{Path, PathBuf}, sync::Mutex};
 
-use crate::{cache, editor, errors, prefs, project};
+use crate::{cache, editor, errors, io_utils, prefs, process, project};
+
+// must match tauri.conf.json > tauri > bundle > identifier
+pub const APP_IDENTIFIER: &str = "nomnom-unity-hub";
 
 pub struct AppState {
   pub prefs: Mutex<prefs::Prefs>,
   pub user_cache: Mutex<cache::UserCache>,
   pub projects: Mutex<Vec<project::Project>>,
   pub editors: Mutex<Vec<editor::UnityEditorInstall>>,
+  pub processes: Mutex<Vec<process::LaunchedEditor>>,
+  pub config_dir: PathBuf,
+  pub cache_dir: PathBuf,
+}
+
+impl AppState {
+  // loads everything from disk, the editor list is left empty
+  pub fn load(config_dir: PathBuf, cache_dir: PathBuf) -> anyhow::Result<Self> {
+    let prefs = load_prefs_from_disk(&config_dir)?;
+    let user_cache = load_user_cache_from_disk(&config_dir)?;
+    let projects = load_projects_from_disk(&config_dir)?;
+
+    Ok(Self {
+      prefs: Mutex::new(prefs),
+      user_cache: Mutex::new(user_cache),
+      projects: Mutex::new(projects),
+      editors: Mutex::new(Vec::new()),
+      processes: Mutex::new(Vec::new()),
+      config_dir,
+      cache_dir,
+    })
+  }
+
+  // same folders tauri resolves for the app, without needing an app handle
+  pub fn load_default() -> anyhow::Result<Self> {
+    let config_dir = dirs_next::config_dir()
+      .ok_or(errors::str_error("Failed to get config dir"))?
+      .join(APP_IDENTIFIER);
+    let cache_dir = dirs_next::cache_dir()
+      .ok_or(errors::str_error("Failed to get cache dir"))?
+      .join(APP_IDENTIFIER);
+    Self::load(config_dir, cache_dir)
+  }
 }
 
-pub fn get_save_path(name: &str, app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+pub fn get_config_dir(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
   let config = app_handle.config();
   let config_dir = tauri::api::path::app_config_dir(&config)
-    .expect("failed to get app config dir");
-  
+    .ok_or(errors::str_error("Failed to get app config dir"))?;
+  Ok(config_dir)
+}
+
+pub fn get_cache_dir(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+  let cache_dir = app_handle.path_resolver().app_cache_dir()
+    .ok_or(errors::str_error("Failed to get app cache dir"))?;
+  Ok(cache_dir)
+}
+
+pub fn get_save_path(name: &str, config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  let config_dir = config_dir.as_ref();
   std::fs::create_dir_all(&config_dir)?;
   
   let path = config_dir
@@ -23,124 +69,209 @@ pub fn get_save_path(name: &str, app_handle: &tauri::AppHandle) -> anyhow::Resul
   Ok(path)
 }
 
-pub fn load_from_disk<T>(path: impl Into<PathBuf>) -> anyhow::Result<T>
+// every persisted file is stored as { "schemaVersion": n, "data": ... }
+// where n is the number of migrations that have been applied to it
+pub type Migration = fn(serde_json::Value) -> anyhow::Result<serde_json::Value>;
+
+pub const PREFS_MIGRATIONS: &[Migration] = &[migrate_to_versioned_file];
+pub const USER_CACHE_MIGRATIONS: &[Migration] = &[migrate_to_versioned_file];
+pub const PROJECTS_MIGRATIONS: &[Migration] = &[migrate_to_versioned_file];
+
+// files written before versioning have no schemaVersion, they count as 0
+pub fn schema_version(value: &serde_json::Value) -> u32 {
+  value
+    .get("schemaVersion")
+    .and_then(|x| x.as_u64())
+    .unwrap_or(0) as u32
+}
+
+// run every migration between the file's version and the latest, in order
+pub fn migrate(value: serde_json::Value, migrations: &[Migration]) -> anyhow::Result<serde_json::Value> {
+  let version = schema_version(&value) as usize;
+  if version > migrations.len() {
+    return Err(anyhow::anyhow!("Schema version {} is newer than this app supports ({})", version, migrations.len()));
+  }
+
+  let mut value = value;
+  for migration in &migrations[version..] {
+    value = migration(value)?;
+  }
+  Ok(value)
+}
+
+// returns the value and whether it had to be migrated
+fn read_json<T>(path: &Path, migrations: &[Migration]) -> Option<(T, bool)>
+where T: serde::de::DeserializeOwned {
+  let json = std::fs::read_to_string(path).ok()?;
+  let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+  let version = schema_version(&value);
+
+  let value = migrate(value, migrations)
+    .inspect_err(|err| println!("Failed to migrate {}: {}", path.display(), err))
+    .ok()?;
+  let field = serde_json::from_value(value.get("data")?.clone()).ok()?;
+  Some((field, version < migrations.len() as u32))
+}
+
+// a file written by a newer build isn't corrupt, this build just can't read it
+fn ensure_not_newer(path: &Path, migrations: &[Migration]) -> Result<(), errors::AnyError> {
+  let Some(value) = std::fs::read_to_string(path)
+    .ok()
+    .and_then(|x| serde_json::from_str::<serde_json::Value>(&x).ok()) else {
+    return Ok(());
+  };
+
+  let version = schema_version(&value);
+  if version > migrations.len() as u32 {
+    return Err(errors::schema_too_new(path, version, migrations.len() as u32));
+  }
+  Ok(())
+}
+
+// a bad file is moved aside instead of overwritten, then the last good
+// backup is tried before falling back to defaults.
+// a file from a newer build is left alone and refused, falling back would wipe it
+pub fn load_from_disk<T>(path: impl Into<PathBuf>, migrations: &[Migration]) -> anyhow::Result<T>
 where T: Default + serde::Serialize + serde::de::DeserializeOwned {
   let path = path.into();
-  let json = std::fs::read_to_string(&path)
-    .unwrap_or("{}".to_string());
-  let (exists, field) = match serde_json::from_str(&json) {
-    Ok(field) => (true, field),
-    Err(_) => (false, T::default()),
-  };
-  
-  if !exists {
-    save_to_disk::<T>(path, &field)?;
+  ensure_not_newer(&path, migrations)?;
+  if let Some((field, migrated)) = read_json(&path, migrations) {
+    if migrated {
+      // the old file is kept as the .bak
+      save_to_disk::<T>(path, &field, migrations)?;
+    }
+    return Ok(field);
   }
-  
+
+  if path.exists() {
+    let quarantine_path = io_utils::quarantine(&path)?;
+    println!("Failed to parse {}, moved to {}", path.display(), quarantine_path.display());
+  }
+
+  let backup_path = io_utils::backup_path(&path);
+  let field = match read_json(&backup_path, migrations) {
+    Some((field, _)) => {
+      println!("Restored {} from {}", path.display(), backup_path.display());
+      field
+    }
+    None => T::default(),
+  };
+
+  save_to_disk::<T>(path, &field, migrations)?;
   Ok(field)
 }
 
-pub fn save_new_to_disk<T>(path: impl Into<PathBuf>) -> anyhow::Result<T>
+pub fn save_new_to_disk<T>(path: impl Into<PathBuf>, migrations: &[Migration]) -> anyhow::Result<T>
 where T: Default + serde::Serialize {
   let value = T::default();
-  save_to_disk(path, &value)?;
+  save_to_disk(path, &value, migrations)?;
   Ok(value)
 }
 
-pub fn save_to_disk<T>(path: impl Into<PathBuf>, field: &T) -> anyhow::Result<()>
+pub fn save_to_disk<T>(path: impl Into<PathBuf>, field: &T, migrations: &[Migration]) -> anyhow::Result<()>
 where T: serde::Serialize {
   let path = path.into();
-  let json = serde_json::to_string_pretty(&field)?;
-  std::fs::write(path, json)?;
+  let json = serde_json::to_string_pretty(&serde_json::json!({
+    "schemaVersion": migrations.len(),
+    "data": field,
+  }))?;
+  io_utils::write_atomic(path, json)?;
   Ok(())
 }
 
+// migrations
+
+// v0 -> v1, the bare struct or list gets wrapped with a version
+pub fn migrate_to_versioned_file(value: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+  Ok(serde_json::json!({ "schemaVersion": 1, "data": value }))
+}
+
 // prefs
 
-pub fn get_prefs_save_path(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
-  let path = get_save_path("prefs", app_handle)?;
+pub fn get_prefs_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  let path = get_save_path("prefs", config_dir)?;
   Ok(path)
 }
 
-pub fn get_prefs(app_state: &tauri::State<AppState>) -> anyhow::Result<prefs::Prefs> {
+pub fn get_prefs(app_state: &AppState) -> anyhow::Result<prefs::Prefs> {
   let prefs = app_state.prefs.lock()
-    .map_err(|_| errors::str_error("Failed to get prefs. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("prefs"))?;
   
   Ok(prefs.clone())
 }
 
-pub fn load_prefs_from_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<prefs::Prefs> {
-  let path = get_prefs_save_path(app_handle)?;
-  load_from_disk(&path)
+pub fn load_prefs_from_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<prefs::Prefs> {
+  let path = get_prefs_save_path(config_dir)?;
+  load_from_disk(&path, PREFS_MIGRATIONS)
 }
 
-pub fn save_new_prefs_to_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<prefs::Prefs> {
-  let path = get_prefs_save_path(app_handle)?;
-  save_new_to_disk::<prefs::Prefs>(path)
+pub fn save_new_prefs_to_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<prefs::Prefs> {
+  let path = get_prefs_save_path(config_dir)?;
+  save_new_to_disk::<prefs::Prefs>(path, PREFS_MIGRATIONS)
 }
 
-pub fn save_prefs_to_disk(prefs: &prefs::Prefs, app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-  let path = get_prefs_save_path(app_handle)?;
-  save_to_disk(path, prefs)?;
+pub fn save_prefs_to_disk(prefs: &prefs::Prefs, config_dir: impl AsRef<Path>) -> anyhow::Result<()> {
+  let path = get_prefs_save_path(config_dir)?;
+  save_to_disk(path, prefs, PREFS_MIGRATIONS)?;
   Ok(())
 }
 
 // user cache
 
-pub fn get_user_cache_save_path(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
-  let path = get_save_path("user_cache", app_handle)?;
+pub fn get_user_cache_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  let path = get_save_path("user_cache", config_dir)?;
   Ok(path)
 }
 
-pub fn get_user_cache(app_state: &tauri::State<AppState>) -> anyhow::Result<cache::UserCache> {
+pub fn get_user_cache(app_state: &AppState) -> anyhow::Result<cache::UserCache> {
   let user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
   
   Ok(user_cache.clone())
 }
 
-pub fn load_user_cache_from_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<cache::UserCache> {
-  let path = get_user_cache_save_path(app_handle)?;
-  load_from_disk(&path)
+pub fn load_user_cache_from_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<cache::UserCache> {
+  let path = get_user_cache_save_path(config_dir)?;
+  load_from_disk(&path, USER_CACHE_MIGRATIONS)
 }
 
-pub fn save_new_user_cache_to_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<cache::UserCache> {
-  let path = get_user_cache_save_path(app_handle)?;
-  save_new_to_disk::<cache::UserCache>(path)
+pub fn save_new_user_cache_to_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<cache::UserCache> {
+  let path = get_user_cache_save_path(config_dir)?;
+  save_new_to_disk::<cache::UserCache>(path, USER_CACHE_MIGRATIONS)
 }
 
-pub fn save_user_cache_to_disk(user_cache: &cache::UserCache, app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-  let path = get_user_cache_save_path(app_handle)?;
-  save_to_disk(path, &user_cache)
+pub fn save_user_cache_to_disk(user_cache: &cache::UserCache, config_dir: impl AsRef<Path>) -> anyhow::Result<()> {
+  let path = get_user_cache_save_path(config_dir)?;
+  save_to_disk(path, &user_cache, USER_CACHE_MIGRATIONS)
 }
 
 // projects
 
-pub fn get_projects_save_path(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
-  let path = get_save_path("projects", app_handle)?;
+pub fn get_projects_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  let path = get_save_path("projects", config_dir)?;
   Ok(path)
 }
 
-pub fn get_projects(app_state: &tauri::State<AppState>) -> anyhow::Result<Vec<project::Project>> {
+pub fn get_projects(app_state: &AppState) -> anyhow::Result<Vec<project::Project>> {
   let projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("projects"))?;
   
   Ok(projects.clone())
 }
 
-pub fn load_projects_from_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<Vec<project::Project>> {
-  let path = get_projects_save_path(app_handle)?;
-  load_from_disk(&path)
+pub fn load_projects_from_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<Vec<project::Project>> {
+  let path = get_projects_save_path(config_dir)?;
+  load_from_disk(&path, PROJECTS_MIGRATIONS)
 }
 
-pub fn save_new_projects_to_disk(app_handle: &tauri::AppHandle) -> anyhow::Result<Vec<project::Project>> {
-  let path = get_projects_save_path(app_handle)?;
-  save_new_to_disk::<Vec<project::Project>>(path)
+pub fn save_new_projects_to_disk(config_dir: impl AsRef<Path>) -> anyhow::Result<Vec<project::Project>> {
+  let path = get_projects_save_path(config_dir)?;
+  save_new_to_disk::<Vec<project::Project>>(path, PROJECTS_MIGRATIONS)
 }
 
-pub fn save_projects_to_disk(projects: &Vec<project::Project>, app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-  let path = get_projects_save_path(app_handle)?;
-  save_to_disk(path, &projects)
+pub fn save_projects_to_disk(projects: &Vec<project::Project>, config_dir: impl AsRef<Path>) -> anyhow::Result<()> {
+  let path = get_projects_save_path(config_dir)?;
+  save_to_disk(path, &projects, PROJECTS_MIGRATIONS)
 }
 
 // editors
@@ -150,9 +281,9 @@ pub fn save_projects_to_disk(projects: &Vec<project::Project>, app_handle: &taur
 //     Ok(path)
 // }
 
-pub fn get_editors(app_state: &tauri::State<AppState>) -> anyhow::Result<Vec<editor::UnityEditorInstall>> {
+pub fn get_editors(app_state: &AppState) -> anyhow::Result<Vec<editor::UnityEditorInstall>> {
   let editors = app_state.editors.lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("editors"))?;
   
   Ok(editors.clone())
 }
@@ -26,28 +26,28 @@ impl Default for UserCache {
 // commands
 
 #[tauri::command]
-pub fn cmd_get_user_cache(app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<UserCache, errors::AnyError> {
+pub fn cmd_get_user_cache(app_state: tauri::State<'_, AppState>) -> Result<UserCache, errors::AnyError> {
     //let user_cache = app::get_user_cache(&app_state)?;
     let mut user_cache = app_state.user_cache.lock()
-      .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+      .map_err(|_| errors::state_locked("user_cache"))?;
 
     user_cache.local_packages.retain(|x| std::path::Path::new(&x.name).exists());
-    app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+    app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
     
     Ok(user_cache.clone())
 }
 
 #[tauri::command]
-pub fn cmd_save_user_cache(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+pub fn cmd_save_user_cache(app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
     let user_cache = app::get_user_cache(&app_state)?;
-    app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+    app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_set_user_cache_value(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, key: UserCacheKey, value: serde_json::Value) -> Result<(), errors::AnyError> {
+pub fn cmd_set_user_cache_value(app_state: tauri::State<AppState>, key: UserCacheKey, value: serde_json::Value) -> Result<(), errors::AnyError> {
   let mut user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
 
   match key {
     UserCacheKey::LastEditorVersion => {
@@ -56,52 +56,52 @@ pub fn cmd_set_user_cache_value(app_handle: tauri::AppHandle, app_state: tauri::
     _ => return Err(errors::str_error("Invalid key")),
   }
 
-  app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+  app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_add_git_package_to_cache(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
+pub fn cmd_add_git_package_to_cache(app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
   let mut user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
   user_cache.git_packages.push(package);
-  app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+  app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_add_local_package_to_cache(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
+pub fn cmd_add_local_package_to_cache(app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
   let mut user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
   user_cache.local_packages.push(package);
-  app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+  app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_remove_git_package_from_cache(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
+pub fn cmd_remove_git_package_from_cache(app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
   let mut user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
   user_cache.git_packages.retain(|p| !(p.name == package.name && (p.version == package.version || (p.version.is_empty() && package.version.is_empty()))));
-  app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+  app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_remove_local_package_from_cache(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
+pub fn cmd_remove_local_package_from_cache(app_state: tauri::State<AppState>, package: package::MinimalPackage) -> Result<(), errors::AnyError> {
   let mut user_cache = app_state.user_cache.lock()
-    .map_err(|_| errors::str_error("Failed to get user_cache. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("user_cache"))?;
   user_cache.local_packages.retain(|p| !(p.name == package.name && (p.version == package.version || (p.version.is_empty() && package.version.is_empty()))));
-  app::save_user_cache_to_disk(&user_cache, &app_handle)?;
+  app::save_user_cache_to_disk(&user_cache, &app_state.config_dir)?;
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_delete_template_cache(app_handle: tauri::AppHandle) -> Result<(), errors::AnyError> {
-  let cache_dir = io_utils::get_cache_appended_dir(&app_handle, "templates")?;
+pub fn cmd_delete_template_cache(app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+  let cache_dir = io_utils::get_cache_appended_dir(app_state.inner(), "templates")?;
   std::fs::remove_dir_all(&cache_dir)?;
 
-  let cache_dir = io_utils::get_cache_dir(&app_handle)?
+  let cache_dir = io_utils::get_cache_dir(app_state.inner())?
     .join("editors")
     .with_extension("json");
 
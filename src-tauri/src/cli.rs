@@ -0,0 +1,330 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+  app::{self, AppState},
+  editor::{self, UnityEditorInstall},
+  errors,
+  generate::{self, NewTemplateInfo, ProjectInfoForGeneration, ProjectTemplateInfoForGeneration, TemplateInfoForGeneration},
+  package::{self, MinimalPackage, PackageType},
+  project,
+  template::{self, SurfaceTemplate},
+};
+
+const USAGE: &str = "usage: nomnom-unity-hub <command> [options]
+
+commands:
+  projects list
+  projects add <path>
+  editors list
+  generate project --name <name> --path <dir> --editor <version> [--template <tgz>] [--package <name@version>]...
+  generate template --name <name> --version <version> --editor <version>
+                    [--display-name <name>] [--description <text>]
+                    [--template <tgz> | --from-project <dir>] [--package <name@version>]...
+
+options:
+  --json    print results as json for scripting";
+
+const COMMANDS: [&str; 6] = ["projects", "editors", "generate", "help", "--help", "-h"];
+
+#[derive(Debug, Default)]
+struct CliArgs {
+  positional: Vec<String>,
+  options: HashMap<String, Vec<String>>,
+  json: bool,
+}
+
+impl CliArgs {
+  fn parse(args: &[String]) -> Self {
+    let mut parsed = CliArgs::default();
+    let mut iter = args.iter().peekable();
+
+    while let Some(arg) = iter.next() {
+      if arg == "--json" {
+        parsed.json = true;
+        continue;
+      }
+
+      if let Some(key) = arg.strip_prefix("--") {
+        let value = iter
+          .next_if(|x| !x.starts_with("--"))
+          .cloned()
+          .unwrap_or_default();
+        parsed.options
+          .entry(key.to_string())
+          .or_default()
+          .push(value);
+        continue;
+      }
+
+      parsed.positional.push(arg.clone());
+    }
+
+    parsed
+  }
+
+  fn get(&self, key: &str) -> Option<&String> {
+    self.options
+      .get(key)
+      .and_then(|x| x.last())
+  }
+
+  fn get_all(&self, key: &str) -> Vec<String> {
+    self.options
+      .get(key)
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  fn require(&self, key: &str) -> Result<&String, errors::AnyError> {
+    self.get(key)
+      .filter(|x| !x.is_empty())
+      .ok_or(errors::str_error(&format!("Missing required option --{}", key)))
+  }
+}
+
+// true when the process was started as `nomnom-unity-hub <command> ...`
+// instead of as the gui
+pub fn is_cli_invocation(args: &[String]) -> bool {
+  args
+    .get(1)
+    .is_some_and(|x| COMMANDS.contains(&x.as_str()))
+}
+
+// runs a cli command and returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+  let args = CliArgs::parse(args);
+  let command = args.positional
+    .iter()
+    .map(|x| x.as_str())
+    .collect::<Vec<_>>();
+
+  if matches!(command.first(), None | Some(&"help") | Some(&"--help") | Some(&"-h")) {
+    println!("{}", USAGE);
+    return 0;
+  }
+
+  let app_state = match AppState::load_default() {
+    Ok(app_state) => app_state,
+    Err(err) => return print_error(&args, &err.into()),
+  };
+
+  let result = match command.as_slice() {
+    ["projects", "list"] => app::get_projects(&app_state)
+      .map_err(errors::AnyError::from)
+      .map(|projects| print_projects(&args, &projects)),
+    ["projects", "add", path] => project::add_project(PathBuf::from(path), &app_state)
+      .map(|project| print_projects(&args, &vec![project])),
+    ["editors", "list"] => editor::refresh_editor_installs(&app_state)
+      .map(|editors| print_editors(&args, &editors)),
+    ["generate", "project"] => generate_project(&args, &app_state)
+      .map(|path| print_path(&args, &path)),
+    ["generate", "template"] => generate_template(&args, &app_state)
+      .map(|path| print_path(&args, &path)),
+    _ => {
+      eprintln!("{}", USAGE);
+      return 2;
+    }
+  };
+
+  match result {
+    Ok(_) => 0,
+    Err(err) => print_error(&args, &err),
+  }
+}
+
+fn find_editor(version: &str, app_state: &AppState) -> Result<UnityEditorInstall, errors::AnyError> {
+  editor::refresh_editor_installs(app_state)?
+    .into_iter()
+    .find(|x| x.version == version)
+    .ok_or(errors::editor_not_found(version))
+}
+
+fn find_template(path: &str, editor_version: &str) -> Result<SurfaceTemplate, errors::AnyError> {
+  let path = PathBuf::from(path);
+  if !path.is_file() {
+    return Err(errors::io_not_found("Invalid template path"));
+  }
+  if !path.extension().is_some_and(|x| x == "tgz") {
+    return Err(errors::str_error("Templates are .tgz files"));
+  }
+
+  let (name, version) = template::get_info_from_file_name(&path)?;
+  Ok(SurfaceTemplate { name, version, path, editor_version: editor_version.to_string() })
+}
+
+fn package_from_dependency(name: &str, version: &str) -> MinimalPackage {
+  let is_git = version.starts_with("git") || version.starts_with("http") || version.ends_with(".git");
+  MinimalPackage {
+    name: name.to_string(),
+    version: version.to_string(),
+    is_discoverable: false,
+    _type: if is_git { PackageType::Git } else { PackageType::Default },
+  }
+}
+
+// --package com.unity.foo@1.0.0, or a path to a local package.json
+fn parse_package_arg(arg: &str) -> Result<MinimalPackage, errors::AnyError> {
+  if arg.ends_with("package.json") && std::path::Path::new(arg).is_file() {
+    return Ok(MinimalPackage {
+      name: arg.to_string(),
+      version: String::new(),
+      is_discoverable: false,
+      _type: PackageType::Local,
+    });
+  }
+
+  let (name, version) = arg
+    .split_once('@')
+    .ok_or(errors::str_error(&format!("Invalid package {}, expected name@version", arg)))?;
+  Ok(package_from_dependency(name, version))
+}
+
+// base packages from the template (or the editor defaults), then any --package overrides
+fn collect_packages(args: &CliArgs, base: Vec<MinimalPackage>) -> Result<Vec<MinimalPackage>, errors::AnyError> {
+  let mut packages = base;
+  for arg in args.get_all("package") {
+    let package = parse_package_arg(&arg)?;
+    packages.retain(|x| x.name != package.name);
+    packages.push(package);
+  }
+  Ok(packages)
+}
+
+fn template_packages(surface_template: &SurfaceTemplate, app_state: &AppState) -> Result<Vec<MinimalPackage>, errors::AnyError> {
+  let record = template::extract_template_information(app_state, surface_template)?;
+  let mut packages = record.tgz_package.dependencies
+    .unwrap_or_default()
+    .iter()
+    .map(|(name, version)| package_from_dependency(name, version))
+    .collect::<Vec<_>>();
+  packages.sort_by(|x, y| x.name.cmp(&y.name));
+  Ok(packages)
+}
+
+fn template_info_from_args(args: &CliArgs, app_state: &AppState) -> Result<TemplateInfoForGeneration, errors::AnyError> {
+  let editor_version = args.require("editor")?;
+  let editor = find_editor(editor_version, app_state)?;
+
+  let template_info = match args.get("template") {
+    Some(path) => {
+      let surface_template = find_template(path, editor_version)?;
+      let packages = collect_packages(args, template_packages(&surface_template, app_state)?)?;
+      let selected_files = template::read_template_entry_paths(&surface_template.path)?;
+      TemplateInfoForGeneration {
+        template: Some(surface_template),
+        editor_version: editor,
+        packages,
+        selected_files,
+        is_empty: false,
+      }
+    }
+    None => {
+      let defaults = package::get_default_editor_packages(editor_version.clone(), app_state)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|x| x._type == PackageType::Default)
+        .collect::<Vec<_>>();
+      TemplateInfoForGeneration {
+        template: None,
+        editor_version: editor,
+        packages: collect_packages(args, defaults)?,
+        selected_files: vec![PathBuf::from("package").join("package.json")],
+        is_empty: true,
+      }
+    }
+  };
+
+  Ok(template_info)
+}
+
+fn generate_project(args: &CliArgs, app_state: &AppState) -> Result<PathBuf, errors::AnyError> {
+  let project_info = ProjectInfoForGeneration {
+    name: args.require("name")?.clone(),
+    path: PathBuf::from(args.require("path")?),
+  };
+  let template_info = template_info_from_args(args, app_state)?;
+
+  generate::generate_project(app_state, &project_info, &template_info)
+}
+
+fn generate_template(args: &CliArgs, app_state: &AppState) -> Result<PathBuf, errors::AnyError> {
+  let name = args.require("name")?.clone();
+  let new_template_info = |template: TemplateInfoForGeneration| NewTemplateInfo {
+    template,
+    name: name.clone(),
+    display_name: args.get("display-name").cloned().unwrap_or(name.clone()),
+    version: args.get("version").cloned().unwrap_or("1.0.0".to_string()),
+    description: args.get("description").cloned().unwrap_or_default(),
+  };
+
+  if let Some(project_path) = args.get("from-project") {
+    let project_path = PathBuf::from(project_path);
+    let editor = find_editor(args.require("editor")?, app_state)?;
+    let selected_files = project::list_project_files(&project_path)
+      .iter()
+      .map(|x| PathBuf::from("package").join(x))
+      .collect::<Vec<_>>();
+
+    let template_info = TemplateInfoForGeneration {
+      template: None,
+      editor_version: editor,
+      packages: Vec::new(),
+      selected_files,
+      is_empty: false,
+    };
+    let project_template_info = ProjectTemplateInfoForGeneration { project_path };
+    return generate::generate_template_from_project(app_state, &project_template_info, &new_template_info(template_info));
+  }
+
+  let template_info = template_info_from_args(args, app_state)?;
+  generate::generate_template(app_state, &new_template_info(template_info))
+}
+
+// output
+
+fn print_json<T: serde::Serialize>(value: &T) {
+  match serde_json::to_string_pretty(value) {
+    Ok(json) => println!("{}", json),
+    Err(err) => eprintln!("{}", err),
+  }
+}
+
+fn print_error(args: &CliArgs, err: &errors::AnyError) -> i32 {
+  if args.json {
+    print_json(&serde_json::json!({ "error": err }));
+  } else {
+    eprintln!("error: {}", err);
+  }
+  1
+}
+
+fn print_projects(args: &CliArgs, projects: &Vec<project::Project>) {
+  if args.json {
+    return print_json(projects);
+  }
+
+  for project in projects {
+    println!("{}\t{}\t{}", project.name, project.version, project.path.display());
+  }
+}
+
+fn print_editors(args: &CliArgs, editors: &Vec<UnityEditorInstall>) {
+  if args.json {
+    return print_json(editors);
+  }
+
+  for editor in editors {
+    println!("{}\t{}", editor.version, editor.exe_path.display());
+    if let Some(warning) = &editor.version_warning {
+      eprintln!("warning: {}", warning);
+    }
+  }
+}
+
+fn print_path(args: &CliArgs, path: &PathBuf) {
+  if args.json {
+    return print_json(&serde_json::json!({ "path": path }));
+  }
+
+  println!("{}", path.display());
+}
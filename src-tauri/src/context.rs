@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use crate::{app::{self, AppState}, editor::UnityEditorInstall, errors, prefs::Prefs, project::Project};
+
+// everything the core hub logic needs from its surroundings.
+// the gui hands over its AppState, tests and tools can bring their own
+pub trait HubContext {
+  fn prefs(&self) -> Result<Prefs, errors::AnyError>;
+  fn editors(&self) -> Result<Vec<UnityEditorInstall>, errors::AnyError>;
+  fn projects(&self) -> Result<Vec<Project>, errors::AnyError>;
+  // where prefs, projects, etc are persisted
+  fn config_dir(&self) -> &Path;
+  // scratch space for extracted templates, package lists, etc
+  fn cache_dir(&self) -> &Path;
+
+  fn find_editor(&self, editor_version: &str) -> Result<UnityEditorInstall, errors::AnyError> {
+    self.editors()?
+      .into_iter()
+      .find(|x| x.version == editor_version)
+      .ok_or(errors::editor_not_found(editor_version))
+  }
+}
+
+impl HubContext for AppState {
+  fn prefs(&self) -> Result<Prefs, errors::AnyError> {
+    Ok(app::get_prefs(self)?)
+  }
+
+  fn editors(&self) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+    Ok(app::get_editors(self)?)
+  }
+
+  fn projects(&self) -> Result<Vec<Project>, errors::AnyError> {
+    Ok(app::get_projects(self)?)
+  }
+
+  fn config_dir(&self) -> &Path {
+    &self.config_dir
+  }
+
+  fn cache_dir(&self) -> &Path {
+    &self.cache_dir
+  }
+}
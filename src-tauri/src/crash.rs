@@ -0,0 +1,195 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor, errors, io_utils,
+  process::EditorSession,
+};
+
+// lines of the log kept in the report itself
+const LOG_TAIL_LINES: usize = 50;
+
+// some filesystems only keep file times to the second or two, a dump written right
+// as the session started can look older than it
+const MODIFIED_TOLERANCE_MILLIS: u128 = 2000;
+
+// what one crashed session left behind, copied into <cache_dir>/crashes/<project name>/<id>/
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+  pub id: u128,
+  pub project_path: PathBuf,
+  pub editor_version: String,
+  pub pid: u32,
+  pub exit_code: Option<i32>,
+  pub started_at: u128,
+  pub crashed_at: u128,
+  pub archive_path: PathBuf,
+  // copied files, relative to archive_path
+  pub files: Vec<PathBuf>,
+  pub log_tail: Vec<String>,
+}
+
+// where to look for a session's logs and crash dumps
+#[derive(Debug, Clone, Default)]
+pub struct CrashSources {
+  pub logs: Vec<PathBuf>,
+  // folders holding one crash folder or file per crash, only ones newer than the session are taken
+  pub crash_dirs: Vec<PathBuf>,
+  // only entries starting with this, for folders other apps report to as well
+  pub crash_prefix: Option<String>,
+}
+
+// unity's own crash handler output
+fn get_crash_dirs() -> Vec<PathBuf> {
+  if cfg!(target_os = "windows") {
+    dirs_next::data_local_dir()
+      .map(|x| x.join("Temp").join("Unity").join("Editor").join("Crashes"))
+      .into_iter()
+      .collect()
+  } else if cfg!(target_os = "macos") {
+    dirs_next::home_dir()
+      .map(|x| x.join("Library").join("Logs").join("DiagnosticReports"))
+      .into_iter()
+      .collect()
+  } else {
+    dirs_next::config_dir()
+      .map(|x| x.join("unity3d").join("Crashes"))
+      .into_iter()
+      .collect()
+  }
+}
+
+// a -logFile replaces Editor.log, otherwise the session wrote Editor.log and moved the last one to Editor-prev.log
+pub fn get_default_sources(session: &EditorSession) -> CrashSources {
+  let logs = match &session.log_path {
+    Some(log_path) => vec![log_path.clone()],
+    None => editor::get_editor_log_dir()
+      .map(|x| vec![x.join("Editor.log"), x.join("Editor-prev.log")])
+      .unwrap_or_default(),
+  };
+
+  CrashSources {
+    logs,
+    crash_dirs: get_crash_dirs(),
+    // DiagnosticReports holds every app's reports, unity's are Unity-<date>.ips and the like
+    crash_prefix: cfg!(target_os = "macos").then(|| "Unity".to_string()),
+  }
+}
+
+fn get_crashes_dir(ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  Ok(io_utils::get_cache_dir(ctx)?.join("crashes"))
+}
+
+fn read_tail(path: &Path) -> Vec<String> {
+  let Ok(bytes) = fs::read(path) else {
+    return vec![];
+  };
+  let contents = String::from_utf8_lossy(&bytes);
+  let lines = contents.lines().collect::<Vec<_>>();
+  lines[lines.len().saturating_sub(LOG_TAIL_LINES)..]
+    .iter()
+    .map(|x| x.to_string())
+    .collect()
+}
+
+// copy whatever the session left behind, missing sources are skipped
+pub fn archive_crash(session: &EditorSession, sources: &CrashSources, ctx: &impl HubContext) -> Result<CrashReport, errors::AnyError> {
+  let crashed_at = session.ended_at.unwrap_or(session.started_at);
+  let project_name = session.project_path
+    .file_name()
+    .map(|x| x.to_string_lossy().to_string())
+    .unwrap_or("Unknown".to_string());
+  let archive_path = get_crashes_dir(ctx)?
+    .join(project_name)
+    .join(crashed_at.to_string());
+  fs::create_dir_all(&archive_path)?;
+
+  let mut files = vec![];
+  for log in sources.logs.iter().filter(|x| x.is_file()) {
+    let name = PathBuf::from(log.file_name().unwrap_or_default());
+    fs::copy(log, archive_path.join(&name))?;
+    files.push(name);
+  }
+
+  let crashes = sources.crash_dirs
+    .iter()
+    .filter_map(|x| fs::read_dir(x).ok())
+    .flat_map(|x| x.filter_map(|y| y.ok()))
+    .filter(|x| sources.crash_prefix
+      .as_ref()
+      .map_or(true, |y| x.file_name().to_string_lossy().starts_with(y.as_str())))
+    .map(|x| x.path())
+    .filter(|x| io_utils::modified_millis(x) + MODIFIED_TOLERANCE_MILLIS >= session.started_at);
+  for crash in crashes {
+    let name = Path::new("Crashes").join(crash.file_name().unwrap_or_default());
+    let to = archive_path.join(&name);
+    if crash.is_dir() {
+      io_utils::copy_dir_all(&crash, &to)?;
+    } else {
+      fs::create_dir_all(archive_path.join("Crashes"))?;
+      fs::copy(&crash, &to)?;
+    }
+    files.push(name);
+  }
+
+  let report = CrashReport {
+    id: crashed_at,
+    project_path: session.project_path.clone(),
+    editor_version: session.editor_version.clone(),
+    pid: session.pid,
+    exit_code: session.exit_code,
+    started_at: session.started_at,
+    crashed_at,
+    log_tail: sources.logs.first().map(|x| read_tail(x)).unwrap_or_default(),
+    archive_path: archive_path.clone(),
+    files,
+  };
+  io_utils::write_atomic(archive_path.join("crash.json"), serde_json::to_string_pretty(&report)?)?;
+
+  Ok(report)
+}
+
+// newest first
+pub fn get_crash_reports(project_path: &Path, ctx: &impl HubContext) -> Result<Vec<CrashReport>, errors::AnyError> {
+  let crashes_dir = get_crashes_dir(ctx)?;
+  let Ok(project_dirs) = fs::read_dir(&crashes_dir) else {
+    return Ok(vec![]);
+  };
+
+  let mut reports = project_dirs
+    .filter_map(|x| x.ok())
+    .filter_map(|x| fs::read_dir(x.path()).ok())
+    .flat_map(|x| x.filter_map(|y| y.ok()))
+    .filter_map(|x| fs::read_to_string(x.path().join("crash.json")).ok())
+    .filter_map(|x| serde_json::from_str::<CrashReport>(&x).ok())
+    .filter(|x| x.project_path == project_path)
+    .collect::<Vec<_>>();
+  reports.sort_by(|a, b| b.crashed_at.cmp(&a.crashed_at));
+  Ok(reports)
+}
+
+pub fn delete_crash_report(project_path: &Path, id: u128, ctx: &impl HubContext) -> Result<(), errors::AnyError> {
+  let report = get_crash_reports(project_path, ctx)?
+    .into_iter()
+    .find(|x| x.id == id)
+    .ok_or_else(|| errors::crash_report_not_found(project_path, id))?;
+  fs::remove_dir_all(report.archive_path)?;
+  Ok(())
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_get_crash_reports(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<Vec<CrashReport>, errors::AnyError> {
+  get_crash_reports(&project_path, app_state.inner())
+}
+
+#[tauri::command]
+pub fn cmd_delete_crash_report(project_path: PathBuf, id: u128, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+  delete_crash_report(&project_path, id, app_state.inner())
+}
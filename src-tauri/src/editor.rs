@@ -1,8 +1,11 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{cmp::Ordering, collections::{HashMap, HashSet}, path::{Path, PathBuf}};
 
 use crate::{
   app::{self, AppState},
+  context::HubContext,
   errors, io_utils,
+  prefs::{Prefs, PrefsKey},
+  version::{self, UnityVersion},
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -10,7 +13,11 @@ use crate::{
 pub struct UnityEditorInstall {
   pub exe_path: PathBuf,
   pub version: String,
+  // changeset hash, e.g. ff3792e53c62
+  pub revision: Option<String>,
   pub modules: Vec<UnityEditorModule>,
+  // set when the folder name says something other than the install, e.g. a folder renamed to 2022.3-lts
+  pub version_warning: Option<String>,
 }
 
 impl Default for UnityEditorInstall {
@@ -18,7 +25,9 @@ impl Default for UnityEditorInstall {
     Self {
       exe_path: PathBuf::new(),
       version: String::new(),
+      revision: None,
       modules: Vec::new(),
+      version_warning: None,
     }
   }
 }
@@ -29,12 +38,12 @@ impl Default for UnityEditorInstall {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UnityEditorModule {
-  name: String,
-  id: String,
-  description: String,
-  category: String,
-  visible: bool,
-  selected: bool,
+  pub name: String,
+  pub id: String,
+  pub description: String,
+  pub category: String,
+  pub visible: bool,
+  pub selected: bool,
 }
 
 impl Default for UnityEditorModule {
@@ -163,173 +172,609 @@ pub fn load_modules(editor_path: impl Into<PathBuf>) -> anyhow::Result<Vec<Unity
   Ok(modules)
 }
 
+// the <string> that follows <key>{key}</key> in an Info.plist
+fn read_plist_string(plist_path: &Path, key: &str) -> Option<String> {
+  let contents = std::fs::read_to_string(plist_path).ok()?;
+  let (_, after_key) = contents.split_once(&format!("<key>{}</key>", key))?;
+  let (_, after_tag) = after_key.split_once("<string>")?;
+  let (value, _) = after_tag.split_once("</string>")?;
+  Some(value.trim().to_string()).filter(|x| !x.is_empty())
+}
+
+// Unity.app/Contents/Info.plist, CFBundleVersion holds the full version e.g. 2022.3.10f1
+fn read_plist_version(plist_path: &Path) -> Option<String> {
+  read_plist_string(plist_path, "CFBundleVersion")
+}
+
+// every playback engine ships an ivy.xml stamped with the editor it belongs to
+// <info version="..." e:unityVersion="2022.3.10f1" />
+fn read_ivy_version(data_path: &Path) -> Option<String> {
+  let engines = std::fs::read_dir(data_path.join("PlaybackEngines")).ok()?;
+  engines
+    .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path().join("ivy.xml")).ok())
+    .find_map(|contents| {
+      let (_, after_attr) = contents.split_once("unityVersion=\"")?;
+      let (version, _) = after_attr.split_once('"')?;
+      Some(version.trim().to_string()).filter(|x| !x.is_empty())
+    })
+}
+
+// the version the install says it is, None when this isn't an editor install
+pub fn read_install_version(exe_path: &Path) -> Option<String> {
+  if cfg!(target_os = "windows") {
+    let data_path = exe_path.parent()?.join("Data");
+    read_ivy_version(&data_path)
+  } else if cfg!(target_family = "unix") {
+    let contents_path = exe_path.join("Contents");
+    read_plist_version(&contents_path.join("Info.plist"))
+      .or_else(|| read_ivy_version(&contents_path))
+  } else {
+    None
+  }
+}
+
+// the changeset written to m_EditorVersionWithRevision, only the Info.plist has it
+// CFBundleGetInfoString: Unity version 2022.3.10f1 (ff3792e53c62)
+pub fn read_install_revision(exe_path: &Path) -> Option<String> {
+  if !cfg!(target_family = "unix") {
+    return None;
+  }
+  
+  let info = read_plist_string(&exe_path.join("Contents").join("Info.plist"), "CFBundleGetInfoString")?;
+  let (_, after_paren) = info.rsplit_once('(')?;
+  let (revision, _) = after_paren.split_once(')')?;
+  Some(revision.trim().to_string()).filter(|x| !x.is_empty())
+}
+
+fn get_exe_path(install_path: &Path) -> Option<PathBuf> {
+  if cfg!(target_os = "windows") {
+    Some(install_path.join("Editor").join("Unity").with_extension("exe"))
+  } else if cfg!(target_family = "unix") {
+    Some(install_path.join("Unity").with_extension("app"))
+  } else {
+    None
+  }
+}
+
+// <root>/<version>/Unity.app or <root>/<version>/Editor/Unity.exe
+pub fn read_editor_folder(path: &Path) -> Option<UnityEditorInstall> {
+  if !path.is_dir() {
+    return None;
+  }
+  
+  if path
+    .file_name()
+    .is_some_and(|x| x.to_str().is_some_and(|y| y.starts_with(".")))
+  {
+    return None;
+  }
+  
+  let folder_name = path.file_name()?.to_str()?.to_string();
+  // folders without an editor in them are leftovers or something else entirely
+  let exe_path = get_exe_path(path).filter(|x| x.exists())?;
+  
+  // the folder name is only a hint, people rename them. it's all there is when the
+  // version files are missing or in a format we don't know
+  let (version, version_warning) = match read_install_version(&exe_path) {
+    Some(version) => {
+      let warning = (folder_name != version).then(|| format!(
+        "Folder {} contains editor version {}",
+        folder_name, version,
+      ));
+      (version, warning)
+    }
+    None => {
+      let warning = format!("Could not verify the editor version in {}", folder_name);
+      (folder_name, Some(warning))
+    }
+  };
+  
+  let revision = read_install_revision(&exe_path);
+  
+  // standalone installs don't come with a modules.json
+  let modules = load_modules(&exe_path).unwrap_or_default();
+  
+  Some(UnityEditorInstall {
+    exe_path,
+    version,
+    revision,
+    modules,
+    version_warning,
+  })
+}
+
+// a manually located editor can point anywhere inside the install, so walk up until
+// a folder has a readable editor version. its folder name means nothing there
+pub fn read_manual_editor(path: &Path) -> Option<UnityEditorInstall> {
+  path
+    .ancestors()
+    .filter(|x| get_exe_path(x).is_some_and(|y| read_install_version(&y).is_some()))
+    .find_map(read_editor_folder)
+    .map(|x| UnityEditorInstall { version_warning: None, ..x })
+}
+
+// every folder that holds <version> folders, hub_editors_path first when it's set
+pub fn get_search_roots(prefs: &Prefs) -> Vec<PathBuf> {
+  let mut seen = HashSet::new();
+  prefs.hub_editors_path
+    .iter()
+    .chain(prefs.editor_search_paths.iter())
+    .filter(|x| seen.insert(*x))
+    .cloned()
+    .collect()
+}
+
 pub fn find_editor_installs(
-  app_state: &tauri::State<AppState>,
+  ctx: &impl HubContext,
 ) -> anyhow::Result<Vec<UnityEditorInstall>, errors::AnyError> {
-  let prefs = app::get_prefs(app_state)?;
-  let hub_editors_path = prefs
-    .hub_editors_path
-    .ok_or(errors::str_error("hub_editors_path not set"))?;
+  let prefs = ctx.prefs()?;
+  let mut editors: Vec<UnityEditorInstall> = Vec::new();
   
-  if !hub_editors_path.exists() {
-    return Err(errors::io_not_found("Invalid hub_editors_path"));
+  // any root may be unset or on a drive that isn't mounted right now, just skip it.
+  // editors elsewhere and manually located ones are still found
+  for root in get_search_roots(&prefs) {
+    let Ok(editor_folders) = std::fs::read_dir(&root) else {
+      continue;
+    };
+    editors.extend(editor_folders
+      .filter_map(|entry| read_editor_folder(&entry.ok()?.path())));
   }
   
-  let editor_folders = std::fs::read_dir(hub_editors_path)?;
-  let mut editors = editor_folders
-    .filter_map(|entry| {
-      let entry = entry.ok()?;
-      let path = entry.path();
-      
-      if path
-        .file_name()
-        .is_some_and(|x| x.to_str().is_some_and(|y| y.starts_with(".")))
-      {
-        return None;
-      }
-      
-      let version_name = &path.file_name()?.to_str()?.to_string();
-      
-      let exe_path = {
-        if cfg!(target_os = "windows") {
-          Some(path.join("Editor").join("Unity").with_extension("exe"))
-        } else if cfg!(target_family = "unix") {
-          Some(path.join("Unity").with_extension("app"))
-        } else {
-          None
-        }
-      }?
-    .to_str()?
-    .to_string();
-    
-    let modules = load_modules(&exe_path).ok()?;
-    
-    let editor = UnityEditorInstall {
-      exe_path: PathBuf::from(exe_path),
-      version: version_name.clone(),
-      modules,
+  editors.extend(prefs.manual_editor_paths
+    .iter()
+    .filter_map(|path| read_manual_editor(path)));
+  
+  // the same install can be reached from more than one root, or through a symlink
+  let mut seen = HashSet::new();
+  editors.retain(|x| seen.insert(std::fs::canonicalize(&x.exe_path).unwrap_or(x.exe_path.clone())));
+  
+  // newest first
+  editors.sort_by(|a, b| version::compare(&a.version, &b.version));
+  editors.reverse();
+  
+  Ok(editors)
+}
+
+// rescan the editors folder and store the results in the app state
+pub fn refresh_editor_installs(
+  app_state: &AppState,
+) -> anyhow::Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  let editors = find_editor_installs(app_state)?;
+  let mut stored_editors = app_state
+    .editors
+    .lock()
+    .map_err(|_| errors::state_locked("editors"))?;
+  *stored_editors = editors.clone();
+  Ok(editors)
+}
+
+fn update_prefs(app_state: &AppState, update: impl FnOnce(&mut Prefs)) -> Result<(), errors::AnyError> {
+  let mut prefs = app_state.prefs.lock()
+    .map_err(|_| errors::state_locked("prefs"))?;
+  update(&mut prefs);
+  app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
+  Ok(())
+}
+
+// add a folder holding <version> folders, e.g. the hub's install folder on a second drive
+pub fn add_search_path(path: PathBuf, app_state: &AppState) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  if !path.is_dir() {
+    return Err(errors::pref_invalid(PrefsKey::EditorSearchPaths, &path));
+  }
+  
+  update_prefs(app_state, |prefs| {
+    if !get_search_roots(prefs).contains(&path) {
+      prefs.editor_search_paths.push(path);
+    }
+  })?;
+  refresh_editor_installs(app_state)
+}
+
+pub fn remove_search_path(path: PathBuf, app_state: &AppState) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  update_prefs(app_state, |prefs| prefs.editor_search_paths.retain(|x| *x != path))?;
+  refresh_editor_installs(app_state)
+}
+
+// register an editor that lives outside of every search path, the path can be the
+// executable or any folder inside the install
+pub fn add_manual_editor(path: PathBuf, app_state: &AppState) -> Result<UnityEditorInstall, errors::AnyError> {
+  let editor = read_manual_editor(&path).ok_or(errors::editor_invalid(&path))?;
+  
+  update_prefs(app_state, |prefs| {
+    if !prefs.manual_editor_paths.contains(&path) {
+      prefs.manual_editor_paths.push(path);
+    }
+  })?;
+  refresh_editor_installs(app_state)?;
+  Ok(editor)
+}
+
+pub fn remove_manual_editor(path: PathBuf, app_state: &AppState) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  update_prefs(app_state, |prefs| prefs.manual_editor_paths.retain(|x| *x != path))?;
+  refresh_editor_installs(app_state)
+}
+
+// version resolution
+
+// how far an installed editor is from the version that was asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum VersionChange {
+  Exact,
+  // same major.minor stream
+  PatchUpgrade,
+  PatchDowngrade,
+  // across streams, unity will reimport and may not go back
+  Upgrade,
+  Downgrade,
+}
+
+impl VersionChange {
+  pub fn between(from: &str, to: &str) -> Self {
+    let same_stream = match (from.parse::<UnityVersion>(), to.parse::<UnityVersion>()) {
+      (Ok(from), Ok(to)) => from.same_minor(&to),
+      _ => false,
     };
     
-    if path.is_dir() {
-      Some(editor)
-    } else {
-      None
+    match (version::compare(from, to), same_stream) {
+      (Ordering::Equal, _) => VersionChange::Exact,
+      (Ordering::Less, true) => VersionChange::PatchUpgrade,
+      (Ordering::Greater, true) => VersionChange::PatchDowngrade,
+      (Ordering::Less, false) => VersionChange::Upgrade,
+      (Ordering::Greater, false) => VersionChange::Downgrade,
     }
-  })
-  .collect::<Vec<_>>();
+  }
   
-  // sort by semver
-  editors.sort_by(|a, b| {
-    let split_a = a
-      .version
-    .split(|c| c == '.' || c == 'f')
+  pub fn warning(&self, from: &str, to: &str) -> Option<String> {
+    match self {
+      VersionChange::Exact => None,
+      VersionChange::PatchUpgrade => Some(format!("{} is not installed, using newer patch {}", from, to)),
+      VersionChange::PatchDowngrade => Some(format!("{} is not installed, using older patch {}", from, to)),
+      VersionChange::Upgrade => Some(format!("Project will be upgraded from {} to {}", from, to)),
+      VersionChange::Downgrade => Some(format!("Project will be downgraded from {} to {}, this can lose data", from, to)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorCandidate {
+  pub version: String,
+  pub exe_path: PathBuf,
+  pub change: VersionChange,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorResolution {
+  pub requested: String,
+  pub editor: UnityEditorInstall,
+  pub change: VersionChange,
+  pub warning: Option<String>,
+}
+
+// every installed editor relative to `requested`, closest first.
+// newer patches beat older ones since unity upgrades within a stream cleanly
+pub fn get_candidates(requested: &str, editors: &[UnityEditorInstall]) -> Vec<EditorCandidate> {
+  let rank = |change: VersionChange| match change {
+    VersionChange::Exact => 0,
+    VersionChange::PatchUpgrade => 1,
+    VersionChange::PatchDowngrade => 2,
+    VersionChange::Upgrade => 3,
+    VersionChange::Downgrade => 4,
+  };
+  
+  let mut candidates = editors
+    .iter()
+    .map(|x| EditorCandidate {
+      version: x.version.clone(),
+      exe_path: x.exe_path.clone(),
+      change: VersionChange::between(requested, &x.version),
+    })
     .collect::<Vec<_>>();
-    let split_b = b
-      .version
-      .split(|c| c == '.' || c == 'f')
-      .collect::<Vec<_>>();
-    
-    let nums_a = split_a
-      .iter()
-      .map(|s| s.parse::<u32>().unwrap_or(0))
-      .collect::<Vec<_>>();
-    let nums_b = split_b
-      .iter()
-      .map(|s| s.parse::<u32>().unwrap_or(0))
-      .collect::<Vec<_>>();
-    
-    (nums_a[0].cmp(&nums_b[0]))
-      .then_with(|| nums_a[1].cmp(&nums_b[1]))
-      .then_with(|| nums_a[2].cmp(&nums_b[2]))
-      .then_with(|| nums_a[3].cmp(&nums_b[3]))
+  
+  candidates.sort_by(|a, b| {
+    rank(a.change).cmp(&rank(b.change)).then_with(|| match a.change {
+      // nearest first in both directions
+      VersionChange::PatchDowngrade | VersionChange::Downgrade => version::compare(&b.version, &a.version),
+      _ => version::compare(&a.version, &b.version),
+    })
   });
-  editors.reverse();
+  candidates
+}
+
+// the exact version when installed, otherwise the closest editor in the same stream.
+// anything further away is the user's call, so it fails listing what is installed
+pub fn resolve_editor(requested: &str, ctx: &impl HubContext) -> Result<EditorResolution, errors::AnyError> {
+  let editors = ctx.editors()?;
+  let candidates = get_candidates(requested, &editors);
   
-  Ok(editors)
+  let picked = candidates
+    .first()
+    .filter(|x| matches!(x.change, VersionChange::Exact | VersionChange::PatchUpgrade | VersionChange::PatchDowngrade))
+    .and_then(|x| editors.iter().find(|y| y.exe_path == x.exe_path));
+  
+  let Some(editor) = picked else {
+    return Err(errors::editor_not_found_with_candidates(requested, candidates));
+  };
+  
+  let change = VersionChange::between(requested, &editor.version);
+  Ok(EditorResolution {
+    requested: requested.to_string(),
+    editor: editor.clone(),
+    change,
+    warning: change.warning(requested, &editor.version),
+  })
 }
 
 pub fn open(
   editor_version: String,
   arguments: Vec<String>,
-  app_state: &tauri::State<AppState>,
+  ctx: &impl HubContext,
   wait: bool,
 ) -> anyhow::Result<(), errors::AnyError> {
-  let editor = app_state
-    .editors
-    .lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?
-    .iter()
-    .find(|x| x.version == editor_version)
-    .ok_or(errors::str_error("Invalid editor version"))?
-    .clone();
-  
-  let exe_path = get_real_exe_path(&editor)?;
+  open_with_env(editor_version, arguments, &HashMap::new(), ctx, wait)
+}
 
-  println!("Opening editor: {}", exe_path.display());
-  println!("Arguments: {:?}", arguments);
+// same as open, with extra environment variables for the editor process
+pub fn open_with_env(
+  editor_version: String,
+  arguments: Vec<String>,
+  env: &HashMap<String, String>,
+  ctx: &impl HubContext,
+  wait: bool,
+) -> anyhow::Result<(), errors::AnyError> {
+  let mut child = spawn(editor_version, arguments, env, ctx)?;
   
   if wait {
-    std::process::Command::new(&exe_path)
-      .args(arguments)
-      .output()?;
-  } else {
-    std::process::Command::new(&exe_path)
-      .args(arguments)
-      .spawn()?;
+    child.wait()?;
   }
   
   Ok(())
 }
 
-pub fn estimate_size(
-  editor: &UnityEditorInstall,
-  app: &tauri::AppHandle,
-) -> anyhow::Result<u64, errors::AnyError> {
-  let tmp_json_path = io_utils::get_cache_dir(&app)?
-    .join("editors")
-    .with_extension("json");
+// start the editor and hand back the process, for callers that watch it
+pub fn spawn(
+  editor_version: String,
+  arguments: Vec<String>,
+  env: &HashMap<String, String>,
+  ctx: &impl HubContext,
+) -> anyhow::Result<std::process::Child, errors::AnyError> {
+  let editor = ctx.find_editor(&editor_version)?;
   
-  if !tmp_json_path.exists() {
-    // write a new json to disk
-    let map: HashMap<String, serde_json::Value> = HashMap::new();
-    let json_str = serde_json::to_string_pretty(&map)?;
-    std::fs::write(&tmp_json_path, json_str)?;
-  }
+  let exe_path = get_real_exe_path(&editor)?;
+
+  println!("Opening editor: {}", exe_path.display());
+  println!("Arguments: {:?}", arguments);
   
-  let map = std::fs::read_to_string(&tmp_json_path)?;
-  let mut map = serde_json::from_str::<HashMap<String, serde_json::Value>>(&map)?;
+  let child = std::process::Command::new(&exe_path)
+    .args(arguments)
+    .envs(env)
+    .spawn()?;
   
+  Ok(child)
+}
+
+// where unity writes Editor.log when no -logFile is given
+pub fn get_editor_log_dir() -> Option<PathBuf> {
+  if cfg!(target_os = "windows") {
+    dirs_next::data_local_dir().map(|x| x.join("Unity").join("Editor"))
+  } else if cfg!(target_os = "macos") {
+    dirs_next::home_dir().map(|x| x.join("Library").join("Logs").join("Unity"))
+  } else {
+    dirs_next::config_dir().map(|x| x.join("unity3d"))
+  }
+}
+
+// folders pulled out of their top-level folder and sized on their own
+const MODULE_FOLDERS: [&str; 2] = ["MonoBleedingEdge", "Documentation"];
+// every folder inside is a platform module
+const PLAYBACK_ENGINES_FOLDER: &str = "PlaybackEngines";
+// deep enough for <version>/Editor/Data/PlaybackEngines and Unity.app/Contents/MonoBleedingEdge
+const MODULE_SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SizeSectionKind {
+  Folder,
+  Module,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeSection {
+  // relative to the install root
+  pub path: PathBuf,
+  pub kind: SizeSectionKind,
+  // folders don't include the modules inside them
+  pub size: u64,
+  // newest mtime inside the folder when it was measured
+  pub modified: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorSize {
+  pub total: u64,
+  pub sections: Vec<SizeSection>,
+  // newest mtime of the files directly in the root, e.g. modules.json
+  #[serde(default)]
+  pub modified: u128,
+  pub measured_at: u128,
+}
+
+// top-level folders of the install, plus the module folders found inside them
+fn find_size_sections(root: &Path) -> Vec<(PathBuf, SizeSectionKind)> {
+  let mut modules = vec![];
+  let mut walk = walkdir::WalkDir::new(root)
+    .min_depth(1)
+    .max_depth(MODULE_SEARCH_DEPTH)
+    .into_iter()
+    .filter_entry(|x| x.file_type().is_dir());
+  while let Some(entry) = walk.next() {
+    let Ok(entry) = entry else {
+      continue;
+    };
+    let name = entry.file_name().to_string_lossy();
+    if MODULE_FOLDERS.contains(&name.as_ref()) {
+      modules.push(entry.into_path());
+      walk.skip_current_dir();
+    } else if name == PLAYBACK_ENGINES_FOLDER {
+      let engines = std::fs::read_dir(entry.path())
+        .into_iter()
+        .flat_map(|x| x.filter_map(|y| y.ok()))
+        .filter(|x| x.path().is_dir())
+        .map(|x| x.path());
+      modules.extend(engines);
+      walk.skip_current_dir();
+    }
+  }
+
+  let folders = std::fs::read_dir(root)
+    .into_iter()
+    .flat_map(|x| x.filter_map(|y| y.ok()))
+    .map(|x| x.path())
+    .filter(|x| x.is_dir() && !modules.contains(x))
+    .map(|x| (x, SizeSectionKind::Folder))
+    .collect::<Vec<_>>();
+
+  folders
+    .into_iter()
+    .chain(modules.into_iter().map(|x| (x, SizeSectionKind::Module)))
+    .collect()
+}
+
+// runs f for every item on its own thread
+fn on_threads<T: Send, R: Send>(items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Result<Vec<R>, errors::AnyError> {
+  std::thread::scope(|scope| {
+    let handles = items
+      .into_iter()
+      .map(|x| scope.spawn(|| f(x)))
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .map(|x| x.join().map_err(|_| errors::str_error("Measuring the editor size failed")))
+      .collect()
+  })
+}
+
+// measured again as a whole when anything inside changed, so hardlinks shared between
+// sections are still counted once. files directly in the root, e.g. modules.json, only
+// count towards the total
+pub fn measure_size(root: &Path, previous: Option<&EditorSize>) -> Result<EditorSize, errors::AnyError> {
+  let found = find_size_sections(root);
+  let module_paths = found
+    .iter()
+    .filter(|x| x.1 == SizeSectionKind::Module)
+    .map(|x| x.0.clone())
+    .collect::<Vec<_>>();
+  let section_paths = found
+    .iter()
+    .map(|x| x.0.clone())
+    .collect::<Vec<_>>();
+
+  // folders are keyed without the modules inside them, those have their own
+  let keyed = on_threads(found, |(path, kind)| {
+    let exclude = module_paths
+      .iter()
+      .filter(|x| **x != path && x.starts_with(&path))
+      .cloned()
+      .collect::<Vec<_>>();
+    let modified = io_utils::tree_modified_millis(&path, &exclude);
+    (path, kind, exclude, modified)
+  })?;
+  let root_modified = io_utils::tree_modified_millis(root, &section_paths);
+
+  let unchanged = previous.filter(|x| {
+    x.modified == root_modified
+      && x.sections.len() == keyed.len()
+      && keyed.iter().all(|(path, kind, _, modified)| {
+        let relative = io_utils::diff_paths(path, root).unwrap_or(path.clone());
+        x.sections.iter().any(|y| y.path == relative && y.kind == *kind && y.modified == *modified)
+      })
+  });
+  if let Some(previous) = unchanged {
+    return Ok(previous.clone());
+  }
+
+  let seen = io_utils::SeenLinks::default();
+  let sections = on_threads(keyed, |(path, kind, exclude, modified)| SizeSection {
+    size: io_utils::dir_size_on_disk(&path, &exclude, &seen),
+    path: io_utils::diff_paths(&path, root).unwrap_or(path.clone()),
+    kind,
+    modified,
+  })?;
+  let root_files = io_utils::dir_size_on_disk(root, &section_paths, &seen);
+
+  Ok(EditorSize {
+    total: root_files + sections.iter().map(|x| x.size).sum::<u64>(),
+    sections,
+    modified: root_modified,
+    measured_at: io_utils::now_millis(),
+  })
+}
+
+// <cache_dir>/editors.json, sizes keyed by exe path
+fn get_size_cache_path(ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  Ok(io_utils::get_cache_dir(ctx)?
+    .join("editors")
+    .with_extension("json"))
+}
+
+// entries that don't parse, e.g. the single numbers older versions stored, are measured again
+fn load_size_cache(path: &Path) -> HashMap<String, serde_json::Value> {
+  std::fs::read_to_string(path)
+    .ok()
+    .and_then(|x| serde_json::from_str(&x).ok())
+    .unwrap_or_default()
+}
+
+pub fn get_size_breakdown(
+  editor: &UnityEditorInstall,
+  ctx: &impl HubContext,
+) -> Result<EditorSize, errors::AnyError> {
+  let cache_path = get_size_cache_path(ctx)?;
+  let mut cache = load_size_cache(&cache_path);
+
   let exe_path = editor
-  .exe_path
-    .clone()
+    .exe_path
     .to_str()
     .ok_or(errors::str_error("Invalid editor path"))?
     .to_string();
-  
-  if map.contains_key(&exe_path) {
-    let disk_size = { map.get(&exe_path).and_then(|x| x.as_u64()) };
-    
-    if let Some(disk_size) = disk_size {
-      return Ok(disk_size);
-    }
-  }
-  
+  let previous = cache
+    .get(&exe_path)
+    .and_then(|x| serde_json::from_value::<EditorSize>(x.clone()).ok());
+
   let root_path = crate::editor::get_root_folder(&editor.exe_path)
     .ok_or(errors::str_error("Invalid editor root path"))?;
-  let disk_size = io_utils::dir_size(root_path).unwrap_or(0u64);
-  map.insert(exe_path, serde_json::Value::from(disk_size));
-  std::fs::write(&tmp_json_path, serde_json::to_string_pretty(&map)?)?;
-  
-  Ok(disk_size)
+  let size = measure_size(&root_path, previous.as_ref())?;
+
+  let changed = previous.map_or(true, |x| x != size);
+  if changed {
+    cache.insert(exe_path, serde_json::to_value(&size)?);
+    io_utils::write_atomic(&cache_path, serde_json::to_string_pretty(&cache)?)?;
+  }
+
+  Ok(size)
 }
 
-pub fn read_package_manager_manifest(editor_version: String, app_state: &tauri::State<AppState>) -> Result<EditorPackageManagerManifest, errors::AnyError> {
-  let editor = app_state.editors.lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?
-    .iter()
-    .find(|x| x.version == editor_version)
-    .ok_or(errors::str_error("Invalid editor version"))?
-    .clone();
+// forget the measurements of an install, e.g. once it's gone
+pub fn remove_size_cache(exe_path: &Path, ctx: &impl HubContext) -> Result<(), errors::AnyError> {
+  let cache_path = get_size_cache_path(ctx)?;
+  let mut cache = load_size_cache(&cache_path);
+  if cache.remove(exe_path.to_string_lossy().as_ref()).is_some() {
+    io_utils::write_atomic(&cache_path, serde_json::to_string_pretty(&cache)?)?;
+  }
+  Ok(())
+}
+
+pub fn estimate_size(
+  editor: &UnityEditorInstall,
+  ctx: &impl HubContext,
+) -> anyhow::Result<u64, errors::AnyError> {
+  Ok(get_size_breakdown(editor, ctx)?.total)
+}
+
+pub fn read_package_manager_manifest(editor_version: String, ctx: &impl HubContext) -> Result<EditorPackageManagerManifest, errors::AnyError> {
+  let editor = ctx.find_editor(&editor_version)?;
   let root_dir = crate::editor::get_package_manager_folder(&editor)?;
   let manifest_path = root_dir
     .join("Editor")
@@ -339,7 +784,7 @@ pub fn read_package_manager_manifest(editor_version: String, app_state: &tauri::
   let json = std::fs::read_to_string(&manifest_path)
     .map_err(|_| errors::io_not_found("Invalid package manager manifest"))?;
   let manifest: EditorPackageManagerManifest = serde_json::from_str(&json)
-    .map_err(|_| errors::str_error("Invalid package manager manifest"))?;
+    .map_err(|_| errors::manifest_corrupt(&manifest_path))?;
   Ok(manifest)
 }
 
@@ -349,13 +794,7 @@ pub fn read_package_manager_manifest(editor_version: String, app_state: &tauri::
 pub async fn cmd_get_editors(
   app_state: tauri::State<'_, AppState>,
 ) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
-  let mut stored_editors = app_state
-    .editors
-    .lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?;
-  let editors = find_editor_installs(&app_state)?;
-  *stored_editors = editors.clone();
-  Ok(editors)
+  refresh_editor_installs(&app_state)
 }
 
 #[tauri::command]
@@ -363,10 +802,10 @@ pub fn cmd_open_unity_hub(app_state: tauri::State<AppState>) -> Result<(), error
   let prefs = app::get_prefs(&app_state)?;
   let unity_hub_path = prefs
   .hub_path
-    .ok_or(errors::str_error("unity_hub_path not set"))?;
+    .ok_or(errors::pref_missing(PrefsKey::HubPath))?;
   
   if !unity_hub_path.exists() {
-    return Err(errors::io_not_found("Invalid unity_hub_path"));
+    return Err(errors::pref_invalid(PrefsKey::HubPath, &unity_hub_path));
   }
   
   std::process::Command::new(&unity_hub_path).spawn()?;
@@ -377,17 +816,57 @@ pub fn cmd_open_unity_hub(app_state: tauri::State<AppState>) -> Result<(), error
 #[tauri::command]
 pub async fn cmd_estimate_editor_size(
   editor_version: String,
-  app_handle: tauri::AppHandle,
   app_state: tauri::State<'_, AppState>,
 ) -> Result<u64, errors::AnyError> {
-  let editors = app_state
-  .editors
-    .lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?;
-  let editor = editors
-    .iter()
-    .find(|x| x.version == editor_version)
-    .ok_or(errors::str_error("Invalid editor version"))?;
-  
-  estimate_size(editor, &app_handle)
+  let editor = app_state.find_editor(&editor_version)?;
+  estimate_size(&editor, app_state.inner())
+}
+
+#[tauri::command]
+pub async fn cmd_get_editor_size_breakdown(
+  editor_version: String,
+  app_state: tauri::State<'_, AppState>,
+) -> Result<EditorSize, errors::AnyError> {
+  let editor = app_state.find_editor(&editor_version)?;
+  get_size_breakdown(&editor, app_state.inner())
+}
+
+#[tauri::command]
+pub fn cmd_add_editor_search_path(
+  path: PathBuf,
+  app_state: tauri::State<AppState>,
+) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  add_search_path(path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_remove_editor_search_path(
+  path: PathBuf,
+  app_state: tauri::State<AppState>,
+) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  remove_search_path(path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_add_manual_editor(
+  path: PathBuf,
+  app_state: tauri::State<AppState>,
+) -> Result<UnityEditorInstall, errors::AnyError> {
+  add_manual_editor(path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_remove_manual_editor(
+  path: PathBuf,
+  app_state: tauri::State<AppState>,
+) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+  remove_manual_editor(path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_resolve_editor(
+  editor_version: String,
+  app_state: tauri::State<AppState>,
+) -> Result<EditorResolution, errors::AnyError> {
+  resolve_editor(&editor_version, app_state.inner())
 }
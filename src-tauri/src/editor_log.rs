@@ -0,0 +1,307 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use tauri::Manager;
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor, errors, io_utils, launch,
+};
+
+// event name the frontend gets new lines on
+pub const EDITOR_LOG_EVENT: &str = "editor-log";
+
+const TAIL_INTERVAL: Duration = Duration::from_millis(500);
+// every watcher is a thread polling its file, a view that forgets to unwatch shouldn't pile them up
+pub const MAX_WATCHERS: usize = 8;
+
+// one stop flag per watched log, so the same log isn't tailed twice
+static WATCHERS: Mutex<Option<HashMap<PathBuf, Arc<AtomicBool>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum LogLineKind {
+  Info,
+  CompileError,
+  Warning,
+  Exception,
+  PackageError,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+  // 1 based, counted from where reading started
+  pub number: usize,
+  pub text: String,
+  pub kind: LogLineKind,
+  pub file: Option<PathBuf>,
+  pub line: Option<u32>,
+  pub column: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSource {
+  pub name: String,
+  pub path: PathBuf,
+  pub exists: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEvent {
+  pub path: PathBuf,
+  pub lines: Vec<LogLine>,
+}
+
+// Assets/Player.cs(12,5): error CS0103: ...
+fn parse_compiler_location(text: &str) -> Option<(PathBuf, u32, Option<u32>)> {
+  let (location, _) = text.split_once("): ")?;
+  let (file, position) = location.rsplit_once('(')?;
+  let mut position = position.split(',');
+  let line = position.next()?.trim().parse().ok()?;
+  let column = position.next().and_then(|x| x.trim().parse().ok());
+  Some((PathBuf::from(file.trim()), line, column))
+}
+
+// Player.Update () (at Assets/Player.cs:42)
+fn parse_stack_location(text: &str) -> Option<(PathBuf, u32)> {
+  let (_, location) = text.rsplit_once("(at ")?;
+  let location = location.strip_suffix(')')?;
+  let (file, line) = location.rsplit_once(':')?;
+  Some((PathBuf::from(file), line.parse().ok()?))
+}
+
+// NullReferenceException: ..., UnityEngine.MissingReferenceException: ...
+fn is_exception_header(text: &str) -> bool {
+  text.split_once(':')
+    .map_or(false, |(name, _)| name.ends_with("Exception") && !name.contains(' '))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Block {
+  #[default]
+  None,
+  Exception,
+  Package,
+}
+
+// exceptions and package errors span several lines, so the classifier remembers which block it's in
+#[derive(Debug, Default)]
+pub struct LogClassifier {
+  block: Block,
+  number: usize,
+}
+
+impl LogClassifier {
+  pub fn classify(&mut self, text: String) -> LogLine {
+    self.number += 1;
+    let mut line = LogLine {
+      number: self.number,
+      text,
+      kind: LogLineKind::Info,
+      file: None,
+      line: None,
+      column: None,
+    };
+    let text = line.text.trim_end();
+
+    if text.trim().is_empty() {
+      self.block = Block::None;
+      return line;
+    }
+
+    if let Some((file, number, column)) = parse_compiler_location(text) {
+      if text.contains("): error ") {
+        line.kind = LogLineKind::CompileError;
+      } else if text.contains("): warning ") {
+        line.kind = LogLineKind::Warning;
+      }
+      if line.kind != LogLineKind::Info {
+        self.block = Block::None;
+        line.file = Some(file);
+        line.line = Some(number);
+        line.column = column;
+        return line;
+      }
+    }
+
+    if is_exception_header(text) {
+      self.block = Block::Exception;
+      line.kind = LogLineKind::Exception;
+      return line;
+    }
+
+    let lower = text.to_lowercase();
+    if lower.contains("error occurred while resolving packages")
+      || (text.starts_with("[Package Manager]") && (lower.contains("error") || lower.contains("failed"))) {
+      self.block = Block::Package;
+      line.kind = LogLineKind::PackageError;
+      return line;
+    }
+
+    match self.block {
+      // frames keep going until a blank line
+      Block::Exception if !text.starts_with('[') => {
+        line.kind = LogLineKind::Exception;
+        if let Some((file, number)) = parse_stack_location(text) {
+          line.file = Some(file);
+          line.line = Some(number);
+        }
+        return line;
+      }
+      // the details are indented under the error
+      Block::Package if text.starts_with(' ') || text.starts_with('\t') => {
+        line.kind = LogLineKind::PackageError;
+        return line;
+      }
+      _ => self.block = Block::None,
+    }
+
+    if lower.starts_with("warning") || text.starts_with("[Warning]") {
+      line.kind = LogLineKind::Warning;
+    }
+    line
+  }
+}
+
+pub fn classify_lines(lines: impl IntoIterator<Item = String>) -> Vec<LogLine> {
+  let mut classifier = LogClassifier::default();
+  lines.into_iter().map(|x| classifier.classify(x)).collect()
+}
+
+// the whole log, optionally only some kinds of lines
+pub fn read_log(path: &Path, kinds: Option<&[LogLineKind]>) -> Result<Vec<LogLine>, errors::AnyError> {
+  if !path.is_file() {
+    return Err(errors::io_not_found("Log file not found"));
+  }
+
+  let mut tail = io_utils::FileTail::new(path);
+  let mut lines = tail.read_lines();
+  lines.extend(tail.take_partial());
+
+  Ok(classify_lines(lines)
+    .into_iter()
+    .filter(|x| kinds.map_or(true, |y| y.contains(&x.kind)))
+    .collect())
+}
+
+// Editor.log and Editor-prev.log, then logs the project's launch profiles redirect to
+pub fn get_log_sources(project_path: Option<&Path>, app_state: &AppState) -> Result<Vec<LogSource>, errors::AnyError> {
+  let source = |name: String, path: PathBuf| LogSource {
+    exists: path.is_file(),
+    name,
+    path,
+  };
+
+  let mut sources = vec![];
+  if let Some(log_dir) = editor::get_editor_log_dir() {
+    sources.push(source("Editor.log".to_string(), log_dir.join("Editor.log")));
+    sources.push(source("Editor-prev.log".to_string(), log_dir.join("Editor-prev.log")));
+  }
+
+  if let Some(project_path) = project_path {
+    let prefs = app_state.prefs()?;
+    for profile in launch::get_profiles_for_project(&prefs, project_path) {
+      let Some(log_path) = launch::get_log_file(project_path, &profile.arguments) else {
+        continue;
+      };
+      if !sources.iter().any(|x| x.path == log_path) {
+        sources.push(source(profile.name, log_path));
+      }
+    }
+  }
+
+  Ok(sources)
+}
+
+// keep reading new lines until `stop` is set, classification carries over between reads
+pub fn tail_log(path: &Path, stop: &AtomicBool, mut emit: impl FnMut(Vec<LogLine>)) {
+  let mut tail = io_utils::FileTail::from_end(path);
+  let mut classifier = LogClassifier::default();
+
+  while !stop.load(Ordering::Relaxed) {
+    let lines = tail.read_lines();
+    if !lines.is_empty() {
+      emit(lines.into_iter().map(|x| classifier.classify(x)).collect());
+    }
+    std::thread::sleep(TAIL_INTERVAL);
+  }
+}
+
+pub fn watch(path: PathBuf, app_handle: tauri::AppHandle) -> Result<(), errors::AnyError> {
+  let stop = Arc::new(AtomicBool::new(false));
+  {
+    let mut watchers = WATCHERS.lock()
+      .map_err(|_| errors::state_locked("log watchers"))?;
+    let watchers = watchers.get_or_insert_with(HashMap::new);
+    if watchers.contains_key(&path) {
+      return Ok(());
+    }
+    if watchers.len() >= MAX_WATCHERS {
+      return Err(errors::str_error(&format!("Already watching {} logs, stop one first", MAX_WATCHERS)));
+    }
+    watchers.insert(path.clone(), stop.clone());
+  }
+
+  std::thread::spawn(move || {
+    tail_log(&path, &stop, |lines| {
+      let event = LogEvent { path: path.clone(), lines };
+      if let Err(err) = app_handle.emit_all(EDITOR_LOG_EVENT, event) {
+        println!("Failed to emit log lines: {}", err);
+      }
+    });
+  });
+
+  Ok(())
+}
+
+pub fn unwatch(path: &Path) -> Result<(), errors::AnyError> {
+  let mut watchers = WATCHERS.lock()
+    .map_err(|_| errors::state_locked("log watchers"))?;
+  if let Some(stop) = watchers.as_mut().and_then(|x| x.remove(path)) {
+    stop.store(true, Ordering::Relaxed);
+  }
+  Ok(())
+}
+
+// stops every tail, e.g. when the app exits
+pub fn unwatch_all() -> Result<(), errors::AnyError> {
+  let mut watchers = WATCHERS.lock()
+    .map_err(|_| errors::state_locked("log watchers"))?;
+  for (_, stop) in watchers.take().unwrap_or_default() {
+    stop.store(true, Ordering::Relaxed);
+  }
+  Ok(())
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_get_log_sources(project_path: Option<PathBuf>, app_state: tauri::State<AppState>) -> Result<Vec<LogSource>, errors::AnyError> {
+  get_log_sources(project_path.as_deref(), &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_read_editor_log(path: PathBuf, kinds: Option<Vec<LogLineKind>>) -> Result<Vec<LogLine>, errors::AnyError> {
+  read_log(&path, kinds.as_deref())
+}
+
+// new lines arrive as editor-log events until cmd_unwatch_editor_log
+#[tauri::command]
+pub fn cmd_watch_editor_log(path: PathBuf, app_handle: tauri::AppHandle) -> Result<(), errors::AnyError> {
+  watch(path, app_handle)
+}
+
+#[tauri::command]
+pub fn cmd_unwatch_editor_log(path: PathBuf) -> Result<(), errors::AnyError> {
+  unwatch(&path)
+}
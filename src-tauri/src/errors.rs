@@ -1,26 +1,224 @@
-use std::io;
+use std::{io, path::{Path, PathBuf}};
+
+use crate::{editor::EditorCandidate, prefs::PrefsKey, process::EditorProcess};
 
 #[derive(thiserror::Error, Debug)]
 pub enum AnyError {
     // #[error(transparent)]
     // Core(#[from] std::error::Error),
-    
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
     #[error(transparent)]
-    Anyhow(#[from] anyhow::Error),
+    Anyhow(anyhow::Error),
 
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
+
+    #[error("Editor version {version} is not installed")]
+    EditorNotFound { version: String, candidates: Vec<EditorCandidate> },
+
+    #[error("Editor version {version} is still used by {} project(s)", projects.len())]
+    EditorInUse { version: String, projects: Vec<PathBuf> },
+
+    #[error("Editor version {version} is already installed at {}", path.display())]
+    EditorExists { version: String, path: PathBuf },
+
+    #[error("No Unity editor install found at {}", path.display())]
+    EditorInvalid { path: PathBuf },
+
+    #[error("Invalid Unity version {version}")]
+    VersionInvalid { version: String },
+
+    #[error("Launch profile {name} does not exist")]
+    LaunchProfileNotFound { name: String },
+
+    #[error("{key:?} is not set")]
+    PrefMissing { key: PrefsKey },
+
+    #[error("Invalid {key:?}: {}", path.display())]
+    PrefInvalid { key: PrefsKey, path: PathBuf },
+
+    #[error("Corrupt or unreadable file: {}", path.display())]
+    ManifestCorrupt { path: PathBuf },
+
+    #[error("{} was saved by a newer version of the app (schema {version}, this one reads up to {supported})", path.display())]
+    SchemaTooNew { path: PathBuf, version: u32, supported: u32 },
+
+    #[error("Uninstalling {version} stopped partway ({reason}), {removed} file(s) removed and {} project(s) left on the new editor", projects.len())]
+    UninstallIncomplete { version: String, removed: usize, projects: Vec<PathBuf>, reason: String },
+
+    #[error("Unsupported archive {}: {reason}", path.display())]
+    ArchiveUnsupported { path: PathBuf, reason: String },
+
+    #[error("Failed to extract {}: {reason}", path.display())]
+    ExtractionFailed { path: PathBuf, reason: String },
+
+    #[error("Module {} can only be installed to a folder inside the editor, not {}", path.display(), destination.display())]
+    ModuleDestinationInvalid { path: PathBuf, destination: PathBuf },
+
+    #[error("Project already exists at {}", path.display())]
+    ProjectExists { path: PathBuf },
+
+    #[error("Project not found at {}", path.display())]
+    ProjectNotFound { path: PathBuf },
+
+    #[error("Project is already open in an editor: {}", path.display())]
+    ProjectAlreadyOpen { path: PathBuf, process: EditorProcess },
+
+    #[error("Project has a lock left by an editor that is no longer running: {}", lockfile.display())]
+    ProjectLockStale { path: PathBuf, lockfile: PathBuf },
+
+    #[error("Version switch {id} of {} not found", path.display())]
+    VersionSwitchNotFound { path: PathBuf, id: u128 },
+
+    #[error("Version switch {id} of {} is already rolled back or has newer switches after it", path.display())]
+    VersionSwitchNotLatest { path: PathBuf, id: u128 },
+
+    #[error("Snapshot is missing: {}", path.display())]
+    SnapshotMissing { path: PathBuf },
+
+    #[error("Crash report {id} of {} not found", path.display())]
+    CrashReportNotFound { path: PathBuf, id: u128 },
+
+    #[error("Could not read test results {}: {reason}", path.display())]
+    TestResultsUnreadable { path: PathBuf, reason: String },
+
+    #[error("No test runs recorded for {}", path.display())]
+    NoTestRuns { path: PathBuf },
+
+    #[error("Invalid template: {}", path.display())]
+    TemplateInvalid { path: PathBuf },
+
+    #[error("Failed to clone repository {url}")]
+    GitCloneFailed { url: String, stderr: String },
+
+    #[error("Failed to fetch release notes from {url}: {reason}")]
+    ReleaseNotesFetchFailed { url: String, reason: String },
+
+    #[error("No release notes found for {version}")]
+    ReleaseNotesNotFound { version: String },
+
+    #[error("Failed to get {name}. Is it locked?")]
+    StateLocked { name: String },
+}
+
+impl AnyError {
+    // stable identifier the frontend can match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            AnyError::Io(_) => "Io",
+            AnyError::Anyhow(_) => "Other",
+            AnyError::Serde(_) => "Json",
+            AnyError::EditorNotFound { .. } => "EditorNotFound",
+            AnyError::EditorInUse { .. } => "EditorInUse",
+            AnyError::EditorExists { .. } => "EditorExists",
+            AnyError::EditorInvalid { .. } => "EditorInvalid",
+            AnyError::VersionInvalid { .. } => "VersionInvalid",
+            AnyError::LaunchProfileNotFound { .. } => "LaunchProfileNotFound",
+            AnyError::PrefMissing { .. } => "PrefMissing",
+            AnyError::PrefInvalid { .. } => "PrefInvalid",
+            AnyError::ManifestCorrupt { .. } => "ManifestCorrupt",
+            AnyError::SchemaTooNew { .. } => "SchemaTooNew",
+            AnyError::UninstallIncomplete { .. } => "UninstallIncomplete",
+            AnyError::ArchiveUnsupported { .. } => "ArchiveUnsupported",
+            AnyError::ExtractionFailed { .. } => "ExtractionFailed",
+            AnyError::ModuleDestinationInvalid { .. } => "ModuleDestinationInvalid",
+            AnyError::ProjectExists { .. } => "ProjectExists",
+            AnyError::ProjectNotFound { .. } => "ProjectNotFound",
+            AnyError::ProjectAlreadyOpen { .. } => "ProjectAlreadyOpen",
+            AnyError::ProjectLockStale { .. } => "ProjectLockStale",
+            AnyError::VersionSwitchNotFound { .. } => "VersionSwitchNotFound",
+            AnyError::VersionSwitchNotLatest { .. } => "VersionSwitchNotLatest",
+            AnyError::SnapshotMissing { .. } => "SnapshotMissing",
+            AnyError::CrashReportNotFound { .. } => "CrashReportNotFound",
+            AnyError::TestResultsUnreadable { .. } => "TestResultsUnreadable",
+            AnyError::NoTestRuns { .. } => "NoTestRuns",
+            AnyError::TemplateInvalid { .. } => "TemplateInvalid",
+            AnyError::GitCloneFailed { .. } => "GitCloneFailed",
+            AnyError::ReleaseNotesFetchFailed { .. } => "ReleaseNotesFetchFailed",
+            AnyError::ReleaseNotesNotFound { .. } => "ReleaseNotesNotFound",
+            AnyError::StateLocked { .. } => "StateLocked",
+        }
+    }
+
+    // extra context for the ui to offer a fix, null when there is none
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            AnyError::Io(err) => serde_json::json!({ "kind": format!("{:?}", err.kind()) }),
+            AnyError::Anyhow(_) => serde_json::Value::Null,
+            AnyError::Serde(err) => serde_json::json!({ "line": err.line(), "column": err.column() }),
+            AnyError::EditorNotFound { version, candidates } => serde_json::json!({ "version": version, "candidates": candidates }),
+            AnyError::EditorInUse { version, projects } => serde_json::json!({ "version": version, "projects": projects }),
+            AnyError::EditorExists { version, path } => serde_json::json!({ "version": version, "path": path }),
+            AnyError::EditorInvalid { path } => serde_json::json!({ "path": path }),
+            AnyError::VersionInvalid { version } => serde_json::json!({ "version": version }),
+            AnyError::LaunchProfileNotFound { name } => serde_json::json!({ "name": name }),
+            AnyError::PrefMissing { key } => serde_json::json!({ "key": key }),
+            AnyError::PrefInvalid { key, path } => serde_json::json!({ "key": key, "path": path }),
+            AnyError::ManifestCorrupt { path } => serde_json::json!({ "path": path }),
+            AnyError::SchemaTooNew { path, version, supported } => serde_json::json!({ "path": path, "version": version, "supported": supported }),
+            AnyError::UninstallIncomplete { version, removed, projects, reason } => serde_json::json!({ "version": version, "removed": removed, "projects": projects, "reason": reason }),
+            AnyError::ArchiveUnsupported { path, reason } => serde_json::json!({ "path": path, "reason": reason }),
+            AnyError::ExtractionFailed { path, reason } => serde_json::json!({ "path": path, "reason": reason }),
+            AnyError::ModuleDestinationInvalid { path, destination } => serde_json::json!({ "path": path, "destination": destination }),
+            AnyError::ProjectExists { path } => serde_json::json!({ "path": path }),
+            AnyError::ProjectNotFound { path } => serde_json::json!({ "path": path }),
+            AnyError::ProjectAlreadyOpen { path, process } => serde_json::json!({ "path": path, "process": process }),
+            AnyError::ProjectLockStale { path, lockfile } => serde_json::json!({ "path": path, "lockfile": lockfile }),
+            AnyError::VersionSwitchNotFound { path, id } => serde_json::json!({ "path": path, "id": id }),
+            AnyError::VersionSwitchNotLatest { path, id } => serde_json::json!({ "path": path, "id": id }),
+            AnyError::SnapshotMissing { path } => serde_json::json!({ "path": path }),
+            AnyError::CrashReportNotFound { path, id } => serde_json::json!({ "path": path, "id": id }),
+            AnyError::TestResultsUnreadable { path, reason } => serde_json::json!({ "path": path, "reason": reason }),
+            AnyError::NoTestRuns { path } => serde_json::json!({ "path": path }),
+            AnyError::TemplateInvalid { path } => serde_json::json!({ "path": path }),
+            AnyError::GitCloneFailed { url, stderr } => serde_json::json!({ "url": url, "stderr": stderr }),
+            AnyError::ReleaseNotesFetchFailed { url, reason } => serde_json::json!({ "url": url, "reason": reason }),
+            AnyError::ReleaseNotesNotFound { version } => serde_json::json!({ "version": version }),
+            AnyError::StateLocked { name } => serde_json::json!({ "name": name }),
+        }
+    }
+}
+
+// plenty of helpers still return anyhow::Result, typed errors that went through
+// one keep their code instead of coming out as Other
+impl From<anyhow::Error> for AnyError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<AnyError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<io::Error>() {
+            Ok(err) => return AnyError::Io(err),
+            Err(err) => err,
+        };
+        match err.downcast::<serde_json::Error>() {
+            Ok(err) => AnyError::Serde(err),
+            Err(err) => AnyError::Anyhow(err),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ErrorPayload {
+    code: &'static str,
+    message: String,
+    details: serde_json::Value,
 }
 
 // we must manually implement serde::Serialize
+// sent to the frontend as { code, message, details }
 impl serde::Serialize for AnyError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer, {
-        serializer.serialize_str(self.to_string().as_ref())
+        ErrorPayload {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+        }.serialize(serializer)
     }
 }
 
@@ -30,4 +228,124 @@ pub fn io_not_found(message: &str) -> AnyError {
 
 pub fn str_error(err: &str) -> AnyError {
     AnyError::Anyhow(anyhow::anyhow!("{}", err))
-}
\ No newline at end of file
+}
+
+pub fn editor_not_found(version: &str) -> AnyError {
+    AnyError::EditorNotFound { version: version.to_string(), candidates: Vec::new() }
+}
+
+// installed editors the ui can offer instead, closest first
+pub fn editor_not_found_with_candidates(version: &str, candidates: Vec<EditorCandidate>) -> AnyError {
+    AnyError::EditorNotFound { version: version.to_string(), candidates }
+}
+
+// projects that would be left without their editor
+pub fn editor_in_use(version: &str, projects: Vec<PathBuf>) -> AnyError {
+    AnyError::EditorInUse { version: version.to_string(), projects }
+}
+
+pub fn editor_exists(version: &str, path: impl AsRef<Path>) -> AnyError {
+    AnyError::EditorExists { version: version.to_string(), path: path.as_ref().to_path_buf() }
+}
+
+pub fn editor_invalid(path: impl AsRef<Path>) -> AnyError {
+    AnyError::EditorInvalid { path: path.as_ref().to_path_buf() }
+}
+
+pub fn version_invalid(version: &str) -> AnyError {
+    AnyError::VersionInvalid { version: version.to_string() }
+}
+
+pub fn launch_profile_not_found(name: &str) -> AnyError {
+    AnyError::LaunchProfileNotFound { name: name.to_string() }
+}
+
+pub fn pref_missing(key: PrefsKey) -> AnyError {
+    AnyError::PrefMissing { key }
+}
+
+pub fn pref_invalid(key: PrefsKey, path: impl AsRef<Path>) -> AnyError {
+    AnyError::PrefInvalid { key, path: path.as_ref().to_path_buf() }
+}
+
+pub fn manifest_corrupt(path: impl AsRef<Path>) -> AnyError {
+    AnyError::ManifestCorrupt { path: path.as_ref().to_path_buf() }
+}
+
+pub fn schema_too_new(path: impl AsRef<Path>, version: u32, supported: u32) -> AnyError {
+    AnyError::SchemaTooNew { path: path.as_ref().to_path_buf(), version, supported }
+}
+
+// the editor is partly deleted or some projects couldn't be moved back to it
+pub fn uninstall_incomplete(version: &str, removed: usize, projects: Vec<PathBuf>, reason: &str) -> AnyError {
+    AnyError::UninstallIncomplete { version: version.to_string(), removed, projects, reason: reason.to_string() }
+}
+
+pub fn archive_unsupported(path: impl AsRef<Path>, reason: &str) -> AnyError {
+    AnyError::ArchiveUnsupported { path: path.as_ref().to_path_buf(), reason: reason.to_string() }
+}
+
+pub fn extraction_failed(path: impl AsRef<Path>, reason: &str) -> AnyError {
+    AnyError::ExtractionFailed { path: path.as_ref().to_path_buf(), reason: reason.to_string() }
+}
+
+pub fn module_destination_invalid(path: impl AsRef<Path>, destination: impl AsRef<Path>) -> AnyError {
+    AnyError::ModuleDestinationInvalid { path: path.as_ref().to_path_buf(), destination: destination.as_ref().to_path_buf() }
+}
+
+pub fn project_exists(path: impl AsRef<Path>) -> AnyError {
+    AnyError::ProjectExists { path: path.as_ref().to_path_buf() }
+}
+
+pub fn project_not_found(path: impl AsRef<Path>) -> AnyError {
+    AnyError::ProjectNotFound { path: path.as_ref().to_path_buf() }
+}
+
+pub fn project_already_open(path: impl AsRef<Path>, process: EditorProcess) -> AnyError {
+    AnyError::ProjectAlreadyOpen { path: path.as_ref().to_path_buf(), process }
+}
+
+pub fn project_lock_stale(path: impl AsRef<Path>, lockfile: impl AsRef<Path>) -> AnyError {
+    AnyError::ProjectLockStale { path: path.as_ref().to_path_buf(), lockfile: lockfile.as_ref().to_path_buf() }
+}
+
+pub fn version_switch_not_found(path: impl AsRef<Path>, id: u128) -> AnyError {
+    AnyError::VersionSwitchNotFound { path: path.as_ref().to_path_buf(), id }
+}
+
+// rolling back anything but the newest switch still in place would skip the ones after it
+pub fn version_switch_not_latest(path: impl AsRef<Path>, id: u128) -> AnyError {
+    AnyError::VersionSwitchNotLatest { path: path.as_ref().to_path_buf(), id }
+}
+
+pub fn snapshot_missing(path: impl AsRef<Path>) -> AnyError {
+    AnyError::SnapshotMissing { path: path.as_ref().to_path_buf() }
+}
+
+pub fn crash_report_not_found(path: impl AsRef<Path>, id: u128) -> AnyError {
+    AnyError::CrashReportNotFound { path: path.as_ref().to_path_buf(), id }
+}
+
+pub fn test_results_unreadable(path: impl AsRef<Path>, reason: &str) -> AnyError {
+    AnyError::TestResultsUnreadable { path: path.as_ref().to_path_buf(), reason: reason.to_string() }
+}
+
+pub fn no_test_runs(path: impl AsRef<Path>) -> AnyError {
+    AnyError::NoTestRuns { path: path.as_ref().to_path_buf() }
+}
+
+pub fn template_invalid(path: impl AsRef<Path>) -> AnyError {
+    AnyError::TemplateInvalid { path: path.as_ref().to_path_buf() }
+}
+
+pub fn release_notes_fetch_failed(url: &str, reason: &str) -> AnyError {
+    AnyError::ReleaseNotesFetchFailed { url: url.to_string(), reason: reason.to_string() }
+}
+
+pub fn release_notes_not_found(version: &str) -> AnyError {
+    AnyError::ReleaseNotesNotFound { version: version.to_string() }
+}
+
+pub fn state_locked(name: &str) -> AnyError {
+    AnyError::StateLocked { name: name.to_string() }
+}
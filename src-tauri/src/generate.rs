@@ -2,51 +2,51 @@ use std::{collections::HashMap, path::PathBuf};
 
 use flate2::{read::GzDecoder, write::GzEncoder};
 
-use crate::{app::{self, AppState}, editor::{self, UnityEditorInstall}, errors, io_utils, package::{self, MinimalPackage}, template::{EditorVersionPackageList, SurfaceTemplate}};
+use crate::{app::AppState, context::HubContext, editor::{self, UnityEditorInstall}, errors, io_utils, package::{self, MinimalPackage}, prefs::PrefsKey, template::{EditorVersionPackageList, SurfaceTemplate}};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TemplateInfoForGeneration {
-  template: Option<SurfaceTemplate>,
-  editor_version: UnityEditorInstall,
-  packages: Vec<MinimalPackage>,
-  selected_files: Vec<PathBuf>,
-  is_empty: bool
+  pub template: Option<SurfaceTemplate>,
+  pub editor_version: UnityEditorInstall,
+  pub packages: Vec<MinimalPackage>,
+  pub selected_files: Vec<PathBuf>,
+  pub is_empty: bool
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectTemplateInfoForGeneration {
-  project_path: PathBuf,
+  pub project_path: PathBuf,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectInfoForGeneration {
-  name: String,
-  path: PathBuf
+  pub name: String,
+  pub path: PathBuf
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewTemplateInfo {
-  template: TemplateInfoForGeneration,
-  name: String,
-  display_name: String,
-  version: String,
-  description: String
+  pub template: TemplateInfoForGeneration,
+  pub name: String,
+  pub display_name: String,
+  pub version: String,
+  pub description: String
 }
 
 // generate a new project from a template + info
-pub fn generate_project(app: &tauri::AppHandle, app_state: &tauri::State<'_, AppState>, project_info: &ProjectInfoForGeneration, template_info: &TemplateInfoForGeneration) -> Result<PathBuf, errors::AnyError> {
+pub fn generate_project(ctx: &impl HubContext, project_info: &ProjectInfoForGeneration, template_info: &TemplateInfoForGeneration) -> Result<PathBuf, errors::AnyError> {
   let package_cache_dir_out = &project_info.path.join(project_info.name.clone());
   if package_cache_dir_out.exists() {
-    return Err(errors::str_error(format!("Project already exists at {}", package_cache_dir_out.display()).as_str()));
+    return Err(errors::project_exists(package_cache_dir_out));
   }
 
-  let cached_editor_packages = crate::template::read_editor_version_packages(&app, &template_info.editor_version.version.clone())?;
+  let cached_editor_packages = crate::template::read_editor_version_packages(ctx, &template_info.editor_version.version.clone())?;
 
-  let package_cache_dir = io_utils::get_cache_appended_dir(app, "new_project_package")?;
+  let package_cache_dir = io_utils::get_cache_appended_dir(ctx, "new_project_package")?;
   if template_info.is_empty {
     // make a new project
     // copy entire project over
@@ -58,7 +58,7 @@ pub fn generate_project(app: &tauri::AppHandle, app_state: &tauri::State<'_, App
       .ok_or(errors::str_error("Failed to convert path to string"))?
       .to_string();
     let args = vec!["-createProject".to_string(), package_cache_dir_out_str, "-quit".to_string()];
-    editor::open(editor_version.clone(), args, &app_state, true)?;
+    editor::open(editor_version.clone(), args, ctx, true)?;
 
     let packages_dir = package_cache_dir_out
       .join("Packages");
@@ -203,11 +203,11 @@ pub fn generate_project(app: &tauri::AppHandle, app_state: &tauri::State<'_, App
 }
 
 // generate a new template file
-pub fn generate_template(app: &tauri::AppHandle, app_state: &tauri::State<AppState>, template_info: &NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
-  let package_cache_dir = io_utils::get_cache_appended_dir(app, "new_template_package")?;
-  let package_cache_dir_out = io_utils::get_cache_appended_dir(app, "new_template_package_output")?;
+pub fn generate_template(ctx: &impl HubContext, template_info: &NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
+  let package_cache_dir = io_utils::get_cache_appended_dir(ctx, "new_template_package")?;
+  let package_cache_dir_out = io_utils::get_cache_appended_dir(ctx, "new_template_package_output")?;
 
-  let cached_editor_packages = crate::template::read_editor_version_packages(&app, &template_info.template.editor_version.version.clone())?;
+  let cached_editor_packages = crate::template::read_editor_version_packages(ctx, &template_info.template.editor_version.version.clone())?;
 
   unpack_package_into_cache(&package_cache_dir, &template_info.template)?;
   modify_package_json(&package_cache_dir, &template_info.template.packages, &package_cache_dir_out, &cached_editor_packages)?;
@@ -272,9 +272,9 @@ pub fn generate_template(app: &tauri::AppHandle, app_state: &tauri::State<AppSta
   // }
 
   // build a new tgz file
-  let prefs = app::get_prefs(&app_state)?;
+  let prefs = ctx.prefs()?;
   let output_path = &prefs.hub_appdata_path.clone()
-    .ok_or(errors::str_error("hub_appdata_path not set"))?
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?
     .join("Templates")
     .join(format!("{}-{}.tgz", template_info.name, template_info.version));
 
@@ -295,28 +295,29 @@ pub fn generate_template(app: &tauri::AppHandle, app_state: &tauri::State<AppSta
 fn insert_template_into_hub_database(prefs: &crate::prefs::Prefs, template_info: &NewTemplateInfo) -> Result<(), errors::AnyError> {
   // modify the appdata template manifest.json
   let template_manifest_path = &prefs.hub_appdata_path.as_ref()
-    .ok_or(errors::str_error("hub_appdata_path not set"))?
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?
     .join("Templates")
     .join("manifest.json");
 
   let template_manifest_str = std::fs::read_to_string(&template_manifest_path)?;
-  let mut template_manifest_json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&template_manifest_str)?;
+  let mut template_manifest_json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&template_manifest_str)
+    .map_err(|_| errors::manifest_corrupt(template_manifest_path))?;
 
   // make version if needed
   let editor_version = template_info.template.editor_version.version.clone();
   if !template_manifest_json.contains_key(&editor_version) { 
-    template_manifest_json.insert(editor_version.clone(), serde_json::json!({}));
+    template_manifest_json.insert(editor_version.clone(), serde_json::json!({ "dependencies": {} }));
   }
   let editor_version_entry = template_manifest_json.get_mut(&editor_version)
-    .ok_or(errors::str_error("Failed to get editor version entry"))?;
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?;
 
   let dependency_map = editor_version_entry
     .as_object_mut()
-    .ok_or(errors::str_error("Failed to get editor version entry"))?
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?
     .get_mut("dependencies")
-    .ok_or(errors::str_error("Failed to get editor version entry"))?
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?
     .as_object_mut()
-    .ok_or(errors::str_error("Failed to get editor version entry"))?;
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?;
 
   dependency_map.insert(template_info.name.clone(), serde_json::Value::String(template_info.version.clone()));
   let template_manifest_str = serde_json::to_string(&template_manifest_json)?;
@@ -325,8 +326,8 @@ fn insert_template_into_hub_database(prefs: &crate::prefs::Prefs, template_info:
   Ok(())
 }
 
-fn generate_template_from_project(app: &tauri::AppHandle, app_state: &tauri::State<AppState>, template_info: &ProjectTemplateInfoForGeneration, new_template_info: &NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
-  let cache_path = io_utils::get_cache_appended_dir(&app, "project_template")?;
+pub fn generate_template_from_project(ctx: &impl HubContext, template_info: &ProjectTemplateInfoForGeneration, new_template_info: &NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
+  let cache_path = io_utils::get_cache_appended_dir(ctx, "project_template")?;
   let cache_package_path = cache_path.join("package");
 
   if !cache_package_path.exists() {
@@ -386,9 +387,9 @@ fn generate_template_from_project(app: &tauri::AppHandle, app_state: &tauri::Sta
   });
   std::fs::write(&package_json_path, serde_json::to_string_pretty(&new_package_json)?)?;
 
-  let prefs = app::get_prefs(&app_state)?;
+  let prefs = ctx.prefs()?;
   let output_path = &prefs.hub_appdata_path.clone()
-    .ok_or(errors::str_error("hub_appdata_path not set"))?
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?
     .join("Templates")
     .join(format!("{}-{}.tgz", new_template_info.name, new_template_info.version));
 
@@ -548,19 +549,19 @@ fn create_gitignore(output_path: &PathBuf) -> Result<(), errors::AnyError> {
 // commands
 
 #[tauri::command]
-pub async fn cmd_generate_project(app: tauri::AppHandle, app_state: tauri::State<'_, AppState>, project_info: ProjectInfoForGeneration, template_info: TemplateInfoForGeneration) -> Result<PathBuf, errors::AnyError> {
-  let output = generate_project(&app, &app_state, &project_info, &template_info)?;
+pub async fn cmd_generate_project(app_state: tauri::State<'_, AppState>, project_info: ProjectInfoForGeneration, template_info: TemplateInfoForGeneration) -> Result<PathBuf, errors::AnyError> {
+  let output = generate_project(app_state.inner(), &project_info, &template_info)?;
   Ok(output)
 }
 
 #[tauri::command]
-pub async fn cmd_generate_template(app: tauri::AppHandle, app_state: tauri::State<'_, AppState>, template_info: NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
-  let output = generate_template(&app, &app_state, &template_info)?;
+pub async fn cmd_generate_template(app_state: tauri::State<'_, AppState>, template_info: NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
+  let output = generate_template(app_state.inner(), &template_info)?;
   Ok(output)
 }
 
 #[tauri::command]
-pub async fn cmd_generate_template_from_project(app: tauri::AppHandle, app_state: tauri::State<'_, AppState>, template_info: ProjectTemplateInfoForGeneration, new_template_info: NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
-  let output = generate_template_from_project(&app, &app_state, &template_info, &new_template_info)?;
+pub async fn cmd_generate_template_from_project(app_state: tauri::State<'_, AppState>, template_info: ProjectTemplateInfoForGeneration, new_template_info: NewTemplateInfo) -> Result<PathBuf, errors::AnyError> {
+  let output = generate_template_from_project(app_state.inner(), &template_info, &new_template_info)?;
   Ok(output)
 }
\ No newline at end of file
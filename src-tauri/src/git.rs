@@ -62,15 +62,21 @@ pub async fn cmd_get_git_package_json(app: tauri::AppHandle, url: String) -> Res
     
     std::fs::create_dir_all(&cache_path)?;
 
-    fn run_clone(args: Vec<&str>) -> bool {
-      std::process::Command::new("git")
+    // stderr on failure so the ui can show what git complained about
+    fn run_clone(args: Vec<&str>) -> Result<(), String> {
+      let output = std::process::Command::new("git")
         .args(args.as_slice())
         .output()
         .inspect(|x| println!("{:?}", x))
-        .is_ok_and(|x| x.status.success())
+        .map_err(|err| err.to_string())?;
+
+      if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+      }
+      Ok(())
     }
 
-    let clone_success = {
+    let clone_result = {
       if let Some(branch) = branch {
         let args = vec!["clone", "-b", &branch, &url, cache_path_str];
         run_clone(args)
@@ -80,9 +86,9 @@ pub async fn cmd_get_git_package_json(app: tauri::AppHandle, url: String) -> Res
       }
     };
 
-    if !clone_success {
+    if let Err(stderr) = clone_result {
         std::fs::remove_dir_all(&cache_path)?;
-        return Err(errors::str_error("Failed to clone repository"));
+        return Err(errors::AnyError::GitCloneFailed { url, stderr });
     }
 
     let package_json = {
@@ -0,0 +1,213 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use crate::{
+  app::{self, AppState},
+  editor, errors, io_utils,
+  prefs::{Prefs, PrefsKey},
+  project::{self, Project},
+};
+
+// <hub_appdata_path>/projects-v1.json
+// { "schema_version": "v1", "data": { "<path>": { ... } } }
+#[derive(Debug, Clone, serde::Deserialize)]
+struct HubProjectsFile {
+  #[serde(default)]
+  data: HashMap<String, HubProject>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HubProject {
+  title: Option<String>,
+  path: Option<PathBuf>,
+  version: Option<String>,
+  #[serde(default)]
+  is_favorite: bool,
+  last_modified: Option<u128>,
+}
+
+// <hub_appdata_path>/editors-v2.json
+// { "schema_version": "v2", "data": [{ "version": "...", "location": [...], "manual": true }] }
+#[derive(Debug, Clone, serde::Deserialize)]
+struct HubEditorsFile {
+  #[serde(default)]
+  data: Vec<HubEditor>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct HubEditor {
+  location: HubEditorLocation,
+}
+
+// older hub versions store a single path instead of a list
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum HubEditorLocation {
+  One(PathBuf),
+  Many(Vec<PathBuf>),
+}
+
+impl HubEditorLocation {
+  fn paths(self) -> Vec<PathBuf> {
+    match self {
+      HubEditorLocation::One(path) => vec![path],
+      HubEditorLocation::Many(paths) => paths,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HubImportReport {
+  pub added_projects: Vec<PathBuf>,
+  pub duplicate_projects: Vec<PathBuf>,
+  pub missing_projects: Vec<PathBuf>,
+  pub added_editor_paths: Vec<PathBuf>,
+  pub duplicate_editor_paths: Vec<PathBuf>,
+  pub missing_editor_paths: Vec<PathBuf>,
+}
+
+fn read_hub_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>, errors::AnyError> {
+  if !path.is_file() {
+    return Ok(None);
+  }
+
+  let contents = std::fs::read_to_string(path)?;
+  let value = serde_json::from_str(&contents)
+    .map_err(|_| errors::manifest_corrupt(path))?;
+  Ok(Some(value))
+}
+
+fn get_hub_appdata_path(prefs: &Prefs) -> Result<PathBuf, errors::AnyError> {
+  let hub_appdata_path = prefs
+    .hub_appdata_path
+    .clone()
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?;
+
+  if !hub_appdata_path.is_dir() {
+    return Err(errors::pref_invalid(PrefsKey::HubAppDataPath, &hub_appdata_path));
+  }
+
+  Ok(hub_appdata_path)
+}
+
+// merge the hub's projects into `projects`, keeping what is already registered
+fn import_projects(
+  hub_appdata_path: &Path,
+  projects: &mut Vec<Project>,
+  report: &mut HubImportReport,
+) -> Result<(), errors::AnyError> {
+  let Some(hub_projects) = read_hub_file::<HubProjectsFile>(&hub_appdata_path.join("projects-v1.json"))? else {
+    return Ok(());
+  };
+
+  let now = io_utils::now_millis();
+
+  // the map is unordered, most recently opened go first like the hub shows them
+  let mut hub_projects = hub_projects.data
+    .into_iter()
+    .map(|(key, x)| (x.path.clone().unwrap_or(PathBuf::from(key)), x))
+    .collect::<Vec<_>>();
+  hub_projects.sort_by(|(_, x), (_, y)| y.last_modified.cmp(&x.last_modified));
+
+  for (path, hub_project) in hub_projects {
+    if projects.iter().any(|x| x.path == path) {
+      report.duplicate_projects.push(path);
+      continue;
+    }
+
+    if !path.join("Assets").exists() {
+      report.missing_projects.push(path);
+      continue;
+    }
+
+    // prefer what is on disk, the hub's copy can be stale
+    let mut project = project::load(&path).unwrap_or(Project {
+      name: hub_project.title.clone().unwrap_or_default(),
+      path: path.clone(),
+      version: hub_project.version.clone().unwrap_or_default(),
+      ..Default::default()
+    });
+    project.is_pinned = hub_project.is_favorite;
+    project.added_at = now;
+    project.last_opened_at = hub_project.last_modified.unwrap_or(0);
+
+    report.added_projects.push(path);
+    projects.push(project);
+  }
+
+  Ok(())
+}
+
+// secondaryInstallPath.json becomes a search root, editors-v2.json locations become
+// manual editors unless a search root already covers them
+fn import_editor_paths(
+  hub_appdata_path: &Path,
+  prefs: &mut Prefs,
+  report: &mut HubImportReport,
+) -> Result<(), errors::AnyError> {
+  let secondary_install_path = read_hub_file::<String>(&hub_appdata_path.join("secondaryInstallPath.json"))?
+    .filter(|x| !x.trim().is_empty())
+    .map(PathBuf::from);
+
+  if let Some(path) = secondary_install_path {
+    if editor::get_search_roots(prefs).contains(&path) {
+      report.duplicate_editor_paths.push(path);
+    } else if !path.is_dir() {
+      report.missing_editor_paths.push(path);
+    } else {
+      prefs.editor_search_paths.push(path.clone());
+      report.added_editor_paths.push(path);
+    }
+  }
+
+  let hub_editors = read_hub_file::<HubEditorsFile>(&hub_appdata_path.join("editors-v2.json"))?
+    .map(|x| x.data)
+    .unwrap_or_default();
+
+  for path in hub_editors.into_iter().flat_map(|x| x.location.paths()) {
+    let is_covered = editor::get_search_roots(prefs)
+      .iter()
+      .any(|root| path.starts_with(root));
+    if is_covered || prefs.manual_editor_paths.contains(&path) {
+      report.duplicate_editor_paths.push(path);
+    } else if !path.exists() {
+      report.missing_editor_paths.push(path);
+    } else {
+      prefs.manual_editor_paths.push(path.clone());
+      report.added_editor_paths.push(path);
+    }
+  }
+
+  Ok(())
+}
+
+// read the official hub's project list and editor locations and merge them into ours
+pub fn import_from_hub(app_state: &AppState) -> Result<HubImportReport, errors::AnyError> {
+  let mut report = HubImportReport::default();
+
+  {
+    let mut prefs = app_state.prefs.lock()
+      .map_err(|_| errors::state_locked("prefs"))?;
+    let hub_appdata_path = get_hub_appdata_path(&prefs)?;
+
+    let mut projects = app_state.projects.lock()
+      .map_err(|_| errors::state_locked("projects"))?;
+    import_projects(&hub_appdata_path, &mut projects, &mut report)?;
+    import_editor_paths(&hub_appdata_path, &mut prefs, &mut report)?;
+
+    app::save_projects_to_disk(&projects, &app_state.config_dir)?;
+    app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
+  }
+
+  Ok(report)
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_import_from_hub(app_state: tauri::State<AppState>) -> Result<HubImportReport, errors::AnyError> {
+  let report = import_from_hub(&app_state)?;
+  editor::refresh_editor_installs(&app_state)?;
+  Ok(report)
+}
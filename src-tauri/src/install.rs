@@ -0,0 +1,342 @@
+use std::{
+  fs,
+  io::{self, Read},
+  path::{Component, Path, PathBuf},
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+  },
+};
+
+use tauri::Manager;
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor::{self, UnityEditorInstall, UnityEditorModule},
+  errors, io_utils,
+  prefs::PrefsKey,
+};
+
+// event name the frontend gets extraction progress on
+pub const INSTALL_EVENT: &str = "editor-install";
+
+// archive entries extracted between progress events
+const PROGRESS_EVERY: u64 = 200;
+
+// how deep inside an archive the editor folder may sit, e.g. Unity-2022.3.10f1/Editor
+const INSTALL_SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleArchive {
+  pub path: PathBuf,
+  // written to modules.json, e.g. android
+  pub id: String,
+  pub name: String,
+  pub category: Option<String>,
+  // relative to the install folder, unity's own module archives extract from the install root
+  pub destination: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRequest {
+  // .tar.xz, .tar.gz, .zip or .pkg
+  pub archive: PathBuf,
+  #[serde(default)]
+  pub modules: Vec<ModuleArchive>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallProgress {
+  pub archive: PathBuf,
+  // 1 based, the editor archive first then every module
+  pub step: usize,
+  pub steps: usize,
+  // entries for zips, compressed bytes read for tarballs, 0 of 0 while a pkg expands
+  pub done: u64,
+  pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+  TarXz,
+  TarGz,
+  Zip,
+  Pkg,
+}
+
+fn get_archive_kind(path: &Path) -> Option<ArchiveKind> {
+  let name = path.file_name()?.to_string_lossy().to_lowercase();
+  if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+    Some(ArchiveKind::TarXz)
+  } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+    Some(ArchiveKind::TarGz)
+  } else if name.ends_with(".zip") {
+    Some(ArchiveKind::Zip)
+  } else if name.ends_with(".pkg") {
+    Some(ArchiveKind::Pkg)
+  } else {
+    None
+  }
+}
+
+// counts what the decoder pulled from the file, tarballs have no entry count up front
+struct CountingReader<R> {
+  inner: R,
+  read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let read = self.inner.read(buf)?;
+    self.read.fetch_add(read as u64, Ordering::Relaxed);
+    Ok(read)
+  }
+}
+
+fn extract_tar(archive: &Path, kind: ArchiveKind, to: &Path, progress: &mut impl FnMut(u64, u64)) -> Result<(), errors::AnyError> {
+  let total = fs::metadata(archive)?.len();
+  let read = Arc::new(AtomicU64::new(0));
+  let file = CountingReader { inner: fs::File::open(archive)?, read: read.clone() };
+  let decoder: Box<dyn Read> = match kind {
+    ArchiveKind::TarXz => Box::new(xz2::read::XzDecoder::new(file)),
+    _ => Box::new(flate2::read::GzDecoder::new(file)),
+  };
+
+  let mut tar = tar::Archive::new(decoder);
+  tar.set_preserve_permissions(true);
+  for (index, entry) in tar.entries()?.enumerate() {
+    // unpack_in refuses entries that would land outside `to`
+    entry?.unpack_in(to)?;
+    if (index as u64 + 1) % PROGRESS_EVERY == 0 {
+      progress(read.load(Ordering::Relaxed), total);
+    }
+  }
+  progress(total, total);
+  Ok(())
+}
+
+fn extract_zip(archive: &Path, to: &Path, progress: &mut impl FnMut(u64, u64)) -> Result<(), errors::AnyError> {
+  let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)
+    .map_err(|x| errors::extraction_failed(archive, &x.to_string()))?;
+  let total = zip.len() as u64;
+
+  for index in 0..zip.len() {
+    let mut entry = zip.by_index(index)
+      .map_err(|x| errors::extraction_failed(archive, &x.to_string()))?;
+    // entries with .. or absolute paths are skipped
+    let Some(name) = entry.enclosed_name().map(|x| to.join(x)) else {
+      continue;
+    };
+
+    if entry.is_dir() {
+      fs::create_dir_all(&name)?;
+    } else {
+      if let Some(parent) = name.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      io::copy(&mut entry, &mut fs::File::create(&name)?)?;
+    }
+
+    #[cfg(unix)]
+    if let Some(mode) = entry.unix_mode() {
+      use std::os::unix::fs::PermissionsExt;
+      fs::set_permissions(&name, fs::Permissions::from_mode(mode))?;
+    }
+
+    let done = index as u64 + 1;
+    if done % PROGRESS_EVERY == 0 || done == total {
+      progress(done, total);
+    }
+  }
+  Ok(())
+}
+
+// pkgutil unpacks every component's payload into `scratch`, their contents are merged into `to`
+fn extract_pkg(archive: &Path, to: &Path, scratch: &Path, progress: &mut impl FnMut(u64, u64)) -> Result<(), errors::AnyError> {
+  if !cfg!(target_os = "macos") {
+    return Err(errors::archive_unsupported(archive, "pkg installers can only be expanded on macOS"));
+  }
+
+  // pkgutil wants a folder that doesn't exist yet
+  let expanded = scratch.join(format!("pkg-{}", io_utils::now_millis()));
+  let output = std::process::Command::new("pkgutil")
+    .arg("--expand-full")
+    .arg(archive)
+    .arg(&expanded)
+    .output()?;
+  if !output.status.success() {
+    return Err(errors::extraction_failed(archive, String::from_utf8_lossy(&output.stderr).trim()));
+  }
+
+  let payloads = walkdir::WalkDir::new(&expanded)
+    .max_depth(2)
+    .into_iter()
+    .filter_map(|x| x.ok())
+    .filter(|x| x.file_type().is_dir() && x.file_name() == "Payload");
+  // module payloads land in folders the install already has, so folders are merged
+  for payload in payloads {
+    for entry in fs::read_dir(payload.path())? {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        io_utils::copy_dir_all(entry.path(), to.join(entry.file_name()))?;
+      } else {
+        fs::copy(entry.path(), to.join(entry.file_name()))?;
+      }
+    }
+  }
+  fs::remove_dir_all(&expanded)?;
+
+  progress(0, 0);
+  Ok(())
+}
+
+// `scratch` is where pkg installers are expanded first, it should be a dot folder the editor scan skips
+pub fn extract_archive(archive: &Path, to: &Path, scratch: &Path, mut progress: impl FnMut(u64, u64)) -> Result<(), errors::AnyError> {
+  let kind = get_archive_kind(archive)
+    .ok_or(errors::archive_unsupported(archive, "expected .tar.xz, .tar.gz, .zip or .pkg"))?;
+  if !archive.is_file() {
+    return Err(errors::io_not_found("Archive not found"));
+  }
+
+  fs::create_dir_all(to)?;
+  match kind {
+    ArchiveKind::TarXz | ArchiveKind::TarGz => extract_tar(archive, kind, to, &mut progress),
+    ArchiveKind::Zip => extract_zip(archive, to, &mut progress),
+    ArchiveKind::Pkg => extract_pkg(archive, to, scratch, &mut progress),
+  }
+}
+
+// the folder inside the archive that holds an editor in the layout find_editor_installs reads.
+// the version names the install folder, so the archive's folder names can't stand in for it
+fn find_extracted_install(path: &Path) -> Option<(PathBuf, UnityEditorInstall)> {
+  walkdir::WalkDir::new(path)
+    .max_depth(INSTALL_SEARCH_DEPTH)
+    .into_iter()
+    .filter_map(|x| x.ok())
+    .filter(|x| x.file_type().is_dir())
+    .find_map(|x| editor::read_editor_folder(x.path())
+      .filter(|y| editor::read_install_version(&y.exe_path).is_some())
+      .map(|y| (x.into_path(), y)))
+}
+
+// modules that came with the install stay, the installed ones are added or marked selected
+fn write_modules(install_path: &Path, modules: &[ModuleArchive]) -> Result<(), errors::AnyError> {
+  let json_path = install_path.join("modules").with_extension("json");
+  let mut listed: Vec<UnityEditorModule> = fs::read_to_string(&json_path)
+    .ok()
+    .and_then(|x| serde_json::from_str(&x).ok())
+    .unwrap_or_default();
+
+  for module in modules {
+    match listed.iter_mut().find(|x| x.id == module.id) {
+      Some(existing) => existing.selected = true,
+      None => listed.push(UnityEditorModule {
+        name: module.name.clone(),
+        id: module.id.clone(),
+        category: module.category.clone().unwrap_or("Platforms".to_string()),
+        visible: true,
+        selected: true,
+        ..Default::default()
+      }),
+    }
+  }
+
+  fs::write(&json_path, serde_json::to_string_pretty(&listed)?)?;
+  Ok(())
+}
+
+// module destinations stay inside the install, no absolute paths or ..
+fn check_module_destinations(modules: &[ModuleArchive]) -> Result<(), errors::AnyError> {
+  for module in modules {
+    let Some(destination) = &module.destination else {
+      continue;
+    };
+    if !destination.components().all(|x| matches!(x, Component::Normal(_))) {
+      return Err(errors::module_destination_invalid(&module.path, destination));
+    }
+  }
+  Ok(())
+}
+
+fn install_into(staging: &Path, hub_editors_path: &Path, request: &InstallRequest, emit: &mut impl FnMut(InstallProgress)) -> Result<PathBuf, errors::AnyError> {
+  let steps = request.modules.len() + 1;
+
+  let extracted = staging.join("editor");
+  extract_archive(&request.archive, &extracted, staging, |done, total| emit(InstallProgress {
+    archive: request.archive.clone(),
+    step: 1,
+    steps,
+    done,
+    total,
+  }))?;
+
+  let (found_path, found) = find_extracted_install(&extracted)
+    .ok_or(errors::editor_invalid(&request.archive))?;
+  let install_path = hub_editors_path.join(&found.version);
+  // checked before the modules too, so they aren't extracted for nothing
+  if install_path.exists() {
+    return Err(errors::editor_exists(&found.version, &install_path));
+  }
+
+  // modules go into the staged editor, so a failed module leaves nothing in the editors folder
+  for (index, module) in request.modules.iter().enumerate() {
+    let destination = found_path.join(module.destination.clone().unwrap_or_default());
+    extract_archive(&module.path, &destination, staging, |done, total| emit(InstallProgress {
+      archive: module.path.clone(),
+      step: index + 2,
+      steps,
+      done,
+      total,
+    }))?;
+  }
+  write_modules(&found_path, &request.modules)?;
+
+  if install_path.exists() {
+    return Err(errors::editor_exists(&found.version, &install_path));
+  }
+  // the staging folder lives next to the install, so this is a rename on the same drive
+  fs::rename(&found_path, &install_path)?;
+
+  Ok(install_path)
+}
+
+// extracts everything offline into <hub_editors_path>/<version>, the version comes from the archive itself
+pub fn install_from_archives(request: &InstallRequest, app_state: &AppState, mut emit: impl FnMut(InstallProgress)) -> Result<UnityEditorInstall, errors::AnyError> {
+  let hub_editors_path = app_state.prefs()?
+    .hub_editors_path
+    .ok_or(errors::pref_missing(PrefsKey::HubEditorsPath))?;
+  check_module_destinations(&request.modules)?;
+  fs::create_dir_all(&hub_editors_path)?;
+
+  // dot folders are skipped by find_editor_installs, so a half done install never shows up
+  let staging = hub_editors_path.join(format!(".installing-{}", io_utils::now_millis()));
+  let result = install_into(&staging, &hub_editors_path, request, &mut emit);
+  // a leftover staging folder is hidden, it mustn't hide what happened to the install
+  if staging.exists() {
+    if let Err(err) = fs::remove_dir_all(&staging) {
+      println!("Failed to remove {}: {}", staging.display(), err);
+    }
+  }
+  let install_path = result?;
+
+  editor::refresh_editor_installs(app_state)?
+    .into_iter()
+    .find(|x| editor::get_root_folder(&x.exe_path).as_deref() == Some(install_path.as_path()))
+    .ok_or(errors::editor_invalid(&install_path))
+}
+
+// commands
+
+// progress arrives as editor-install events
+#[tauri::command]
+pub async fn cmd_install_editor_from_archives(request: InstallRequest, app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<UnityEditorInstall, errors::AnyError> {
+  install_from_archives(&request, &app_state, |progress| {
+    if let Err(err) = app_handle.emit_all(INSTALL_EVENT, progress) {
+      println!("Failed to emit install progress: {}", err);
+    }
+  })
+}
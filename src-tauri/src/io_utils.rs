@@ -1,7 +1,9 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::{io, fs};
 
-use crate::errors;
+use crate::{context::HubContext, errors};
 use filesize::PathExt;
 
 pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<()> {
@@ -18,9 +20,8 @@ pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<
     Ok(())
 }
 
-pub fn get_cache_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, errors::AnyError> {
-    let cache_dir = app.path_resolver().app_cache_dir()
-        .ok_or(errors::str_error("Failed to get cache dir"))?;
+pub fn get_cache_dir(ctx: &impl HubContext) -> Result<std::path::PathBuf, errors::AnyError> {
+    let cache_dir = ctx.cache_dir().to_path_buf();
       
     // if cache_dir.exists() {
     //     std::fs::remove_dir_all(&cache_dir)?;
@@ -30,8 +31,8 @@ pub fn get_cache_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, error
     Ok(cache_dir)
 }
 
-pub fn get_cache_appended_dir(app: &tauri::AppHandle, name: &str) -> Result<std::path::PathBuf, errors::AnyError> {
-    let cache_dir = get_cache_dir(app)?;
+pub fn get_cache_appended_dir(ctx: &impl HubContext, name: &str) -> Result<std::path::PathBuf, errors::AnyError> {
+    let cache_dir = get_cache_dir(ctx)?;
     let cache_path = cache_dir.join(name);
 
     if cache_path.exists() {
@@ -43,6 +44,107 @@ pub fn get_cache_appended_dir(app: &tauri::AppHandle, name: &str) -> Result<std:
     Ok(cache_path)
 }
 
+// foo.json -> foo.json.bak
+pub fn backup_path(path: impl AsRef<Path>) -> std::path::PathBuf {
+    append_extension(path, "bak")
+}
+
+fn append_extension(path: impl AsRef<Path>, extension: &str) -> std::path::PathBuf {
+    let path = path.as_ref();
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+// write to a temp file next to the target then rename over it, so a crash
+// mid-write never leaves a half written file. the previous version is kept as .bak
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let tmp_path = append_extension(path, "tmp");
+
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        io::Write::write_all(&mut file, contents.as_ref())?;
+        file.sync_all()?;
+    }
+
+    if path.is_file() {
+        fs::copy(path, backup_path(path))?;
+    }
+
+    fs::rename(&tmp_path, path)
+}
+
+// milliseconds since the unix epoch, 0 when the clock is set before it
+pub fn now_millis() -> u128 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .unwrap_or(std::time::Duration::from_secs(0))
+        .as_millis()
+}
+
+// move a file that failed to parse out of the way, keeping it around for inspection
+pub fn quarantine(path: impl AsRef<Path>) -> io::Result<std::path::PathBuf> {
+    let quarantine_path = append_extension(&path, &format!("corrupt-{}", now_millis()));
+    fs::rename(path, &quarantine_path)?;
+    Ok(quarantine_path)
+}
+
+// follows a file that is being appended to, like tail -f
+pub struct FileTail {
+    path: std::path::PathBuf,
+    offset: u64,
+    partial: String,
+}
+
+impl FileTail {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf(), offset: 0, partial: String::new() }
+    }
+
+    // skip what is already there, only lines written from now on are read
+    pub fn from_end(path: impl AsRef<Path>) -> Self {
+        let offset = fs::metadata(path.as_ref()).map_or(0, |x| x.len());
+        Self { path: path.as_ref().to_path_buf(), offset, partial: String::new() }
+    }
+
+    // complete lines written since the last read, a partial last line waits for the next read
+    pub fn read_lines(&mut self) -> Vec<String> {
+        use io::{Read, Seek};
+
+        let Ok(mut file) = fs::File::open(&self.path) else {
+            return vec![];
+        };
+        // the file was started over, e.g. unity rewrites Editor.log on launch
+        if file.metadata().map_or(false, |x| x.len() < self.offset) {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if file.seek(io::SeekFrom::Start(self.offset)).is_err() {
+            return vec![];
+        }
+        let mut bytes = vec![];
+        let Ok(read) = file.read_to_end(&mut bytes) else {
+            return vec![];
+        };
+        self.offset += read as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+
+        let mut lines = vec![];
+        while let Some(index) = self.partial.find('\n') {
+            lines.push(self.partial[..index].trim_end_matches('\r').to_string());
+            self.partial.drain(..=index);
+        }
+        lines
+    }
+
+    // the unfinished last line, once nothing more will be written
+    pub fn take_partial(&mut self) -> Option<String> {
+        Some(std::mem::take(&mut self.partial)).filter(|x| !x.is_empty())
+    }
+}
+
 pub fn dir_size(path: impl Into<std::path::PathBuf>) -> Result<u64, errors::AnyError> {
     let path: std::path::PathBuf = path.into();
 
@@ -70,6 +172,65 @@ pub fn file_size(path: impl Into<std::path::PathBuf>) -> Result<u64, errors::Any
     Ok(size)
 }
 
+// (device, inode) of hardlinked files already counted, shared between walks
+#[derive(Debug, Default)]
+pub struct SeenLinks(Mutex<HashSet<(u64, u64)>>);
+
+impl SeenLinks {
+    #[cfg(unix)]
+    fn first_link(&self, metadata: &fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+        if metadata.nlink() < 2 {
+            return true;
+        }
+        self.0.lock().map_or(true, |mut x| x.insert((metadata.dev(), metadata.ino())))
+    }
+
+    // std has no stable file index on windows, so links are counted every time
+    #[cfg(not(unix))]
+    fn first_link(&self, _metadata: &fs::Metadata) -> bool {
+        true
+    }
+}
+
+// allocated size of everything under `path` except the `exclude`d folders.
+// a hardlinked file only counts for the first walk sharing `seen` that finds it
+pub fn dir_size_on_disk(path: &Path, exclude: &[PathBuf], seen: &SeenLinks) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|x| !exclude.iter().any(|y| y == x.path()))
+        .filter_map(|x| x.ok())
+        .filter(|x| !x.file_type().is_dir())
+        .filter_map(|x| x.metadata().ok().map(|y| (x, y)))
+        .filter(|(_, metadata)| seen.first_link(metadata))
+        .map(|(x, metadata)| x.path().size_on_disk_fast(&metadata).unwrap_or(metadata.len()))
+        .sum()
+}
+
+// 0 when the path is missing or has no mtime
+pub fn modified_millis(path: &Path) -> u128 {
+    fs::metadata(path)
+        .and_then(|x| x.modified())
+        .ok()
+        .and_then(|x| x.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |x| x.as_millis())
+}
+
+// newest mtime of a folder and everything inside it, writing a file deep inside doesn't
+// change the mtime of the folders above it. 0 when nothing has one
+pub fn tree_modified_millis(path: &Path, exclude: &[PathBuf]) -> u128 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|x| !exclude.iter().any(|y| y == x.path()))
+        .filter_map(|x| x.ok())
+        .filter_map(|x| x.metadata().ok())
+        .filter_map(|x| x.modified().ok())
+        .filter_map(|x| x.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|x| x.as_millis())
+        .max()
+        .unwrap_or(0)
+}
+
 // A type to represent a path, split into its component parts
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct FilePath {
@@ -179,7 +340,7 @@ pub fn build_ids(node: &mut FileDir, id: &mut u64) {
 /// Construct a relative path from a provided base directory path to the provided path
 ///
 /// ```rust
-/// use pathdiff::diff_paths;
+/// use nomnom_unity_hub::io_utils::diff_paths;
 /// use std::path::*;
 ///
 /// let baz: PathBuf = "/foo/bar/baz".into();
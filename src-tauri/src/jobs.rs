@@ -0,0 +1,355 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  sync::Mutex,
+  time::Duration,
+};
+
+use tauri::Manager;
+
+use crate::{
+  app::{self, AppState},
+  context::HubContext,
+  editor::{self, VersionChange},
+  errors, io_utils, launch, process, project,
+};
+
+pub const JOB_HISTORY_MIGRATIONS: &[app::Migration] = &[app::migrate_to_versioned_file];
+
+// event name the frontend listens on
+pub const JOB_EVENT: &str = "job-event";
+
+// jobs kept per project, oldest are dropped first
+const MAX_JOBS_PER_PROJECT: usize = 50;
+
+// how often the log is checked for new lines while the editor runs
+const TAIL_INTERVAL: Duration = Duration::from_millis(200);
+
+// jobs finish on their own threads, keep them from writing the history over each other
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TestPlatform {
+  EditMode,
+  PlayMode,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JobKind {
+  // -executeMethod Namespace.Class.Method
+  #[serde(rename_all = "camelCase")]
+  ExecuteMethod { method: String },
+  // -runTests -testPlatform EditMode|PlayMode
+  #[serde(rename_all = "camelCase")]
+  RunTests { platform: TestPlatform, filter: Option<String> },
+  // -buildTarget only switches the project, the method does the actual build
+  #[serde(rename_all = "camelCase")]
+  Build { target: String, method: String },
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRequest {
+  pub project_path: PathBuf,
+  // the project's own version when missing
+  pub editor_version: Option<String>,
+  pub kind: JobKind,
+  // arguments and env from a launch profile, applied after the job's own
+  pub launch_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum JobStatus {
+  Running,
+  Succeeded,
+  TestsFailed,
+  Failed,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+  pub id: u128,
+  pub project_path: PathBuf,
+  pub editor_version: String,
+  pub kind: JobKind,
+  pub arguments: Vec<String>,
+  pub log_path: PathBuf,
+  pub results_path: Option<PathBuf>,
+  pub status: JobStatus,
+  pub exit_code: Option<i32>,
+  pub message: Option<String>,
+  // set when the editor isn't the version the project was saved with
+  #[serde(default)]
+  pub warning: Option<String>,
+  pub started_at: u128,
+  pub finished_at: Option<u128>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JobEvent {
+  Started { job: Job },
+  #[serde(rename_all = "camelCase")]
+  Log { id: u128, line: String },
+  // unity prints "DisplayProgressbar: <title>" in batch mode instead of showing a bar
+  #[serde(rename_all = "camelCase")]
+  Progress { id: u128, message: String },
+  Finished { job: Job },
+}
+
+// project path -> jobs, oldest first
+pub type JobHistory = HashMap<PathBuf, Vec<Job>>;
+
+// jobs started in the same millisecond still get their own id and log file
+fn next_job_id() -> u128 {
+  static LAST_ID: Mutex<u128> = Mutex::new(0);
+  let now = io_utils::now_millis();
+  match LAST_ID.lock() {
+    Ok(mut last) => {
+      *last = now.max(*last + 1);
+      *last
+    }
+    Err(_) => now,
+  }
+}
+
+pub fn get_job_history_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  app::get_save_path("job_history", config_dir)
+}
+
+pub fn load_job_history(ctx: &impl HubContext) -> anyhow::Result<JobHistory> {
+  let path = get_job_history_save_path(ctx.config_dir())?;
+  app::load_from_disk(path, JOB_HISTORY_MIGRATIONS)
+}
+
+// insert or replace the job in its project's history
+fn record_job(job: &Job, ctx: &impl HubContext) -> Result<(), errors::AnyError> {
+  let _lock = HISTORY_LOCK.lock()
+    .map_err(|_| errors::state_locked("job history"))?;
+
+  let mut history = load_job_history(ctx)?;
+  let jobs = history.entry(job.project_path.clone()).or_default();
+  match jobs.iter_mut().find(|x| x.id == job.id) {
+    Some(existing) => *existing = job.clone(),
+    None => jobs.push(job.clone()),
+  }
+  if jobs.len() > MAX_JOBS_PER_PROJECT {
+    let extra = jobs.len() - MAX_JOBS_PER_PROJECT;
+    jobs.drain(..extra);
+  }
+
+  let path = get_job_history_save_path(ctx.config_dir())?;
+  app::save_to_disk(path, &history, JOB_HISTORY_MIGRATIONS)?;
+  Ok(())
+}
+
+pub fn get_project_jobs(project_path: &Path, ctx: &impl HubContext) -> Result<Vec<Job>, errors::AnyError> {
+  let history = load_job_history(ctx)?;
+  Ok(history.get(project_path).cloned().unwrap_or_default())
+}
+
+// logs and results sit in the project's Logs folder, which unity already keeps out of version control
+fn get_job_dir(project_path: &Path) -> PathBuf {
+  project_path.join("Logs").join("jobs")
+}
+
+pub fn build_arguments(project_path: &Path, kind: &JobKind, log_path: &Path, results_path: Option<&Path>) -> Vec<String> {
+  let mut arguments = vec![
+    "-projectPath".to_string(),
+    project_path.to_string_lossy().to_string(),
+    "-batchmode".to_string(),
+    "-logFile".to_string(),
+    log_path.to_string_lossy().to_string(),
+  ];
+
+  match kind {
+    JobKind::ExecuteMethod { method } => {
+      arguments.extend(["-quit".to_string(), "-executeMethod".to_string(), method.clone()]);
+    }
+    // -quit stops the test runner before it starts, it exits on its own
+    JobKind::RunTests { platform, filter } => {
+      let platform = match platform {
+        TestPlatform::EditMode => "EditMode",
+        TestPlatform::PlayMode => "PlayMode",
+      };
+      arguments.extend(["-runTests".to_string(), "-testPlatform".to_string(), platform.to_string()]);
+      if let Some(results_path) = results_path {
+        arguments.extend(["-testResults".to_string(), results_path.to_string_lossy().to_string()]);
+      }
+      if let Some(filter) = filter {
+        arguments.extend(["-testFilter".to_string(), filter.clone()]);
+      }
+    }
+    JobKind::Build { target, method } => {
+      arguments.extend([
+        "-quit".to_string(),
+        "-buildTarget".to_string(),
+        target.clone(),
+        "-executeMethod".to_string(),
+        method.clone(),
+      ]);
+    }
+  }
+
+  arguments
+}
+
+// unity exits 0 on success, 2 when tests ran and some failed, 3 when the run itself broke
+pub fn status_from_exit_code(kind: &JobKind, exit_code: Option<i32>) -> (JobStatus, Option<String>) {
+  let is_tests = matches!(kind, JobKind::RunTests { .. });
+  match exit_code {
+    Some(0) => (JobStatus::Succeeded, None),
+    Some(2) if is_tests => (JobStatus::TestsFailed, Some("Some tests failed".to_string())),
+    Some(3) if is_tests => (JobStatus::Failed, Some("Test run failed to start or finish".to_string())),
+    Some(code) => (JobStatus::Failed, Some(format!("Editor exited with code {}", code))),
+    None => (JobStatus::Failed, Some("Editor was killed before it finished".to_string())),
+  }
+}
+
+// check the request and record the job as running, the editor isn't started yet
+pub fn prepare_job(request: JobRequest, app_state: &AppState) -> Result<(Job, HashMap<String, String>), errors::AnyError> {
+  let project = project::load(&request.project_path)?;
+  // batch mode takes the same lock as an open editor
+  process::ensure_closed(&request.project_path, app_state)?;
+  let kind = request.kind;
+
+  let requested = request.editor_version.unwrap_or_else(|| project.version.clone());
+  let editor = editor::resolve_editor(&requested, app_state)?.editor;
+  // nobody is watching a batch run, so say so when it may upgrade or downgrade the project
+  let warning = VersionChange::between(&project.version, &editor.version)
+    .warning(&project.version, &editor.version);
+
+  let profile = {
+    let prefs = app_state.prefs()?;
+    match request.launch_profile.as_deref() {
+      Some(name) => launch::resolve_profile(&prefs, &request.project_path, Some(name))?,
+      None => None,
+    }
+  };
+
+  let id = next_job_id();
+  let job_dir = get_job_dir(&request.project_path);
+  fs::create_dir_all(&job_dir)?;
+  let log_path = job_dir.join(format!("{}.log", id));
+  let results_path = matches!(kind, JobKind::RunTests { .. })
+    .then(|| job_dir.join(format!("{}-results.xml", id)));
+
+  let mut arguments = build_arguments(&request.project_path, &kind, &log_path, results_path.as_deref());
+  let mut env = HashMap::new();
+  if let Some(profile) = profile {
+    arguments.extend(profile.arguments);
+    env = profile.env;
+  }
+
+  let job = Job {
+    id,
+    project_path: request.project_path,
+    editor_version: editor.version,
+    kind,
+    arguments,
+    log_path,
+    results_path,
+    status: JobStatus::Running,
+    exit_code: None,
+    message: None,
+    warning,
+    started_at: id,
+    finished_at: None,
+  };
+  record_job(&job, app_state)?;
+
+  Ok((job, env))
+}
+
+fn emit_lines(id: u128, lines: Vec<String>, emit: &mut impl FnMut(JobEvent)) {
+  for line in lines {
+    if let Some(message) = line.strip_prefix("DisplayProgressbar:") {
+      emit(JobEvent::Progress { id, message: message.trim().to_string() });
+    }
+    emit(JobEvent::Log { id, line });
+  }
+}
+
+// a job that broke before the editor's exit code was known doesn't stay running
+fn fail_job(mut job: Job, err: errors::AnyError, app_state: &AppState, emit: &mut impl FnMut(JobEvent)) -> Result<Job, errors::AnyError> {
+  job.status = JobStatus::Failed;
+  job.message = Some(err.to_string());
+  job.finished_at = Some(io_utils::now_millis());
+  record_job(&job, app_state)?;
+  emit(JobEvent::Finished { job });
+  Err(err)
+}
+
+// start the editor, stream its log until it exits and record how it went
+pub fn run_job(
+  mut job: Job,
+  env: &HashMap<String, String>,
+  app_state: &AppState,
+  mut emit: impl FnMut(JobEvent),
+) -> Result<Job, errors::AnyError> {
+  emit(JobEvent::Started { job: job.clone() });
+
+  // tracked like a launched editor so the project shows as open while the job runs
+  let spawned = editor::spawn(job.editor_version.clone(), job.arguments.clone(), env, app_state)
+    .and_then(|child| process::track_batch(app_state, child, job.project_path.clone(), job.editor_version.clone(), Some(job.log_path.clone())));
+  let pid = match spawned {
+    Ok(pid) => pid,
+    Err(err) => return fail_job(job, err, app_state, &mut emit),
+  };
+
+  let mut tail = io_utils::FileTail::new(&job.log_path);
+  let exit_status = loop {
+    emit_lines(job.id, tail.read_lines(), &mut emit);
+    match process::try_wait_batch(app_state, pid) {
+      Ok(Some(status)) => break status,
+      Ok(None) => std::thread::sleep(TAIL_INTERVAL),
+      Err(err) => return fail_job(job, err, app_state, &mut emit),
+    }
+  };
+
+  // whatever was written between the last read and the exit
+  let mut lines = tail.read_lines();
+  lines.extend(tail.take_partial());
+  emit_lines(job.id, lines, &mut emit);
+
+  let (status, message) = status_from_exit_code(&job.kind, exit_status.code());
+  job.status = status;
+  job.message = message;
+  job.exit_code = exit_status.code();
+  job.finished_at = Some(io_utils::now_millis());
+  record_job(&job, app_state)?;
+
+  emit(JobEvent::Finished { job: job.clone() });
+  Ok(job)
+}
+
+// commands
+
+// returns as soon as the job is recorded, progress arrives as job-event
+#[tauri::command]
+pub fn cmd_run_job(request: JobRequest, app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<Job, errors::AnyError> {
+  let (job, env) = prepare_job(request, &app_state)?;
+
+  let running = job.clone();
+  std::thread::spawn(move || {
+    let app_state = app_handle.state::<AppState>();
+    let result = run_job(running, &env, &app_state, |event| {
+      if let Err(err) = app_handle.emit_all(JOB_EVENT, event) {
+        println!("Failed to emit job event: {}", err);
+      }
+    });
+    if let Err(err) = result {
+      println!("Job failed: {}", err);
+    }
+  });
+
+  Ok(job)
+}
+
+#[tauri::command]
+pub fn cmd_get_project_jobs(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<Vec<Job>, errors::AnyError> {
+  get_project_jobs(&project_path, app_state.inner())
+}
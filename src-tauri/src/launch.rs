@@ -0,0 +1,179 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use crate::{
+  app::{self, AppState},
+  context::HubContext,
+  errors,
+  prefs::Prefs,
+};
+
+// extra command line arguments and environment variables for opening a project
+// e.g. -nographics, -buildTarget Android, -force-vulkan, -logFile <path>
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LaunchProfile {
+  pub name: String,
+  pub arguments: Vec<String>,
+  pub env: HashMap<String, String>,
+  // only offered for this project, None for every project
+  pub project_path: Option<PathBuf>,
+}
+
+impl LaunchProfile {
+  fn is_visible_to(&self, project_path: &Path) -> bool {
+    self.project_path
+      .as_ref()
+      .map_or(true, |x| x == project_path)
+  }
+
+  fn same_scope(&self, other: &LaunchProfile) -> bool {
+    self.name == other.name && self.project_path == other.project_path
+  }
+}
+
+// profiles a project can use, its own first
+pub fn get_profiles_for_project(prefs: &Prefs, project_path: &Path) -> Vec<LaunchProfile> {
+  let mut profiles = prefs.launch_profiles
+    .iter()
+    .filter(|x| x.is_visible_to(project_path))
+    .cloned()
+    .collect::<Vec<_>>();
+  profiles.sort_by_key(|x| x.project_path.is_none());
+  profiles
+}
+
+// an explicit name wins, then the project's pick, then the default.
+// a project profile shadows a global one with the same name
+pub fn resolve_profile(prefs: &Prefs, project_path: &Path, name: Option<&str>) -> Result<Option<LaunchProfile>, errors::AnyError> {
+  let name = name
+    .or(prefs.project_launch_profiles.get(project_path).map(|x| x.as_str()))
+    .or(prefs.default_launch_profile.as_deref());
+
+  let Some(name) = name else {
+    return Ok(None);
+  };
+
+  get_profiles_for_project(prefs, project_path)
+    .into_iter()
+    .find(|x| x.name == name)
+    .map(Some)
+    .ok_or(errors::launch_profile_not_found(name))
+}
+
+// -projectPath always goes first so a profile can't point the editor elsewhere.
+// a relative -logFile is passed on resolved, the editor doesn't start in the project
+pub fn build_arguments(project_path: &Path, profile: Option<&LaunchProfile>) -> Vec<String> {
+  let mut arguments = vec![
+    "-projectPath".to_string(),
+    project_path.to_string_lossy().to_string(),
+  ];
+  if let Some(profile) = profile {
+    let log_file = get_log_file(project_path, &profile.arguments);
+    arguments.extend(profile.arguments.iter().cloned());
+    if let (Some(log_file), Some(index)) = (log_file, arguments.iter().position(|x| x == "-logFile")) {
+      arguments[index + 1] = log_file.to_string_lossy().to_string();
+    }
+  }
+  arguments
+}
+
+// the file a -logFile argument points at, relative paths are inside the project. "-" means stdout
+pub fn get_log_file(project_path: &Path, arguments: &[String]) -> Option<PathBuf> {
+  let index = arguments.iter().position(|x| x == "-logFile")?;
+  arguments
+    .get(index + 1)
+    .filter(|x| x.as_str() != "-")
+    .map(|x| project_path.join(x))
+}
+
+fn update_prefs<T>(app_state: &AppState, update: impl FnOnce(&mut Prefs) -> Result<T, errors::AnyError>) -> Result<T, errors::AnyError> {
+  let mut prefs = app_state.prefs.lock()
+    .map_err(|_| errors::state_locked("prefs"))?;
+  let result = update(&mut prefs)?;
+  app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
+  Ok(result)
+}
+
+// add or replace a profile with the same name and scope
+pub fn save_profile(profile: LaunchProfile, app_state: &AppState) -> Result<Vec<LaunchProfile>, errors::AnyError> {
+  if profile.name.trim().is_empty() {
+    return Err(errors::str_error("Launch profile needs a name"));
+  }
+
+  update_prefs(app_state, |prefs| {
+    match prefs.launch_profiles.iter_mut().find(|x| x.same_scope(&profile)) {
+      Some(existing) => *existing = profile,
+      None => prefs.launch_profiles.push(profile),
+    }
+    Ok(prefs.launch_profiles.clone())
+  })
+}
+
+pub fn delete_profile(name: String, project_path: Option<PathBuf>, app_state: &AppState) -> Result<Vec<LaunchProfile>, errors::AnyError> {
+  update_prefs(app_state, |prefs| {
+    let before = prefs.launch_profiles.len();
+    prefs.launch_profiles.retain(|x| !(x.name == name && x.project_path == project_path));
+    if prefs.launch_profiles.len() == before {
+      return Err(errors::launch_profile_not_found(&name));
+    }
+
+    // forget picks that pointed at it, unless another scope still has the name
+    let still_named = |path: &Path| prefs.launch_profiles
+      .iter()
+      .any(|x| x.name == name && x.is_visible_to(path));
+    let stale = prefs.project_launch_profiles
+      .iter()
+      .filter(|(path, x)| **x == name && !still_named(path))
+      .map(|(path, _)| path.clone())
+      .collect::<Vec<_>>();
+    for path in stale {
+      prefs.project_launch_profiles.remove(&path);
+    }
+    if project_path.is_none() && prefs.default_launch_profile.as_deref() == Some(name.as_str()) {
+      prefs.default_launch_profile = None;
+    }
+
+    Ok(prefs.launch_profiles.clone())
+  })
+}
+
+// None goes back to the default profile
+pub fn set_project_profile(project_path: PathBuf, name: Option<String>, app_state: &AppState) -> Result<(), errors::AnyError> {
+  update_prefs(app_state, |prefs| {
+    match name {
+      Some(name) => {
+        if !get_profiles_for_project(prefs, &project_path).iter().any(|x| x.name == name) {
+          return Err(errors::launch_profile_not_found(&name));
+        }
+        prefs.project_launch_profiles.insert(project_path, name);
+      }
+      None => {
+        prefs.project_launch_profiles.remove(&project_path);
+      }
+    }
+    Ok(())
+  })
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_get_launch_profiles(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<Vec<LaunchProfile>, errors::AnyError> {
+  let prefs = app_state.prefs()?;
+  Ok(get_profiles_for_project(&prefs, &project_path))
+}
+
+#[tauri::command]
+pub fn cmd_save_launch_profile(profile: LaunchProfile, app_state: tauri::State<AppState>) -> Result<Vec<LaunchProfile>, errors::AnyError> {
+  save_profile(profile, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_delete_launch_profile(name: String, project_path: Option<PathBuf>, app_state: tauri::State<AppState>) -> Result<Vec<LaunchProfile>, errors::AnyError> {
+  delete_profile(name, project_path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_set_project_launch_profile(project_path: PathBuf, name: Option<String>, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+  set_project_profile(project_path, name, &app_state)
+}
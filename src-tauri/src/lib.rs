@@ -0,0 +1,175 @@
+#![allow(dead_code)]
+#![allow(
+    unused,
+    unused_imports,
+    unused_allocation,
+    unused_assignments,
+    unused_results,
+    unused_variables
+)]
+
+use std::sync::Mutex;
+
+use tauri::Manager;
+
+pub mod app;
+pub mod cache;
+pub mod cli;
+pub mod context;
+pub mod crash;
+pub mod editor;
+pub mod editor_log;
+pub mod errors;
+pub mod generate;
+pub mod git;
+pub mod hub_import;
+pub mod install;
+pub mod io_utils;
+pub mod jobs;
+pub mod launch;
+pub mod modules;
+pub mod package;
+pub mod prefs;
+pub mod process;
+pub mod project;
+pub mod release_notes;
+pub mod template;
+pub mod test_report;
+pub mod uninstall;
+pub mod version;
+pub mod version_switch;
+
+#[derive(Clone, serde::Serialize)]
+struct Payload {
+  args: Vec<String>,
+  cwd: String,
+}
+
+// starts the gui
+pub fn run() {
+  tauri::Builder::default()
+    .invoke_handler(tauri::generate_handler![
+      app::cmd_show_path_in_file_manager,
+      app::cmd_is_valid_path,
+      app::cmd_is_valid_dir,
+      app::cmd_is_valid_file,
+      // prefs
+      prefs::cmd_get_prefs,
+      prefs::cmd_load_prefs,
+      prefs::cmd_save_prefs,
+      prefs::cmd_set_pref_value,
+      prefs::cmd_set_prefs,
+      // user_cache
+      cache::cmd_get_user_cache,
+      cache::cmd_save_user_cache,
+      cache::cmd_set_user_cache_value,
+      cache::cmd_add_git_package_to_cache,
+      cache::cmd_add_local_package_to_cache,
+      cache::cmd_remove_git_package_from_cache,
+      cache::cmd_remove_local_package_from_cache,
+      cache::cmd_delete_template_cache,
+      // project
+      project::cmd_get_default_project_path,
+      project::cmd_remove_missing_projects,
+      project::cmd_add_project,
+      project::cmd_remove_project,
+      project::cmd_get_projects,
+      project::cmd_get_projects_on_page,
+      project::cmd_open_project_in_editor,
+      project::cmd_get_usage_report,
+      project::cmd_change_project_editor_version,
+      project::cmd_fetch_project_thumbnail,
+      project::cmd_pin_project,
+      project::cmd_unpin_project,
+      project::cmd_is_open_in_editor,
+      project::cmd_load_project_files_tree,
+      project::cmd_load_project_packages,
+      // launch profiles
+      launch::cmd_get_launch_profiles,
+      launch::cmd_save_launch_profile,
+      launch::cmd_delete_launch_profile,
+      launch::cmd_set_project_launch_profile,
+      modules::cmd_check_project_modules,
+      // version switch
+      version_switch::cmd_get_project_version_history,
+      version_switch::cmd_rollback_project_version,
+      // jobs
+      jobs::cmd_run_job,
+      jobs::cmd_get_project_jobs,
+      test_report::cmd_run_tests,
+      test_report::cmd_get_test_reports,
+      test_report::cmd_compare_last_test_runs,
+      // processes
+      process::cmd_get_running_editors,
+      process::cmd_remove_stale_lock,
+      process::cmd_focus_project_editor,
+      // editor logs
+      editor_log::cmd_get_log_sources,
+      editor_log::cmd_read_editor_log,
+      editor_log::cmd_watch_editor_log,
+      editor_log::cmd_unwatch_editor_log,
+      // crashes
+      crash::cmd_get_crash_reports,
+      crash::cmd_delete_crash_report,
+      // editors
+      editor::cmd_get_editors,
+      editor::cmd_open_unity_hub,
+      editor::cmd_estimate_editor_size,
+      editor::cmd_get_editor_size_breakdown,
+      uninstall::cmd_get_editor_dependent_projects,
+      uninstall::cmd_uninstall_editor,
+      install::cmd_install_editor_from_archives,
+      release_notes::cmd_get_release_notes,
+      release_notes::cmd_search_release_notes,
+      editor::cmd_resolve_editor,
+      editor::cmd_add_editor_search_path,
+      editor::cmd_remove_editor_search_path,
+      editor::cmd_add_manual_editor,
+      editor::cmd_remove_manual_editor,
+      // hub import
+      hub_import::cmd_import_from_hub,
+      // packages
+      package::cmd_get_default_editor_packages,
+      // templates
+      template::cmd_get_surface_templates,
+      template::cmd_get_template_information,
+      template::cmd_get_template_file_paths,
+      template::cmd_delete_template,
+      // generate
+      generate::cmd_generate_project,
+      generate::cmd_generate_template,
+      generate::cmd_generate_template_from_project,
+      // git
+      git::cmd_get_git_package_json
+    ])
+    .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+      println!("{}, {argv:?}, {cwd}", app.package_info().name);
+      app.emit_all("single-instance", Payload { args: argv, cwd })
+        .unwrap();
+    }))
+    .setup(|app| {
+      let app_handle = app.handle();
+      let config_dir = app::get_config_dir(&app_handle)?;
+      let cache_dir = app::get_cache_dir(&app_handle)?;
+
+      app.manage(app::AppState::load(config_dir, cache_dir)?);
+
+      let app_state = app.state::<app::AppState>();
+      if let Err(err) = editor::refresh_editor_installs(&app_state) {
+        println!("{}", err);
+      }
+
+      process::watch(app_handle);
+
+      Ok(())
+    })
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|_, event| {
+      if let tauri::RunEvent::Exit = event {
+        if let Err(err) = editor_log::unwatch_all() {
+          println!("{}", err);
+        }
+      }
+    });
+}
@@ -0,0 +1,150 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor, errors,
+};
+
+// BuildTarget enum values as serialized by unity, with the hub module each one needs.
+// standalone targets ship with the editor on their own os, consoles have no hub module
+const BUILD_TARGETS: [(i32, &str, Option<&str>); 17] = [
+  (2, "StandaloneOSX", Some("mac-mono")),
+  (5, "StandaloneWindows", Some("windows-mono")),
+  (9, "iOS", Some("ios")),
+  (13, "Android", Some("android")),
+  (19, "StandaloneWindows64", Some("windows-mono")),
+  (20, "WebGL", Some("webgl")),
+  (21, "WSAPlayer", Some("universal-windows-platform")),
+  (24, "StandaloneLinux64", Some("linux-mono")),
+  (31, "PS4", None),
+  (33, "XboxOne", None),
+  (37, "tvOS", Some("appletv")),
+  (38, "Switch", None),
+  (39, "Lumin", Some("lumin")),
+  (42, "GameCoreXboxSeries", None),
+  (43, "GameCoreXboxOne", None),
+  (44, "PS5", None),
+  (47, "VisionOS", Some("visionos")),
+];
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTarget {
+  pub id: i32,
+  // None for targets this hub doesn't know about
+  pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredModule {
+  pub id: String,
+  pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleCheck {
+  pub project_path: PathBuf,
+  // the editor the project would be opened with
+  pub editor_version: String,
+  // None when the project has no readable build settings, e.g. it was never opened
+  pub build_target: Option<BuildTarget>,
+  pub required: Vec<RequiredModule>,
+  pub missing: Vec<RequiredModule>,
+  // standalone installs have no modules.json, so nothing can be reported missing
+  pub modules_listed: bool,
+}
+
+// m_ActiveBuildTarget: 13
+fn parse_active_build_target(contents: &str) -> Option<i32> {
+  contents
+    .lines()
+    .find_map(|x| x.trim().strip_prefix("m_ActiveBuildTarget:"))
+    .and_then(|x| x.trim().parse().ok())
+}
+
+// checked in ProjectSettings first, then Library where unity writes it by default.
+// binary serialized assets can't be read and count as unknown
+pub fn get_active_build_target(project_path: &Path) -> Option<BuildTarget> {
+  let id = ["ProjectSettings", "Library"]
+    .iter()
+    .map(|x| project_path.join(x).join("EditorUserBuildSettings.asset"))
+    .filter_map(|x| fs::read(x).ok())
+    .find_map(|x| parse_active_build_target(&String::from_utf8_lossy(&x)))?;
+
+  let name = BUILD_TARGETS
+    .iter()
+    .find(|x| x.0 == id)
+    .map(|x| x.1.to_string());
+  Some(BuildTarget { id, name })
+}
+
+fn is_built_in(module_id: &str) -> bool {
+  match module_id {
+    "windows-mono" => cfg!(target_os = "windows"),
+    "mac-mono" => cfg!(target_os = "macos"),
+    "linux-mono" => cfg!(target_os = "linux"),
+    _ => false,
+  }
+}
+
+// module ids the editor needs to build for `build_target`
+pub fn get_required_module_ids(build_target: &BuildTarget) -> Vec<&'static str> {
+  BUILD_TARGETS
+    .iter()
+    .filter(|x| x.0 == build_target.id)
+    .filter_map(|x| x.2)
+    .filter(|x| !is_built_in(x))
+    .collect()
+}
+
+// compares the project's active build target against the modules installed for the editor it would open with
+pub fn check_project_modules(project_path: &Path, editor_version: &str, ctx: &impl HubContext) -> Result<ModuleCheck, errors::AnyError> {
+  if !project_path.exists() {
+    return Err(errors::project_not_found(project_path));
+  }
+
+  let resolution = editor::resolve_editor(editor_version, ctx)?;
+  let modules = &resolution.editor.modules;
+  let build_target = get_active_build_target(project_path);
+
+  let required = build_target
+    .as_ref()
+    .map(get_required_module_ids)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|id| RequiredModule {
+      id: id.to_string(),
+      // modules.json has the display name, fall back to the id
+      name: modules
+        .iter()
+        .find(|x| x.id == id)
+        .map_or(id.to_string(), |x| x.name.clone()),
+    })
+    .collect::<Vec<_>>();
+
+  let modules_listed = !modules.is_empty();
+  let missing = required
+    .iter()
+    .filter(|x| modules_listed && !modules.iter().any(|y| y.id == x.id && y.selected))
+    .cloned()
+    .collect();
+
+  Ok(ModuleCheck {
+    project_path: project_path.to_path_buf(),
+    editor_version: resolution.editor.version,
+    build_target,
+    required,
+    missing,
+    modules_listed,
+  })
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_check_project_modules(project_path: PathBuf, editor_version: String, app_state: tauri::State<AppState>) -> Result<ModuleCheck, errors::AnyError> {
+  check_project_modules(&project_path, &editor_version, app_state.inner())
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::{app::AppState, errors, template};
+use crate::{app::AppState, context::HubContext, errors, template};
 
 pub struct Package {
   pub name: String,
@@ -54,7 +54,7 @@ pub struct MinimalPackage {
 
 // pub fn get_editor_package_manager_manifest(editor_version: String, app_state: &tauri::State<AppState>) -> Result<PackageManagerEditorManifest, errors::AnyError> {
 //   let editor = app_state.editors.lock()
-//     .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?
+//     .map_err(|_| errors::state_locked("editors"))?
 //     .iter()
 //     .find(|x| x.version == editor_version)
 //     .ok_or(errors::str_error("Invalid editor version"))?
@@ -97,12 +97,8 @@ pub struct MinimalPackage {
 //   Ok(manifest)
 // }
 
-// commands
-
-// need to override versions with current template versions
-#[tauri::command]
-pub fn cmd_get_default_editor_packages(editor_version: String, app_state: tauri::State<AppState>) -> Result<Vec<MinimalPackage>, errors::AnyError> {
-  let manifest = crate::editor::read_package_manager_manifest(editor_version, &app_state)?;
+pub fn get_default_editor_packages(editor_version: String, ctx: &impl HubContext) -> Result<Vec<MinimalPackage>, errors::AnyError> {
+  let manifest = crate::editor::read_package_manager_manifest(editor_version, ctx)?;
   let mut manifest_packages = manifest.packages
     .iter()
     // .filter(|x| x.1.is_discoverable == Some(true))
@@ -118,4 +114,12 @@ pub fn cmd_get_default_editor_packages(editor_version: String, app_state: tauri:
   manifest_packages.sort_by(|x, y| x.name.cmp(&y.name));
   
   Ok(manifest_packages)
+}
+
+// commands
+
+// need to override versions with current template versions
+#[tauri::command]
+pub fn cmd_get_default_editor_packages(editor_version: String, app_state: tauri::State<AppState>) -> Result<Vec<MinimalPackage>, errors::AnyError> {
+  get_default_editor_packages(editor_version, app_state.inner())
 }
\ No newline at end of file
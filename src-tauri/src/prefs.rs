@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use crate::{app::{self, AppState}, errors};
 
@@ -8,7 +8,13 @@ pub enum PrefsKey {
     HubEditorsPath,
     HubAppDataPath,
     NewProjectPath,
-    ProjectSortType
+    ProjectSortType,
+    EditorSearchPaths,
+    ManualEditorPaths,
+    LaunchProfiles,
+    DefaultLaunchProfile,
+    ProjectLaunchProfiles,
+    ReleaseNotesUrl
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -22,7 +28,20 @@ pub struct Prefs {
     pub hub_editors_path: Option<PathBuf>,
     // typically C:\Users\nomno\AppData\Roaming\UnityHub\
     pub hub_appdata_path: Option<PathBuf>,
-    pub project_sort_type: Option<crate::project::SortType>
+    pub project_sort_type: Option<crate::project::SortType>,
+    // more folders laid out like hub_editors_path, e.g. a second drive
+    pub editor_search_paths: Vec<PathBuf>,
+    // editor executables that live outside of any search path
+    pub manual_editor_paths: Vec<PathBuf>,
+    // extra arguments and environment for opening projects
+    pub launch_profiles: Vec<crate::launch::LaunchProfile>,
+    // profile name used by projects that haven't picked one
+    pub default_launch_profile: Option<String>,
+    // project path -> profile name
+    pub project_launch_profiles: HashMap<PathBuf, String>,
+    // where to get release notes the editor doesn't ship, {version} is replaced, e.g.
+    // http://mirror.local/unity/{version}.html or a file path on a share
+    pub release_notes_url: Option<String>
 }
 
 impl Default for Prefs {
@@ -50,7 +69,16 @@ impl Default for Prefs {
 
             hub_appdata_path: Some(dirs_next::config_dir().unwrap().join("UnityHub")),
 
-            project_sort_type: None
+            project_sort_type: None,
+
+            editor_search_paths: Vec::new(),
+            manual_editor_paths: Vec::new(),
+
+            launch_profiles: Vec::new(),
+            default_launch_profile: None,
+            project_launch_profiles: HashMap::new(),
+
+            release_notes_url: None
         }
     }
 }
@@ -64,35 +92,35 @@ pub fn cmd_get_prefs(app_state: tauri::State<AppState>) -> Result<Prefs, errors:
 }
 
 #[tauri::command]
-pub fn cmd_set_prefs(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, prefs: Prefs) -> Result<(), errors::AnyError> {
+pub fn cmd_set_prefs(app_state: tauri::State<AppState>, prefs: Prefs) -> Result<(), errors::AnyError> {
     let mut lock = app_state.prefs.lock()
-        .map_err(|_| errors::str_error("Failed to get prefs. Is it locked?"))?;
-    *lock = prefs.clone();
-    app::save_new_prefs_to_disk(&app_handle)?;
+        .map_err(|_| errors::state_locked("prefs"))?;
+    app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
+    *lock = prefs;
     Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_load_prefs(app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<Prefs, errors::AnyError> {
-    let disk_prefs = app::load_prefs_from_disk(&app_handle)?;
+pub fn cmd_load_prefs(app_state: tauri::State<'_, AppState>) -> Result<Prefs, errors::AnyError> {
+    let disk_prefs = app::load_prefs_from_disk(&app_state.config_dir)?;
     let mut lock = app_state.prefs.lock()
-        .map_err(|_| errors::str_error("Failed to get prefs. Is it locked?"))?;
+        .map_err(|_| errors::state_locked("prefs"))?;
     *lock = disk_prefs.clone();
     Ok(disk_prefs)
 }
 
 #[tauri::command]
-pub fn cmd_save_prefs(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+pub fn cmd_save_prefs(app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
     let prefs = app::get_prefs(&app_state)?;
-    app::save_prefs_to_disk(&prefs, &app_handle)?;
+    app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_set_pref_value(app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>, key: PrefsKey, value: serde_json::Value) -> Result<(), errors::AnyError> {
+pub fn cmd_set_pref_value(app_state: tauri::State<'_, AppState>, key: PrefsKey, value: serde_json::Value) -> Result<(), errors::AnyError> {
     // let mut prefs = app::get_prefs(&app_state)?;
     let mut prefs = app_state.prefs.lock()
-        .map_err(|_| errors::str_error("Failed to get prefs. Is it locked?"))?;
+        .map_err(|_| errors::state_locked("prefs"))?;
 
     match key {
         PrefsKey::NewProjectPath => {
@@ -110,10 +138,28 @@ pub fn cmd_set_pref_value(app_handle: tauri::AppHandle, app_state: tauri::State<
         PrefsKey::ProjectSortType => {
             prefs.project_sort_type = serde_json::from_value(value)?;
         },
+        PrefsKey::EditorSearchPaths => {
+            prefs.editor_search_paths = serde_json::from_value(value)?;
+        },
+        PrefsKey::ManualEditorPaths => {
+            prefs.manual_editor_paths = serde_json::from_value(value)?;
+        },
+        PrefsKey::LaunchProfiles => {
+            prefs.launch_profiles = serde_json::from_value(value)?;
+        },
+        PrefsKey::DefaultLaunchProfile => {
+            prefs.default_launch_profile = serde_json::from_value(value)?;
+        },
+        PrefsKey::ProjectLaunchProfiles => {
+            prefs.project_launch_profiles = serde_json::from_value(value)?;
+        },
+        PrefsKey::ReleaseNotesUrl => {
+            prefs.release_notes_url = serde_json::from_value(value)?;
+        },
         // _ => return Err(errors::str_error("Invalid key")),
     }
 
-    app::save_prefs_to_disk(&prefs, &app_handle)?;
+    app::save_prefs_to_disk(&prefs, &app_state.config_dir)?;
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,588 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::{Child, ExitStatus},
+  time::Duration,
+};
+
+use tauri::Manager;
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  crash, editor, errors, io_utils, project,
+};
+
+// events the frontend listens on, both carry an EditorSession
+pub const EDITOR_STARTED_EVENT: &str = "editor-started";
+pub const EDITOR_EXITED_EVENT: &str = "editor-exited";
+
+// how often launched editors are checked for exiting
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+// an editor the hub started, kept so we can tell when it exits
+#[derive(Debug)]
+pub struct LaunchedEditor {
+  pub child: Child,
+  pub project_path: PathBuf,
+  pub editor_version: String,
+  pub started_at: u128,
+  // from -logFile, Editor.log when None
+  pub log_path: Option<PathBuf>,
+  // batch jobs wait on their own editor, reap_exited leaves them alone
+  pub batch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ProcessSource {
+  // started by this hub
+  Launched,
+  // found by its -projectPath argument
+  ProcessTable,
+  // the lockfile is held but the process couldn't be found
+  Lockfile,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorProcess {
+  pub pid: Option<u32>,
+  pub project_path: PathBuf,
+  pub editor_version: Option<String>,
+  pub source: ProcessSource,
+}
+
+// one run of an editor the hub launched, ended_at is None while it runs
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorSession {
+  pub pid: u32,
+  pub project_path: PathBuf,
+  pub editor_version: String,
+  pub log_path: Option<PathBuf>,
+  pub started_at: u128,
+  pub ended_at: Option<u128>,
+  pub exit_code: Option<i32>,
+  // exited with an error code or a signal that isn't a normal quit
+  pub crashed: bool,
+}
+
+impl LaunchedEditor {
+  fn session(&self) -> EditorSession {
+    EditorSession {
+      pid: self.child.id(),
+      project_path: self.project_path.clone(),
+      editor_version: self.editor_version.clone(),
+      log_path: self.log_path.clone(),
+      started_at: self.started_at,
+      ended_at: None,
+      exit_code: None,
+      crashed: false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum OpenState {
+  Closed,
+  Open { process: EditorProcess },
+  // Temp/UnityLockfile is left over but no editor has the project, usually after a crash
+  #[serde(rename_all = "camelCase")]
+  StaleLock { lockfile: PathBuf },
+}
+
+// a process from the os process table that was started with -projectPath
+#[derive(Debug, Clone)]
+struct ProcessInfo {
+  pid: u32,
+  project_path: PathBuf,
+  // executable and leading arguments, one of them is usually the editor
+  exe_paths: Vec<PathBuf>,
+}
+
+// the hub is a gui app on windows, console tools started from it would flash a window each time
+fn hidden_command(program: &str) -> std::process::Command {
+  #[allow(unused_mut)]
+  let mut command = std::process::Command::new(program);
+  #[cfg(target_os = "windows")]
+  {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    command.creation_flags(CREATE_NO_WINDOW);
+  }
+  command
+}
+
+pub fn get_lockfile_path(project_path: &Path) -> PathBuf {
+  project_path.join("Temp").join("UnityLockfile")
+}
+
+// compare the way the os would, so ./Game and /home/me/Game match
+fn same_path(a: &Path, b: &Path) -> bool {
+  match (fs::canonicalize(a), fs::canonicalize(b)) {
+    (Ok(a), Ok(b)) => a == b,
+    _ => a == b,
+  }
+}
+
+pub fn track(app_state: &AppState, child: Child, project_path: PathBuf, editor_version: String, log_path: Option<PathBuf>) -> Result<EditorSession, errors::AnyError> {
+  let launched = LaunchedEditor {
+    child,
+    project_path,
+    editor_version,
+    started_at: io_utils::now_millis(),
+    log_path,
+    batch: false,
+  };
+  let session = launched.session();
+
+  let mut processes = app_state.processes.lock()
+    .map_err(|_| errors::state_locked("processes"))?;
+  processes.push(launched);
+  Ok(session)
+}
+
+// a batch mode editor, the caller polls it with try_wait_batch
+pub fn track_batch(app_state: &AppState, child: Child, project_path: PathBuf, editor_version: String, log_path: Option<PathBuf>) -> Result<u32, errors::AnyError> {
+  let pid = child.id();
+  let mut processes = app_state.processes.lock()
+    .map_err(|_| errors::state_locked("processes"))?;
+  processes.push(LaunchedEditor {
+    child,
+    project_path,
+    editor_version,
+    started_at: io_utils::now_millis(),
+    log_path,
+    batch: true,
+  });
+  Ok(pid)
+}
+
+// forgets the batch editor once it has exited
+pub fn try_wait_batch(app_state: &AppState, pid: u32) -> Result<Option<ExitStatus>, errors::AnyError> {
+  let mut processes = app_state.processes.lock()
+    .map_err(|_| errors::state_locked("processes"))?;
+  let index = processes.iter().position(|x| x.batch && x.child.id() == pid)
+    .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, format!("Batch editor {} is not tracked", pid)))?;
+
+  match processes[index].child.try_wait() {
+    Ok(None) => Ok(None),
+    Ok(Some(status)) => {
+      processes.remove(index);
+      Ok(Some(status))
+    }
+    Err(err) => {
+      processes.remove(index);
+      Err(err.into())
+    }
+  }
+}
+
+// launched editors that are still running.
+// exited ones stay until reap_exited records their session
+pub fn get_running_sessions(app_state: &AppState) -> Result<Vec<EditorSession>, errors::AnyError> {
+  let mut processes = app_state.processes.lock()
+    .map_err(|_| errors::state_locked("processes"))?;
+
+  Ok(processes
+    .iter_mut()
+    .filter_map(|x| matches!(x.child.try_wait(), Ok(None)).then(|| x.session()))
+    .collect())
+}
+
+pub fn get_launched(app_state: &AppState) -> Result<Vec<EditorProcess>, errors::AnyError> {
+  Ok(get_running_sessions(app_state)?
+    .into_iter()
+    .map(|x| EditorProcess {
+      pid: Some(x.pid),
+      project_path: x.project_path,
+      editor_version: Some(x.editor_version),
+      source: ProcessSource::Launched,
+    })
+    .collect())
+}
+
+pub fn get_session(project_path: &Path, app_state: &AppState) -> Result<Option<EditorSession>, errors::AnyError> {
+  Ok(get_running_sessions(app_state)?
+    .into_iter()
+    .find(|x| same_path(&x.project_path, project_path)))
+}
+
+// an error code or a signal other than the ones sent to quit, e.g. by logging out.
+// SIGKILL counts as a crash since it's usually the out of memory killer
+fn is_crash(status: &ExitStatus) -> bool {
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::ExitStatusExt;
+    if let Some(signal) = status.signal() {
+      return !matches!(signal, libc::SIGTERM | libc::SIGINT | libc::SIGHUP);
+    }
+  }
+  !status.success()
+}
+
+// forget launched editors that have exited and add their time to the project,
+// crashed sessions get their logs archived
+pub fn reap_exited(app_state: &AppState) -> Result<Vec<EditorSession>, errors::AnyError> {
+  let exited = {
+    let mut processes = app_state.processes.lock()
+      .map_err(|_| errors::state_locked("processes"))?;
+
+    let mut exited = vec![];
+    let mut index = 0;
+    while index < processes.len() {
+      if processes[index].batch {
+        index += 1;
+        continue;
+      }
+      match processes[index].child.try_wait() {
+        Ok(None) => index += 1,
+        status => {
+          let launched = processes.remove(index);
+          let (exit_code, crashed) = match status {
+            Ok(Some(status)) => (status.code(), is_crash(&status)),
+            _ => (None, true),
+          };
+          exited.push(EditorSession {
+            ended_at: Some(io_utils::now_millis()),
+            exit_code,
+            crashed,
+            ..launched.session()
+          });
+        }
+      }
+    }
+    exited
+  };
+
+  for session in &exited {
+    let duration = session.ended_at.unwrap_or(session.started_at).saturating_sub(session.started_at);
+    // the project may have been removed while the editor was open
+    if let Err(err) = project::record_session(app_state, &session.project_path, duration) {
+      println!("Failed to record editor session: {}", err);
+    }
+    if session.crashed {
+      if let Err(err) = crash::archive_crash(session, &crash::get_default_sources(session), app_state) {
+        println!("Failed to archive crash: {}", err);
+      }
+    }
+  }
+
+  Ok(exited)
+}
+
+// poll launched editors for the lifetime of the app, emitting editor-exited
+pub fn watch(app_handle: tauri::AppHandle) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(WATCH_INTERVAL);
+
+    let app_state = app_handle.state::<AppState>();
+    match reap_exited(&app_state) {
+      Ok(sessions) => {
+        for session in sessions {
+          if let Err(err) = app_handle.emit_all(EDITOR_EXITED_EVENT, session) {
+            println!("Failed to emit editor exit: {}", err);
+          }
+        }
+      }
+      Err(err) => println!("Failed to check editors: {}", err),
+    }
+  });
+}
+
+// -projectPath <path>, unity also takes the lowercase spelling
+#[cfg(target_os = "linux")]
+fn project_path_from_args(args: &[String]) -> Option<PathBuf> {
+  let index = args.iter().position(|x| x.eq_ignore_ascii_case("-projectPath"))?;
+  args.get(index + 1).map(PathBuf::from)
+}
+
+// for command lines we only get as one string, a quoted path or everything up to a flag.
+// an unquoted path can have " -" in it, e.g. "My Game - Copy", so the longest
+// candidate that is a folder wins, then the shortest
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn project_path_from_command_line(command_line: &str) -> Option<PathBuf> {
+  let lower = command_line.to_ascii_lowercase();
+  let start = lower.find("-projectpath")? + "-projectpath".len();
+  let rest = command_line[start..].trim_start();
+  if let Some(quoted) = rest.strip_prefix('"') {
+    return Some(PathBuf::from(quoted.split('"').next()?)).filter(|x| !x.as_os_str().is_empty());
+  }
+
+  let mut candidates = rest
+    .match_indices(" -")
+    .map(|(index, _)| rest[..index].trim_end())
+    .chain([rest.trim_end()])
+    .filter(|x| !x.is_empty())
+    .map(PathBuf::from)
+    .collect::<Vec<_>>();
+  let existing = candidates.iter().rposition(|x| x.is_dir());
+  match existing {
+    Some(index) => Some(candidates.swap_remove(index)),
+    None => candidates.into_iter().next(),
+  }
+}
+
+#[cfg(target_os = "linux")]
+fn list_processes() -> Vec<ProcessInfo> {
+  let Ok(entries) = fs::read_dir("/proc") else {
+    return vec![];
+  };
+
+  entries
+    .filter_map(|x| x.ok())
+    .filter_map(|entry| {
+      let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+      let cmdline = fs::read(entry.path().join("cmdline")).ok()?;
+      let args = cmdline
+        .split(|x| *x == 0)
+        .filter(|x| !x.is_empty())
+        .map(|x| String::from_utf8_lossy(x).to_string())
+        .collect::<Vec<_>>();
+      let project_path = project_path_from_args(&args)?;
+
+      // scripts and wrappers put the editor in a later argument
+      let mut exe_paths = fs::read_link(entry.path().join("exe")).into_iter().collect::<Vec<_>>();
+      exe_paths.extend(args.iter().take(2).map(PathBuf::from));
+
+      Some(ProcessInfo { pid, project_path, exe_paths })
+    })
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn list_processes() -> Vec<ProcessInfo> {
+  let Ok(output) = std::process::Command::new("ps").args(["-axww", "-o", "pid=,args="]).output() else {
+    return vec![];
+  };
+
+  String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .filter_map(|line| {
+      let (pid, command_line) = line.trim_start().split_once(' ')?;
+      let project_path = project_path_from_command_line(command_line)?;
+      // ps joins arguments with spaces, the editor path ends at the binary inside the bundle
+      let exe_paths = command_line
+        .find("/Contents/MacOS/Unity")
+        .map(|x| PathBuf::from(&command_line[..x + "/Contents/MacOS/Unity".len()]))
+        .into_iter()
+        .collect();
+      Some(ProcessInfo { pid: pid.parse().ok()?, project_path, exe_paths })
+    })
+    .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn list_processes() -> Vec<ProcessInfo> {
+  let script = "Get-CimInstance Win32_Process -Filter \"Name='Unity.exe'\" | ForEach-Object { \"$($_.ProcessId)`t$($_.ExecutablePath)`t$($_.CommandLine)\" }";
+  let Ok(output) = hidden_command("powershell").args(["-NoProfile", "-Command", script]).output() else {
+    return vec![];
+  };
+
+  String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .filter_map(|line| {
+      let mut parts = line.splitn(3, '\t');
+      let pid = parts.next()?.trim().parse().ok()?;
+      let exe_path = PathBuf::from(parts.next()?);
+      let project_path = project_path_from_command_line(parts.next()?)?;
+      Some(ProcessInfo { pid, project_path, exe_paths: vec![exe_path] })
+    })
+    .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn list_processes() -> Vec<ProcessInfo> {
+  vec![]
+}
+
+// an installed editor whose executable the process was started from
+fn find_process_version(process: &ProcessInfo, ctx: &impl HubContext) -> Option<String> {
+  let editors = ctx.editors().unwrap_or_default();
+  process.exe_paths
+    .iter()
+    .find_map(|exe| editors
+      .iter()
+      .find(|x| editor::get_real_exe_path(x).map_or(false, |y| same_path(&y, exe)))
+      .map(|x| x.version.clone())
+      .or_else(|| editor::read_manual_editor(exe).map(|x| x.version)))
+}
+
+// editors running anywhere on the machine, not only the ones we started
+pub fn find_running_editors(ctx: &impl HubContext) -> Vec<EditorProcess> {
+  list_processes()
+    .into_iter()
+    .map(|x| EditorProcess {
+      pid: Some(x.pid),
+      editor_version: find_process_version(&x, ctx),
+      project_path: x.project_path,
+      source: ProcessSource::ProcessTable,
+    })
+    .collect()
+}
+
+// windows keeps the lockfile open while the editor runs
+#[cfg(target_os = "windows")]
+fn is_lockfile_held(lockfile: &Path) -> bool {
+  fs::OpenOptions::new().write(true).open(lockfile).is_err()
+}
+
+// /proc/locks lists every flock and fcntl lock by device and inode, reading it
+// doesn't take a lock that could race an editor starting up
+#[cfg(target_os = "linux")]
+fn is_lockfile_held(lockfile: &Path) -> bool {
+  use std::os::unix::fs::MetadataExt;
+
+  let (Ok(metadata), Ok(locks)) = (fs::metadata(lockfile), fs::read_to_string("/proc/locks")) else {
+    return false;
+  };
+  // the kernel prints major:minor in hex, split the way glibc encodes dev_t
+  let dev = metadata.dev();
+  let major = ((dev >> 32) & 0xfffff000) | ((dev >> 8) & 0xfff);
+  let minor = ((dev >> 12) & 0xffffff00) | (dev & 0xff);
+  let file_id = format!("{:02x}:{:02x}:{}", major, minor, metadata.ino());
+
+  // e.g. "1: FLOCK  ADVISORY  WRITE 1234 08:01:5678 0 EOF", waiting locks have "->" after the id
+  locks.lines().any(|line| line
+    .split_whitespace()
+    .filter(|x| *x != "->")
+    .nth(5)
+    .is_some_and(|x| x == file_id))
+}
+
+// fcntl's F_GETLK only reports who holds a lock. flock and fcntl locks share
+// one lock table on macOS and the BSDs, so it sees both
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_lockfile_held(lockfile: &Path) -> bool {
+  use std::os::fd::AsRawFd;
+
+  let Ok(file) = fs::OpenOptions::new().read(true).write(true).open(lockfile) else {
+    return false;
+  };
+
+  let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+  lock.l_type = libc::F_WRLCK as libc::c_short;
+  lock.l_whence = libc::SEEK_SET as libc::c_short;
+  let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+  result == 0 && lock.l_type != libc::F_UNLCK as libc::c_short
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn is_lockfile_held(_lockfile: &Path) -> bool {
+  false
+}
+
+// launched editors first, then the process table, then the lockfile
+pub fn get_open_state(project_path: &Path, app_state: &AppState) -> Result<OpenState, errors::AnyError> {
+  let launched = get_launched(app_state)?
+    .into_iter()
+    .find(|x| same_path(&x.project_path, project_path));
+  if let Some(process) = launched {
+    return Ok(OpenState::Open { process });
+  }
+
+  let running = find_running_editors(app_state)
+    .into_iter()
+    .find(|x| same_path(&x.project_path, project_path));
+  if let Some(process) = running {
+    return Ok(OpenState::Open { process });
+  }
+
+  let lockfile = get_lockfile_path(project_path);
+  if !lockfile.exists() {
+    return Ok(OpenState::Closed);
+  }
+
+  if is_lockfile_held(&lockfile) {
+    return Ok(OpenState::Open {
+      process: EditorProcess {
+        pid: None,
+        project_path: project_path.to_path_buf(),
+        editor_version: None,
+        source: ProcessSource::Lockfile,
+      },
+    });
+  }
+
+  Ok(OpenState::StaleLock { lockfile })
+}
+
+// an editor already has the project or left its lock behind, unity would refuse to open it
+pub fn ensure_closed(project_path: &Path, app_state: &AppState) -> Result<(), errors::AnyError> {
+  match get_open_state(project_path, app_state)? {
+    OpenState::Closed => Ok(()),
+    OpenState::Open { process } => Err(errors::project_already_open(project_path, process)),
+    OpenState::StaleLock { lockfile } => Err(errors::project_lock_stale(project_path, lockfile)),
+  }
+}
+
+// only removes the lock when no editor has the project
+pub fn remove_stale_lock(project_path: &Path, app_state: &AppState) -> Result<(), errors::AnyError> {
+  match get_open_state(project_path, app_state)? {
+    OpenState::Closed => Ok(()),
+    OpenState::Open { process } => Err(errors::project_already_open(project_path, process)),
+    OpenState::StaleLock { lockfile } => {
+      fs::remove_file(lockfile)?;
+      Ok(())
+    }
+  }
+}
+
+// bring the editor's window to the front, best effort since it depends on desktop tools
+pub fn focus(pid: u32) -> Result<(), errors::AnyError> {
+  let mut command = if cfg!(target_os = "windows") {
+    let mut command = hidden_command("powershell");
+    command.args([
+      "-NoProfile",
+      "-Command",
+      &format!("(New-Object -ComObject WScript.Shell).AppActivate({})", pid),
+    ]);
+    command
+  } else if cfg!(target_os = "macos") {
+    let mut command = hidden_command("osascript");
+    command.args([
+      "-e",
+      &format!("tell application \"System Events\" to set frontmost of (first process whose unix id is {}) to true", pid),
+    ]);
+    command
+  } else {
+    let mut command = hidden_command("xdotool");
+    command.args(["search", "--pid", &pid.to_string(), "windowactivate"]);
+    command
+  };
+
+  let status = command
+    .status()
+    .map_err(|_| errors::str_error("Failed to focus the editor window"))?;
+  if !status.success() {
+    return Err(errors::str_error("Failed to focus the editor window"));
+  }
+  Ok(())
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_remove_stale_lock(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+  remove_stale_lock(&project_path, &app_state)
+}
+
+#[tauri::command]
+pub fn cmd_focus_project_editor(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+  match get_open_state(&project_path, &app_state)? {
+    OpenState::Open { process: EditorProcess { pid: Some(pid), .. } } => focus(pid),
+    _ => Err(errors::str_error("Project isn't open in an editor")),
+  }
+}
+
+#[tauri::command]
+pub fn cmd_get_running_editors(app_state: tauri::State<AppState>) -> Result<Vec<EditorProcess>, errors::AnyError> {
+  let mut processes = get_launched(&app_state)?;
+  for process in find_running_editors(app_state.inner()) {
+    if !processes.iter().any(|x| x.pid == process.pid) {
+      processes.push(process);
+    }
+  }
+  Ok(processes)
+}
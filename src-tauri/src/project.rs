@@ -1,6 +1,8 @@
 use std::{fs, path::{Path, PathBuf}};
 
-use crate::{app::{self, AppState}, errors, io_utils, package, template::TgzPackageJson};
+use tauri::Manager;
+
+use crate::{app::{self, AppState}, context::HubContext, editor::{self, EditorResolution, VersionChange}, launch, errors, prefs::PrefsKey, io_utils, package, process::{self, OpenState}, template::TgzPackageJson, version, version_switch::{self, VersionSwitch}};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(default, rename_all = "camelCase")]
@@ -11,6 +13,9 @@ pub struct Project {
   pub is_pinned: bool,
   pub added_at: u128,
   pub last_opened_at: u128,
+  // millis across every session the hub launched
+  pub total_open_time: u128,
+  pub session_count: u32,
 }
 
 impl Default for Project {
@@ -22,6 +27,8 @@ impl Default for Project {
       is_pinned: false,
       added_at: 0u128,
       last_opened_at: 0u128,
+      total_open_time: 0u128,
+      session_count: 0,
     }
   }
 }
@@ -47,7 +54,7 @@ pub fn load(path: impl AsRef<Path>) -> Result<Project, errors::AnyError> {
   let assets_path = path.join("Assets");
 
   if !assets_path.exists() {
-    return Err(errors::io_not_found("Invalid project path"));
+    return Err(errors::project_not_found(path));
   }
   
   let file_name = path
@@ -91,9 +98,9 @@ pub fn has_valid_path(project: &Project) -> anyhow::Result<(), errors::AnyError>
   Ok(())
 }
 
-pub fn remove_missing_projects(app_state: &tauri::State<AppState>) -> anyhow::Result<Vec<Project>> {
+pub fn remove_missing_projects(app_state: &AppState) -> anyhow::Result<Vec<Project>> {
   let mut projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to lock projects"))?;
+    .map_err(|_| errors::state_locked("projects"))?;
   let missing_projects = projects
     .iter()
     .filter(|x| !x.path.clone().exists() || !x.path.clone().join("Assets").exists())
@@ -122,7 +129,7 @@ fn filter_by_sort(sort_by: SortType, projects: &mut Vec<Project>) {
     SortType::DateOpened => projects.sort_by(|x, y| y.last_opened_at.cmp(&x.last_opened_at)),
     SortType::Name => projects.sort_by(|x, y| x.name.cmp(&y.name)),
     SortType::DateAdded => projects.sort_by(|x, y| y.added_at.cmp(&x.added_at)),
-    SortType::EditorVersion => projects.sort_by(|x, y| y.version.cmp(&x.version)),
+    SortType::EditorVersion => projects.sort_by(|x, y| version::compare(&y.version, &x.version)),
     _ => {}
   }
 }
@@ -132,8 +139,8 @@ fn sort_by_pinned(projects: &mut Vec<Project>) {
   projects.sort_by(|x, y| y.is_pinned.cmp(&x.is_pinned));
 }
 
-pub fn get_projects_on_page(app_state: &tauri::State<AppState>, page: usize, per_page_count: usize, search: SearchOptions) -> anyhow::Result<Vec<Project>> {
-  let mut projects = app::get_projects(&app_state)?;
+pub fn get_projects_on_page(ctx: &impl HubContext, page: usize, per_page_count: usize, search: SearchOptions) -> anyhow::Result<Vec<Project>> {
+  let mut projects = ctx.projects()?;
   sort_by_pinned(&mut projects);
 
   let start = page * per_page_count;
@@ -142,7 +149,7 @@ pub fn get_projects_on_page(app_state: &tauri::State<AppState>, page: usize, per
     filter_by_name(&name_filter, &mut projects);
   }
 
-  if let Some(sort_by) = app::get_prefs(&app_state)?.project_sort_type {
+  if let Some(sort_by) = ctx.prefs()?.project_sort_type {
     filter_by_sort(sort_by, &mut projects);
   }
 
@@ -159,72 +166,115 @@ pub fn get_projects_on_page(app_state: &tauri::State<AppState>, page: usize, per
   Ok(projects)
 }
 
-pub fn update_project_open_time(app_state: &tauri::State<AppState>, project_path: PathBuf, app_handle: &tauri::AppHandle) -> Result<(), errors::AnyError> {
+pub fn update_project_open_time(app_state: &AppState, project_path: PathBuf) -> Result<(), errors::AnyError> {
   let time = std::time::UNIX_EPOCH
     .elapsed()
     .unwrap_or(std::time::Duration::from_secs(0));
   let millis = time.as_millis();
 
   let mut projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("projects"))?;
   let project = projects
     .iter_mut()
     .find(|x| x.path == project_path)
-    .ok_or(errors::str_error(format!("Project not found at {:?}", project_path).as_str()))?;
+    .ok_or(errors::project_not_found(&project_path))?;
   project.last_opened_at = millis;
 
-  app::save_projects_to_disk(&projects, &app_handle)?;
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
 
   Ok(())
 }
 
-pub fn open_project(project_path: PathBuf, editor_version: String, app_state: &tauri::State<AppState>, app_handle: &tauri::AppHandle) -> Result<(), errors::AnyError> {
-  if !project_path.exists() {
-    return Err(errors::io_not_found("Invalid project path"));
-  }
-  
-  let project_path_str = project_path.to_str()
-    .ok_or(errors::str_error("Invalid project path"))?
-    .to_string();
-  
-  let args = vec!["-projectPath".to_string(), project_path_str];
-  crate::editor::open(editor_version, args, &app_state, false)?;
+pub fn record_session(app_state: &AppState, project_path: &Path, duration: u128) -> Result<(), errors::AnyError> {
+  let mut projects = app_state.projects.lock()
+    .map_err(|_| errors::state_locked("projects"))?;
+  let project = projects
+    .iter_mut()
+    .find(|x| x.path == project_path)
+    .ok_or(errors::project_not_found(project_path))?;
+  project.total_open_time += duration;
+  project.session_count += 1;
+
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
 
-  update_project_open_time(&app_state, project_path, &app_handle)?;
   Ok(())
 }
 
-// commands
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectUsage {
+  pub name: String,
+  pub path: PathBuf,
+  pub session_count: u32,
+  pub total_open_time: u128,
+  pub average_session: u128,
+  pub last_opened_at: u128,
+}
 
-#[tauri::command]
-pub fn cmd_get_default_project_path(app_state: tauri::State<AppState>) -> Result<String, errors::AnyError> {
-  let prefs = app::get_prefs(&app_state)?;
-  let new_project_path = prefs.new_project_path
-    .ok_or(errors::str_error("new_project_path not set"))?
-    .to_str()
-    .ok_or(errors::str_error("Invalid new_project_path"))?
-    .to_string();
-  Ok(new_project_path)
+// projects that were opened at least once, most used first
+pub fn get_usage_report(ctx: &impl HubContext) -> Result<Vec<ProjectUsage>, errors::AnyError> {
+  let mut usage = ctx.projects()?
+    .into_iter()
+    .filter(|x| x.session_count > 0)
+    .map(|x| ProjectUsage {
+      average_session: x.total_open_time / x.session_count as u128,
+      name: x.name,
+      path: x.path,
+      session_count: x.session_count,
+      total_open_time: x.total_open_time,
+      last_opened_at: x.last_opened_at,
+    })
+    .collect::<Vec<_>>();
+  usage.sort_by(|a, b| b.total_open_time.cmp(&a.total_open_time));
+  Ok(usage)
 }
 
-#[tauri::command]
-pub async fn cmd_remove_missing_projects(app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<Vec<Project>, errors::AnyError> {
-  let removed_projects = remove_missing_projects(&app_state)?;
-  let projects = app::get_projects(&app_state)?;
-  app::save_projects_to_disk(&projects, &app_handle)?;
-  Ok(removed_projects)
+// opens with the closest installed editor when the exact version is missing,
+// the returned warning says what will happen to the project
+pub fn open_project(project_path: PathBuf, editor_version: String, app_state: &AppState) -> Result<EditorResolution, errors::AnyError> {
+  open_project_with_profile(project_path, editor_version, None, app_state)
 }
 
-#[tauri::command]
-pub fn cmd_add_project(project_path: PathBuf, and_open: bool, app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<Project, errors::AnyError> {
+// launch_profile overrides the project's own pick and the default profile
+pub fn open_project_with_profile(project_path: PathBuf, editor_version: String, launch_profile: Option<String>, app_state: &AppState) -> Result<EditorResolution, errors::AnyError> {
+  if !project_path.exists() {
+    return Err(errors::project_not_found(&project_path));
+  }
+  
+  let profile = launch::resolve_profile(&app_state.prefs()?, &project_path, launch_profile.as_deref())?;
+  
+  let mut resolution = editor::resolve_editor(&editor_version, app_state)?;
+  
+  // the caller may have picked a different editor than the one the project was saved with
+  let project_version = load(&project_path)
+    .map(|x| x.version)
+    .unwrap_or(editor_version);
+  resolution.change = VersionChange::between(&project_version, &resolution.editor.version);
+  resolution.warning = resolution.change.warning(&project_version, &resolution.editor.version);
+  resolution.requested = project_version;
+  
+  process::ensure_closed(&project_path, app_state)?;
+  
+  let args = launch::build_arguments(&project_path, profile.as_ref());
+  let env = profile.map(|x| x.env).unwrap_or_default();
+  let log_path = launch::get_log_file(&project_path, &args);
+  let child = editor::spawn(resolution.editor.version.clone(), args, &env, app_state)?;
+  process::track(app_state, child, project_path.clone(), resolution.editor.version.clone(), log_path)?;
+
+  update_project_open_time(app_state, project_path)?;
+  Ok(resolution)
+}
+
+// register an existing project folder, newest projects go first
+pub fn add_project(project_path: PathBuf, app_state: &AppState) -> Result<Project, errors::AnyError> {
   if !Path::new(&project_path).exists() {
-    return Err(errors::io_not_found("Invalid project path"));
+    return Err(errors::project_not_found(&project_path));
   }
   
   {
     let projects = app::get_projects(&app_state)?;
     if projects.iter().any(|x| x.path == project_path) {
-      return Err(errors::str_error("Project already exists"));
+      return Err(errors::project_exists(&project_path));
     }
   }
   
@@ -236,35 +286,93 @@ pub fn cmd_add_project(project_path: PathBuf, and_open: bool, app_handle: tauri:
 
   {
     let mut projects = app_state.projects.lock()
-      .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+      .map_err(|_| errors::state_locked("projects"))?;
     projects.insert(0, project.clone());
 
-    app::save_projects_to_disk(&projects, &app_handle)?;
+    app::save_projects_to_disk(&projects, &app_state.config_dir)?;
+  }
+
+  Ok(project)
+}
+
+// files inside of a project that are worth copying, relative to the project root.
+// skips hidden files, generated folders and anything sitting directly in the root
+pub fn list_project_files(project_path: impl AsRef<Path>) -> Vec<PathBuf> {
+  let project_path = project_path.as_ref();
+  let walk = walkdir::WalkDir::new(project_path);
+  
+  fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry
+      .file_name()
+      .to_str()
+      .map(|s| s.starts_with('.'))
+      .unwrap_or(false)
+  }
+  
+  fn is_banned(entry: &walkdir::DirEntry) -> bool {
+    let banned_names = ["Library", "obj", "Logs", "Temp", "UserSettings"];
+    banned_names
+      .iter()
+      .any(|s| entry.file_name().to_str().unwrap().starts_with(s))
   }
+  
+  walk
+    .into_iter()
+    .filter_entry(|e| !is_hidden(e) && !is_banned(e))
+    .filter_map(|x| x.ok())
+    .filter(|x| x.depth() > 1)
+    .filter_map(|x| x.path().strip_prefix(project_path).ok().map(|x| x.to_path_buf()))
+    .collect()
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_get_default_project_path(app_state: tauri::State<AppState>) -> Result<String, errors::AnyError> {
+  let prefs = app::get_prefs(&app_state)?;
+  let new_project_path = prefs.new_project_path
+    .ok_or(errors::pref_missing(PrefsKey::NewProjectPath))?
+    .to_str()
+    .ok_or(errors::str_error("Invalid new_project_path"))?
+    .to_string();
+  Ok(new_project_path)
+}
+
+#[tauri::command]
+pub async fn cmd_remove_missing_projects(app_state: tauri::State<'_, AppState>) -> Result<Vec<Project>, errors::AnyError> {
+  let removed_projects = remove_missing_projects(&app_state)?;
+  let projects = app::get_projects(&app_state)?;
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
+  Ok(removed_projects)
+}
+
+#[tauri::command]
+pub fn cmd_add_project(project_path: PathBuf, and_open: bool, app_state: tauri::State<AppState>) -> Result<Project, errors::AnyError> {
+  let project = add_project(project_path.clone(), &app_state)?;
 
   if and_open {
     let editor_version = &project.version;
-    update_project_open_time(&app_state, project_path.clone(), &app_handle)?;
-    open_project(project_path, editor_version.to_string(), &app_state, &app_handle)?;
+    update_project_open_time(&app_state, project_path.clone())?;
+    open_project(project_path, editor_version.to_string(), &app_state)?;
   }
 
   Ok(project)
 }
 
 #[tauri::command]
-pub fn cmd_remove_project(project_path: PathBuf, app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+pub fn cmd_remove_project(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
   if !Path::new(&project_path).exists() {
-    return Err(errors::io_not_found("Invalid project path"));
+    return Err(errors::project_not_found(&project_path));
   }
   
   let projects = {
     let mut projects = app_state.projects.lock()
-      .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+      .map_err(|_| errors::state_locked("projects"))?;
     projects.retain(|x| x.path != project_path);
     projects
   };
   
-  app::save_projects_to_disk(&projects, &app_handle)?;
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
   
   Ok(())
 }
@@ -278,30 +386,29 @@ pub async fn cmd_get_projects(app_state: tauri::State<'_, AppState>) -> Result<V
 
 #[tauri::command]
 pub async fn cmd_get_projects_on_page(app_state: tauri::State<'_, AppState>, page: usize, per_page_count: usize, search_options: SearchOptions) -> Result<Vec<Project>, errors::AnyError> {
-  let projects = get_projects_on_page(&app_state, page, per_page_count, search_options)?;
+  let projects = get_projects_on_page(app_state.inner(), page, per_page_count, search_options)?;
   Ok(projects)
 }
 
 #[tauri::command]
-pub fn cmd_open_project_in_editor(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, project_path: PathBuf, editor_version: String) -> Result<(), errors::AnyError> {
-  open_project(project_path, editor_version, &app_state, &app_handle)?;
-  Ok(())
+pub fn cmd_open_project_in_editor(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, project_path: PathBuf, editor_version: String, launch_profile: Option<String>) -> Result<EditorResolution, errors::AnyError> {
+  let resolution = open_project_with_profile(project_path.clone(), editor_version, launch_profile, &app_state)?;
+  if let Some(session) = process::get_session(&project_path, &app_state)? {
+    if let Err(err) = app_handle.emit_all(process::EDITOR_STARTED_EVENT, session) {
+      println!("Failed to emit editor start: {}", err);
+    }
+  }
+  Ok(resolution)
 }
 
 #[tauri::command]
-pub fn cmd_change_project_editor_version(app_handle: tauri::AppHandle, app_state: tauri::State<AppState>, project_path: PathBuf, editor_version: String) -> Result<(), errors::AnyError> {
-  let mut projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
-  
-  let project = projects
-    .iter_mut()
-    .find(|x| x.path == project_path)
-    .ok_or(errors::str_error("Project not found"))?;
-  
-  project.version = editor_version;
-  app::save_projects_to_disk(&projects, &app_handle)?;
-  
-  Ok(())
+pub fn cmd_get_usage_report(app_state: tauri::State<AppState>) -> Result<Vec<ProjectUsage>, errors::AnyError> {
+  get_usage_report(app_state.inner())
+}
+
+#[tauri::command]
+pub fn cmd_change_project_editor_version(app_state: tauri::State<AppState>, project_path: PathBuf, editor_version: String) -> Result<VersionSwitch, errors::AnyError> {
+  version_switch::switch_editor_version(project_path, editor_version, &app_state)
 }
 
 #[tauri::command]
@@ -315,121 +422,67 @@ pub fn cmd_fetch_project_thumbnail(project_path: PathBuf) -> Result<String, erro
 }
 
 #[tauri::command]
-pub fn cmd_pin_project(project_path: PathBuf, app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+pub fn cmd_pin_project(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
   let mut projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("projects"))?;
   
   let project = projects
     .iter_mut()
     .find(|x| x.path == project_path)
-    .ok_or(errors::str_error("Project not found"))?;
+    .ok_or(errors::project_not_found(&project_path))?;
   
   project.is_pinned = true;
   println!("{} pinned", project.path.display());
-  app::save_projects_to_disk(&projects, &app_handle)?;
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
   
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_unpin_project(project_path: PathBuf, app_handle: tauri::AppHandle, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
+pub fn cmd_unpin_project(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<(), errors::AnyError> {
   let mut projects = app_state.projects.lock()
-    .map_err(|_| errors::str_error("Failed to get projects. Is it locked?"))?;
+    .map_err(|_| errors::state_locked("projects"))?;
   
   let project = projects
     .iter_mut()
     .find(|x| x.path == project_path)
-    .ok_or(errors::str_error("Project not found"))?;
+    .ok_or(errors::project_not_found(&project_path))?;
   
   project.is_pinned = false;
   println!("{} unpinned", project.path.display());
-  app::save_projects_to_disk(&projects, &app_handle)?;
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
   
   Ok(())
 }
 
 #[tauri::command]
-pub fn cmd_is_open_in_editor(project_path: PathBuf, editor_version: String, app_state: tauri::State<AppState>) -> Result<bool, errors::AnyError> {
-  use window_titles::{Connection, ConnectionTrait};
-  let connection = Connection::new()
-    .map_err(|_| errors::str_error("Failed to get connection"))?;
-  let titles = connection.window_titles()
-    .map_err(|_| errors::str_error("Failed to get window titles"))?;
-
-  println!("{:?}", titles);
-
-  let project_name = project_path
-    .file_name()
-    .ok_or(errors::str_error("Invalid project path"))?
-    .to_str()
-    .ok_or(errors::str_error("Invalid project path"))?
-    .to_string();
-
-  let project_name = format!("{} - ", project_name);
-
-  // needs to start with project_name and contain Unity editor_version near the end
-  // todo: make sure this works fine on other platforms
-  let contains_title = titles
-    .iter()
-    .any(|x| x.starts_with(&project_name) && x.contains(format!("Unity {}", editor_version).as_str()));
-  Ok(contains_title)
+pub fn cmd_is_open_in_editor(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<OpenState, errors::AnyError> {
+  process::get_open_state(&project_path, &app_state)
 }
 
 #[tauri::command]
 pub fn cmd_load_project_files_tree(project_path: PathBuf) -> Result<io_utils::FileDir, errors::AnyError> {
-  let project_path_path = project_path.as_path();
-  let walk = walkdir::WalkDir::new(project_path_path);
-  // let all_files = walk
-  //   .into_iter()
-  //   .filter_map(|x| x.and(|y| y.path()))
-  //   .collect::<Vec<_>>();
-
   let mut root = io_utils::dir("package");
 
   // io_utils::build_tree(&mut root, &vec!["package.json".to_string()], 0);
   
   // let project_dir = std::path::PathBuf::from("ProjectData~");
   
-  fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    entry
-      .file_name()
-      .to_str()
-      .map(|s| s.starts_with('.'))
-      .unwrap_or(false)
-  }
-  
-  fn is_banned(entry: &walkdir::DirEntry) -> bool {
-    let banned_names = ["Library", "obj", "Logs", "Temp", "UserSettings"];
-    banned_names
-      .iter()
-      .any(|s| entry.file_name().to_str().unwrap().starts_with(s))
-  }
-  
-  for file in walk.into_iter().filter_entry(|e| !is_hidden(e) && !is_banned(e)).filter_map(|x| x.ok()) {
-    let file_path = file.path();
-    let file_path = file_path.strip_prefix(project_path_path);
-
-    if let Ok(file_path) = file_path {
-      let depth = file.depth();
-      if depth <= 1 {
-        continue;
+  for file_path in list_project_files(&project_path) {
+    // let file_path = project_dir.join(file_path);
+    let path_split = file_path
+      .components()
+      .filter_map(|x| {
+      if let std::path::Component::Normal(x) = x {
+        Some(x.to_str().and_then(|x| Some(x.to_string())))
+      } else {
+        None
       }
-
-      // let file_path = project_dir.join(file_path);
-      let path_split = file_path
-        .components()
-        .filter_map(|x| {
-        if let std::path::Component::Normal(x) = x {
-          Some(x.to_str().and_then(|x| Some(x.to_string())))
-        } else {
-          None
-        }
-      })
-        .map(|x| x.unwrap())
-        .collect::<Vec<_>>();
-      
-      io_utils::build_tree(&mut root, &path_split, 0);
-    }
+    })
+      .map(|x| x.unwrap())
+      .collect::<Vec<_>>();
+    
+    io_utils::build_tree(&mut root, &path_split, 0);
   }
 
   root.sort();
@@ -441,7 +494,7 @@ pub fn cmd_load_project_files_tree(project_path: PathBuf) -> Result<io_utils::Fi
 }
 
 #[tauri::command]
-pub async fn cmd_load_project_packages(app: tauri::AppHandle, app_state: tauri::State<'_, AppState>, project_path: PathBuf, editor_version: String) -> Result<TgzPackageJson, errors::AnyError> {
+pub async fn cmd_load_project_packages(app_state: tauri::State<'_, AppState>, project_path: PathBuf, editor_version: String) -> Result<TgzPackageJson, errors::AnyError> {
   let packages_path = project_path
     .join("Packages")
     .join("manifest")
@@ -468,6 +521,6 @@ pub async fn cmd_load_project_packages(app: tauri::AppHandle, app_state: tauri::
     }
   };
   
-  let package = crate::template::extract_packages(&app, &app_state, &editor_version, &package_json_content, &packages_lock_content)?;
+  let package = crate::template::extract_packages(app_state.inner(), &editor_version, &package_json_content, &packages_lock_content, &packages_path, &packages_lock_path)?;
   Ok(package)
 }
\ No newline at end of file
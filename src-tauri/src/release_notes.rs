@@ -0,0 +1,325 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor::{self, UnityEditorInstall},
+  errors, io_utils,
+  prefs::Prefs,
+  version::{self, UnityVersion},
+};
+
+// how deep the Documentation folder sits, e.g. <version>/Editor/Data/Documentation
+const DOCS_SEARCH_DEPTH: usize = 4;
+// how deep release notes sit inside it, e.g. Documentation/en/Manual/ReleaseNotes.html
+const NOTES_SEARCH_DEPTH: usize = 4;
+// a slow mirror shouldn't hold up the release notes view
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+// a mirror that just failed isn't asked again for this long, unless refreshing
+const FAILED_FETCH_RETRY_MILLIS: u128 = 5 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReleaseNotesSource {
+  // shipped with the editor's documentation module
+  Bundled { path: PathBuf },
+  // the release_notes_url pref, a file path or url
+  Mirror { url: String },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNotes {
+  pub version: String,
+  pub source: ReleaseNotesSource,
+  // plain text, html is stripped when stored
+  pub text: String,
+  pub fetched_at: u128,
+}
+
+// a remote fetch that didn't work out, for the url it was made against
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedFetch {
+  pub url: String,
+  pub failed_at: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNotesMatch {
+  pub version: String,
+  pub lines: Vec<String>,
+}
+
+// <cache_dir>/release_notes/<version><extension>. the version comes from the frontend
+// and ends up in a file name, so only a real one is let through
+fn get_notes_file_path(editor_version: &str, extension: &str, ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  let version = editor_version.parse::<UnityVersion>()?;
+  Ok(io_utils::get_cache_dir(ctx)?
+    .join("release_notes")
+    .join(format!("{}{}", version, extension)))
+}
+
+// <cache_dir>/release_notes/<version>.json
+fn get_cache_path(editor_version: &str, ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  get_notes_file_path(editor_version, ".json", ctx)
+}
+
+pub fn get_cached(editor_version: &str, ctx: &impl HubContext) -> Option<ReleaseNotes> {
+  let path = get_cache_path(editor_version, ctx).ok()?;
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|x| serde_json::from_str(&x).ok())
+}
+
+// <cache_dir>/release_notes/<version>.failed.json
+fn get_failed_fetch_path(editor_version: &str, ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  get_notes_file_path(editor_version, ".failed.json", ctx)
+}
+
+pub fn record_failed_fetch(editor_version: &str, url: &str, ctx: &impl HubContext) -> Result<(), errors::AnyError> {
+  let failed = FailedFetch { url: url.to_string(), failed_at: io_utils::now_millis() };
+  let path = get_failed_fetch_path(editor_version, ctx)?;
+  fs::create_dir_all(path.parent().unwrap_or(&path))?;
+  io_utils::write_atomic(&path, serde_json::to_string_pretty(&failed)?)?;
+  Ok(())
+}
+
+// a changed mirror url is tried right away
+pub fn recently_failed(editor_version: &str, url: &str, ctx: &impl HubContext) -> bool {
+  get_failed_fetch_path(editor_version, ctx)
+    .ok()
+    .and_then(|x| fs::read_to_string(x).ok())
+    .and_then(|x| serde_json::from_str::<FailedFetch>(&x).ok())
+    .is_some_and(|x| x.url == url && io_utils::now_millis().saturating_sub(x.failed_at) < FAILED_FETCH_RETRY_MILLIS)
+}
+
+fn decode_entities(text: &str) -> String {
+  text
+    .replace("&nbsp;", " ")
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&#39;", "'")
+    .replace("&amp;", "&")
+}
+
+// block tags start a new line, every other tag goes, blank lines are dropped
+pub fn html_to_text(html: &str) -> String {
+  let mut text = String::new();
+  let mut rest = html;
+  while let Some(start) = rest.find('<') {
+    text.push_str(&rest[..start]);
+    let Some(end) = rest[start..].find('>') else {
+      rest = "";
+      break;
+    };
+    let tag = rest[start + 1..start + end].trim_start_matches('/').to_lowercase();
+    let name = tag.split(|x: char| x.is_whitespace() || x == '/').next().unwrap_or_default();
+    if matches!(name, "p" | "br" | "li" | "div" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+      text.push('\n');
+    }
+    if name == "li" && !rest[start + 1..].starts_with('/') {
+      text.push_str("- ");
+    }
+    rest = &rest[start + end + 1..];
+  }
+  text.push_str(rest);
+
+  decode_entities(&text)
+    .lines()
+    .map(|x| x.trim())
+    .filter(|x| !x.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn is_html(path_or_url: &str) -> bool {
+  let lower = path_or_url.to_lowercase();
+  lower.ends_with(".html") || lower.ends_with(".htm")
+}
+
+// lower is a better match, None when it isn't release notes. the manual's
+// "what's new" pages are feature overviews, not notes
+fn notes_file_rank(path: &Path) -> Option<u8> {
+  let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase().replace(['-', '_', ' '], "");
+  let extension = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+  if !matches!(extension.as_str(), "html" | "htm" | "txt" | "md") {
+    return None;
+  }
+  if stem == "releasenotes" {
+    Some(0)
+  } else if stem.contains("releasenotes") {
+    Some(1)
+  } else if stem.contains("changelog") {
+    Some(2)
+  } else {
+    None
+  }
+}
+
+// release notes inside the documentation module, None when it isn't installed.
+// the best named file wins, ties go to the first path so it's the same one every time
+pub fn find_bundled(editor: &UnityEditorInstall) -> Option<PathBuf> {
+  let root = editor::get_root_folder(&editor.exe_path)?;
+  walkdir::WalkDir::new(root)
+    .max_depth(DOCS_SEARCH_DEPTH)
+    .into_iter()
+    .filter_entry(|x| x.file_type().is_dir())
+    .filter_map(|x| x.ok())
+    .filter(|x| x.file_name() == "Documentation")
+    .flat_map(|x| walkdir::WalkDir::new(x.path())
+      .max_depth(NOTES_SEARCH_DEPTH)
+      .into_iter()
+      .filter_map(|y| y.ok()))
+    .filter(|x| x.file_type().is_file())
+    .filter_map(|x| notes_file_rank(x.path()).map(|y| (y, x.into_path())))
+    .min()
+    .map(|x| x.1)
+}
+
+// the pref with {version} filled in
+pub fn get_mirror_url(editor_version: &str, prefs: &Prefs) -> Option<String> {
+  prefs.release_notes_url
+    .as_ref()
+    .filter(|x| !x.trim().is_empty())
+    .map(|x| x.replace("{version}", editor_version))
+}
+
+fn is_remote(url: &str) -> bool {
+  url.starts_with("http://") || url.starts_with("https://")
+}
+
+pub fn save(editor_version: &str, source: ReleaseNotesSource, contents: &str, ctx: &impl HubContext) -> Result<ReleaseNotes, errors::AnyError> {
+  let html = match &source {
+    ReleaseNotesSource::Bundled { path } => is_html(&path.to_string_lossy()) || contents.trim_start().starts_with('<'),
+    ReleaseNotesSource::Mirror { url } => is_html(url) || contents.trim_start().starts_with('<'),
+  };
+  let notes = ReleaseNotes {
+    version: editor_version.to_string(),
+    source,
+    text: if html { html_to_text(contents) } else { contents.trim().to_string() },
+    fetched_at: io_utils::now_millis(),
+  };
+
+  let path = get_cache_path(editor_version, ctx)?;
+  fs::create_dir_all(path.parent().unwrap_or(&path))?;
+  io_utils::write_atomic(&path, serde_json::to_string_pretty(&notes)?)?;
+  Ok(notes)
+}
+
+// cache, then the bundled docs, then a mirror on disk. remote mirrors are left to the caller
+pub fn get_release_notes(editor_version: &str, refresh: bool, ctx: &impl HubContext) -> Result<ReleaseNotes, errors::AnyError> {
+  // it's filled into the mirror url too, so anything but a version is refused up front
+  editor_version.parse::<UnityVersion>()?;
+  if !refresh {
+    if let Some(notes) = get_cached(editor_version, ctx) {
+      return Ok(notes);
+    }
+  }
+
+  if let Some(path) = ctx.find_editor(editor_version).ok().as_ref().and_then(find_bundled) {
+    let contents = String::from_utf8_lossy(&fs::read(&path)?).to_string();
+    return save(editor_version, ReleaseNotesSource::Bundled { path }, &contents, ctx);
+  }
+
+  let url = get_mirror_url(editor_version, &ctx.prefs()?)
+    .filter(|x| !is_remote(x))
+    .ok_or_else(|| errors::release_notes_not_found(editor_version))?;
+  let path = url.strip_prefix("file://").unwrap_or(&url);
+  let contents = match fs::read(path) {
+    Ok(contents) => contents,
+    // the mirror just doesn't have this version
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(errors::release_notes_not_found(editor_version)),
+    Err(err) => return Err(errors::release_notes_fetch_failed(&url, &err.to_string())),
+  };
+  save(editor_version, ReleaseNotesSource::Mirror { url: url.clone() }, &String::from_utf8_lossy(&contents), ctx)
+}
+
+// keyword search over every installed version's notes, newest version first.
+// versions without notes are skipped
+pub fn search(query: &str, ctx: &impl HubContext) -> Result<Vec<ReleaseNotesMatch>, errors::AnyError> {
+  let query = query.trim().to_lowercase();
+  if query.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let mut versions = ctx.editors()?
+    .into_iter()
+    .map(|x| x.version)
+    .collect::<Vec<_>>();
+  versions.sort_by(|a, b| version::compare(b, a));
+  versions.dedup();
+
+  Ok(versions
+    .into_iter()
+    .filter_map(|x| get_release_notes(&x, false, ctx).ok())
+    .map(|x| ReleaseNotesMatch {
+      lines: x.text
+        .lines()
+        .filter(|y| y.to_lowercase().contains(&query))
+        .map(|y| y.to_string())
+        .collect(),
+      version: x.version,
+    })
+    .filter(|x| !x.lines.is_empty())
+    .collect())
+}
+
+async fn fetch_remote(url: &str) -> Result<String, errors::AnyError> {
+  use tauri::api::http::{ClientBuilder, HttpRequestBuilder, ResponseType};
+
+  let to_error = |x: tauri::api::Error| errors::release_notes_fetch_failed(url, &x.to_string());
+  let client = ClientBuilder::new()
+    .connect_timeout(FETCH_TIMEOUT)
+    .build()
+    .map_err(to_error)?;
+  let request = HttpRequestBuilder::new("GET", url)
+    .map_err(to_error)?
+    .response_type(ResponseType::Text)
+    .timeout(FETCH_TIMEOUT);
+  let response = client.send(request).await.map_err(to_error)?;
+  if !response.status().is_success() {
+    return Err(errors::release_notes_fetch_failed(url, &format!("Server responded with {}", response.status().as_u16())));
+  }
+
+  let data = response.read().await.map_err(to_error)?.data;
+  Ok(data.as_str().unwrap_or_default().to_string())
+}
+
+// local sources first, then the mirror over http
+async fn get_or_fetch(editor_version: &str, refresh: bool, app_state: &AppState) -> Result<ReleaseNotes, errors::AnyError> {
+  let local = get_release_notes(editor_version, refresh, app_state);
+  let remote = get_mirror_url(editor_version, &app_state.prefs()?).filter(|x| is_remote(x));
+  let (Err(errors::AnyError::ReleaseNotesNotFound { .. }), Some(url)) = (&local, remote) else {
+    return local;
+  };
+  if !refresh && recently_failed(editor_version, &url, app_state) {
+    return Err(errors::release_notes_fetch_failed(&url, "Failed recently, try refreshing"));
+  }
+
+  match fetch_remote(&url).await {
+    Ok(contents) => save(editor_version, ReleaseNotesSource::Mirror { url }, &contents, app_state),
+    Err(err) => {
+      record_failed_fetch(editor_version, &url, app_state)?;
+      Err(err)
+    }
+  }
+}
+
+// commands
+
+#[tauri::command]
+pub async fn cmd_get_release_notes(editor_version: String, refresh: Option<bool>, app_state: tauri::State<'_, AppState>) -> Result<ReleaseNotes, errors::AnyError> {
+  get_or_fetch(&editor_version, refresh.unwrap_or(false), &app_state).await
+}
+
+// only what's on disk is searched, a remote mirror is fetched when a version's notes are opened
+#[tauri::command]
+pub async fn cmd_search_release_notes(query: String, app_state: tauri::State<'_, AppState>) -> Result<Vec<ReleaseNotesMatch>, errors::AnyError> {
+  search(&query, app_state.inner())
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, io::Read, path::{self, Path, PathBuf}};
 use flate2::read::GzDecoder;
-use crate::{app::{self, AppState}, editor::EditorPackageManagerManifestPackage, errors, io_utils};
+use crate::{app::{self, AppState}, context::HubContext, editor::EditorPackageManagerManifestPackage, errors, io_utils, prefs::PrefsKey};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,7 +21,7 @@ pub struct TgzPackageJson {
   host: Option<String>,
   unity: Option<String>,
   description: Option<String>,
-  dependencies: Option<HashMap<String, String>>,
+  pub dependencies: Option<HashMap<String, String>>,
   _upm: Option<UPM>,
   upm_ci: Option<UpmCi>,
   repository: Option<Repository>,
@@ -99,13 +99,8 @@ pub struct EditorVersionPackageList {
 //   pub category: String,
 // }
 
-pub fn get_core_templates_path(editor_version: String, app_state: &tauri::State<AppState>) -> Result<PathBuf, errors::AnyError> {
-  let editor = app_state.editors.lock()
-    .map_err(|_| errors::str_error("Failed to get editors. Is it locked?"))?
-    .iter()
-    .find(|x| x.version == editor_version)
-    .ok_or(errors::str_error("Invalid editor version"))?
-    .clone();
+pub fn get_core_templates_path(editor_version: String, ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  let editor = ctx.find_editor(&editor_version)?;
   
   let templates_path = crate::editor::get_package_manager_folder(&editor)?
     .join("ProjectTemplates");
@@ -113,8 +108,8 @@ pub fn get_core_templates_path(editor_version: String, app_state: &tauri::State<
   Ok(templates_path)
 }
 
-pub fn get_core_templates(editor_version: String, app_state: &tauri::State<AppState>) -> Result<Vec<SurfaceTemplate>, errors::AnyError> {
-  let core_template_path = get_core_templates_path(editor_version.clone(), &app_state)?;
+pub fn get_core_templates(editor_version: String, ctx: &impl HubContext) -> Result<Vec<SurfaceTemplate>, errors::AnyError> {
+  let core_template_path = get_core_templates_path(editor_version.clone(), ctx)?;
   let files = std::fs::read_dir(&core_template_path)
     .map_err(|_| errors::io_not_found("Invalid core template path"))?
     .filter_map(|x| x.ok())
@@ -129,33 +124,33 @@ pub fn get_core_templates(editor_version: String, app_state: &tauri::State<AppSt
   Ok(files)
 }
 
-fn get_info_from_file_name(path: impl AsRef<Path>) -> Result<(String, String), errors::AnyError> {
+pub fn get_info_from_file_name(path: impl AsRef<Path>) -> Result<(String, String), errors::AnyError> {
   let file_name = path.as_ref().file_name()
     .ok_or(errors::str_error("Invalid template file name"))?
     .to_str()
+    .ok_or(errors::str_error("Invalid template file name"))?
+    .strip_suffix(".tgz")
     .ok_or(errors::str_error("Invalid template file name"))?;
   let last_dash = file_name.rfind('-')
     .ok_or(errors::str_error("Invalid template file name"))?;
   
   let name = &file_name[0..last_dash];
-  let version = &file_name[(last_dash + 1)..(file_name.len() - 4)];
+  let version = &file_name[(last_dash + 1)..];
   
   Ok((name.to_string(), version.to_string()))
 }
 
-pub fn get_user_templates_path(app_state: &tauri::State<AppState>) -> Result<PathBuf, errors::AnyError> {
-  let appdata_path = app_state.prefs.lock()
-    .map_err(|_| errors::str_error("Failed to get prefs. Is it locked?"))?
+pub fn get_user_templates_path(ctx: &impl HubContext) -> Result<PathBuf, errors::AnyError> {
+  let appdata_path = ctx.prefs()?
     .hub_appdata_path
-    .clone()
-    .ok_or(errors::str_error("hub_appdata_path not set"))?;
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?;
   
-  let templates_path = appdata_path.join("templates");
+  let templates_path = appdata_path.join("Templates");
   Ok(templates_path)
 }
 
-pub fn read_user_templates_manifest(editor_version: String, app_state: &tauri::State<AppState>) -> Result<serde_json::Map<String, serde_json::Value>, errors::AnyError> {
-  let user_templates_path = get_user_templates_path(&app_state)?;
+pub fn read_user_templates_manifest(editor_version: String, ctx: &impl HubContext) -> Result<serde_json::Map<String, serde_json::Value>, errors::AnyError> {
+  let user_templates_path = get_user_templates_path(ctx)?;
   let json_path = user_templates_path
     .join("manifest")
     .with_extension("json");
@@ -163,35 +158,35 @@ pub fn read_user_templates_manifest(editor_version: String, app_state: &tauri::S
   let json = std::fs::read_to_string(&json_path)
     .map_err(|_| errors::io_not_found("Invalid user template manifest"))?;
   let manifest: HashMap<String, serde_json::Value> = serde_json::from_str(&json)
-    .map_err(|_| errors::str_error("Invalid user template manifest"))?;
+    .map_err(|_| errors::manifest_corrupt(&json_path))?;
   
   if !manifest.contains_key(&editor_version) {
     return Ok(serde_json::Map::new());
   }
   
   let editor_value = manifest.get(&editor_version)
-    .ok_or(errors::str_error("Invalid editor version"))?;
+    .ok_or(errors::manifest_corrupt(&json_path))?;
   
   if !editor_value.is_object() {
-    return Err(errors::str_error("Invalid editor value"));
+    return Err(errors::manifest_corrupt(&json_path));
   }
   
   let deps = editor_value.get("dependencies")
-    .ok_or(errors::str_error("Invalid editor value"))?;
+    .ok_or(errors::manifest_corrupt(&json_path))?;
   
   if !deps.is_object() {
-    return Err(errors::str_error("Invalid editor value"));
+    return Err(errors::manifest_corrupt(&json_path));
   }
   
   let values = deps.as_object()
-    .ok_or(errors::str_error("Invalid editor value"))?;
+    .ok_or(errors::manifest_corrupt(&json_path))?;
   Ok(values.clone())
 }
 
-pub fn get_user_templates(editor_version: String, app_state: &tauri::State<AppState>) -> Result<Vec<SurfaceTemplate>, errors::AnyError> {
-  let templates = read_user_templates_manifest(editor_version.clone(), &app_state)?;
+pub fn get_user_templates(editor_version: String, ctx: &impl HubContext) -> Result<Vec<SurfaceTemplate>, errors::AnyError> {
+  let templates = read_user_templates_manifest(editor_version.clone(), ctx)?;
   
-  let user_templates_path = get_user_templates_path(&app_state)?;
+  let user_templates_path = get_user_templates_path(ctx)?;
   let files = std::fs::read_dir(&user_templates_path)
     .map_err(|_| errors::io_not_found("Invalid user template path"))?
     .filter_map(|x| x.ok())
@@ -209,9 +204,8 @@ pub fn get_user_templates(editor_version: String, app_state: &tauri::State<AppSt
   Ok(files.collect())
 }
 
-pub fn read_editor_version_packages(app: &tauri::AppHandle, editor_version: &String) -> Result<EditorVersionPackageList, errors::AnyError> {
-  let cache_dir = app.path_resolver().app_cache_dir()
-    .ok_or(errors::str_error("Invalid app cache dir"))?
+pub fn read_editor_version_packages(ctx: &impl HubContext, editor_version: &String) -> Result<EditorVersionPackageList, errors::AnyError> {
+  let cache_dir = ctx.cache_dir()
     .join("templates");
 
   std::fs::create_dir_all(&cache_dir)?;
@@ -228,13 +222,24 @@ pub fn read_editor_version_packages(app: &tauri::AppHandle, editor_version: &Str
   let json = std::fs::read_to_string(&editor_version_json_path)
     .map_err(|_| errors::io_not_found("Invalid editor-version.json"))?;
   let editor_version_contents: EditorVersionPackageList = serde_json::from_str(&json)
-    .map_err(|_| errors::str_error("Invalid editor-version.json"))?;
+    .map_err(|_| errors::manifest_corrupt(&editor_version_json_path))?;
   Ok(editor_version_contents)
 }
 
-pub fn write_editor_version_packages(app: &tauri::AppHandle, editor_version: &String, editor_version_contents: &EditorVersionPackageList) -> Result<(), errors::AnyError> {
-  let cache_dir = app.path_resolver().app_cache_dir()
-    .ok_or(errors::str_error("Invalid app cache dir"))?
+pub fn remove_editor_version_packages(ctx: &impl HubContext, editor_version: &str) -> Result<(), errors::AnyError> {
+  let editor_version_json_path = ctx.cache_dir()
+    .join("templates")
+    .join(editor_version.replace(".", "_"))
+    .with_extension("json");
+
+  if editor_version_json_path.exists() {
+    std::fs::remove_file(&editor_version_json_path)?;
+  }
+  Ok(())
+}
+
+pub fn write_editor_version_packages(ctx: &impl HubContext, editor_version: &String, editor_version_contents: &EditorVersionPackageList) -> Result<(), errors::AnyError> {
+  let cache_dir = ctx.cache_dir()
     .join("templates");
 
   let editor_version_json_path = cache_dir
@@ -242,16 +247,15 @@ pub fn write_editor_version_packages(app: &tauri::AppHandle, editor_version: &St
     .join(editor_version.replace(".", "_"))
     .with_extension("json");
 
-  let json = serde_json::to_string_pretty(&editor_version_contents)
-    .map_err(|_| errors::str_error("Invalid editor-version.json"))?;
-  std::fs::write(&editor_version_json_path, json)
-    .map_err(|_| errors::str_error("Invalid editor-version.json"))?;
+  let json = serde_json::to_string_pretty(&editor_version_contents)?;
+  std::fs::write(&editor_version_json_path, json)?;
   Ok(())
 }
 
-pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppState>, editor_version: &String, package_json_contents: &String, packages_lock_contents: &String) -> Result<TgzPackageJson, errors::AnyError> {
+// the paths are where the contents came from, a corrupt one is named in the error
+pub fn extract_packages(ctx: &impl HubContext, editor_version: &String, package_json_contents: &String, packages_lock_contents: &String, package_json_path: &Path, packages_lock_path: &Path) -> Result<TgzPackageJson, errors::AnyError> {
   let mut package_json: TgzPackageJson = serde_json::from_str(&package_json_contents)
-    .map_err(|_| errors::str_error("Invalid package.json"))?;
+    .map_err(|_| errors::manifest_corrupt(package_json_path))?;
 
   let mut found_deps = HashMap::new();
   let mut found_raw_deps = HashMap::new();
@@ -267,7 +271,7 @@ pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppStat
   
   if !packages_lock_contents.is_empty() {
     let package_lock_json: PackageLockJson = serde_json::from_str(&packages_lock_contents)
-      .map_err(|_| errors::str_error("Invalid package-lock.json"))?;
+      .map_err(|_| errors::manifest_corrupt(packages_lock_path))?;
     
     for (key, value) in package_lock_json.dependencies.iter() {
       found_raw_deps.insert(key.clone(), value.clone());
@@ -286,7 +290,7 @@ pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppStat
       }
     }
   } else {
-    if let Ok(manifest) = crate::editor::read_package_manager_manifest(editor_version.clone(), &app_state) {
+    if let Ok(manifest) = crate::editor::read_package_manager_manifest(editor_version.clone(), ctx) {
       manifest
         .packages
         .iter()
@@ -311,7 +315,7 @@ pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppStat
   }
 
   // cache all found_deps
-  let mut editor_version_contents = read_editor_version_packages(&app, &editor_version)?;
+  let mut editor_version_contents = read_editor_version_packages(ctx, &editor_version)?;
 
   for (key, value) in found_raw_deps {
     editor_version_contents
@@ -325,7 +329,7 @@ pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppStat
       .insert(key.clone(), value);
   }
 
-  write_editor_version_packages(&app, &editor_version, &editor_version_contents)?;
+  write_editor_version_packages(ctx, &editor_version, &editor_version_contents)?;
 
   // println!("found deps: {:?}", found_deps);
   
@@ -340,12 +344,11 @@ pub fn extract_packages(app: &tauri::AppHandle, app_state: &tauri::State<AppStat
   Ok(package_json)
 }
 
-pub fn extract_template_information(app: &tauri::AppHandle, app_state: &tauri::State<AppState>, surface_template: &SurfaceTemplate) -> Result<TgzPackageJsonRecord, errors::AnyError> {
+pub fn extract_template_information(ctx: &impl HubContext, surface_template: &SurfaceTemplate) -> Result<TgzPackageJsonRecord, errors::AnyError> {
   let file_name = surface_template.path.file_name()
     .ok_or(errors::str_error("Invalid template file name"))?;
 
-  let cache_dir = app.path_resolver().app_cache_dir()
-    .ok_or(errors::str_error("Invalid app cache dir"))?
+  let cache_dir = ctx.cache_dir()
     .join("templates");
   
   let cache_dir = cache_dir.join(file_name);
@@ -382,7 +385,7 @@ pub fn extract_template_information(app: &tauri::AppHandle, app_state: &tauri::S
     
     tar
       .entries()
-      .map_err(|_| errors::str_error("Invalid template tgz"))?
+      .map_err(|_| errors::template_invalid(&surface_template.path))?
       .filter_map(|x| x.ok())
       .filter(|x| x.path().is_ok_and(|x| &*x == package_path) || x.path().is_ok_and(|x| &*x == package_lock_path))
       .for_each(|mut x| {
@@ -416,7 +419,9 @@ pub fn extract_template_information(app: &tauri::AppHandle, app_state: &tauri::S
       }
     };
 
-    let package_json = extract_packages(app, app_state, &surface_template.editor_version, &package_json_contents, &package_lock_json)?;
+    // read from the cache, or straight out of the template archive
+    let source_path = if package_json_path.exists() { package_json_path.as_path() } else { surface_template.path.as_path() };
+    let package_json = extract_packages(ctx, &surface_template.editor_version, &package_json_contents, &package_lock_json, source_path, &surface_template.path)?;
     
     // if let Some(package_lock_json_contents) = package_lock_json_contents {
     //   let mut found_deps = HashMap::new();
@@ -511,15 +516,13 @@ pub fn extract_template_information(app: &tauri::AppHandle, app_state: &tauri::S
       disk_size_bytes: io_utils::file_size(surface_template.path.clone()).unwrap_or(0),
     };
     
-    let json = serde_json::to_string(&package_json)
-      .map_err(|_| errors::str_error("Invalid package.json"))?;
-    std::fs::write(&package_json_path, json)
-      .map_err(|_| errors::str_error("Invalid package.json"))?;
+    let json = serde_json::to_string(&package_json)?;
+    std::fs::write(&package_json_path, json)?;
     
     return Ok(package_record);
   }
   
-  Err(errors::str_error("Could not find package.json in template tgz"))
+  Err(errors::template_invalid(&surface_template.path))
 }
 
 fn editor_package_manifest_package_to_dependency(editor_package_manifest: &EditorPackageManagerManifestPackage) -> Option<PackageLockJsonDependency> {
@@ -566,24 +569,16 @@ fn detect_unity_pipeline(tgz_package: &TgzPackageJson) -> Vec<UnityPipeline> {
   pipelines
 }
 
-fn extract_file_paths(app: &tauri::AppHandle, surface_template: &SurfaceTemplate) -> Result<io_utils::FileDir, errors::AnyError> {
-  let file_name = surface_template.path.file_name()
-    .ok_or(errors::str_error("Invalid template file name"))?;
-  
-  let cache_dir = app.path_resolver().app_cache_dir()
-    .ok_or(errors::str_error("Invalid app cache dir"))?
-    .join("templates")
-    .join(file_name);
-  std::fs::create_dir_all(&cache_dir)?;
-  
+// every path inside of a template tgz, minus the template's Library folder
+pub fn read_template_entry_paths(template_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, errors::AnyError> {
   // need to extract tgz file, so dumb
-  let tar_gz = std::fs::File::open(&surface_template.path)?;
+  let tar_gz = std::fs::File::open(template_path.as_ref())?;
   let tar_decoder = GzDecoder::new(tar_gz);
   let mut tar = tar::Archive::new(tar_decoder);
   
   let mut valid_entries = tar
     .entries()
-    .map_err(|_| errors::str_error("Invalid template tgz"))?
+    .map_err(|_| errors::template_invalid(&template_path))?
     .filter_map(|x| x.ok());
   
   let mut file_paths = Vec::new();
@@ -592,7 +587,7 @@ fn extract_file_paths(app: &tauri::AppHandle, surface_template: &SurfaceTemplate
   for entry in valid_entries.into_iter() {
     let path = entry
       .path()
-      .map_err(|_| errors::str_error("Invalid template tgz"))?
+      .map_err(|_| errors::template_invalid(&template_path))?
       .to_path_buf();
     
     let skip = {
@@ -616,6 +611,20 @@ fn extract_file_paths(app: &tauri::AppHandle, surface_template: &SurfaceTemplate
       file_paths.push(path);
     }
   }
+
+  Ok(file_paths)
+}
+
+fn extract_file_paths(ctx: &impl HubContext, surface_template: &SurfaceTemplate) -> Result<io_utils::FileDir, errors::AnyError> {
+  let file_name = surface_template.path.file_name()
+    .ok_or(errors::str_error("Invalid template file name"))?;
+  
+  let cache_dir = ctx.cache_dir()
+    .join("templates")
+    .join(file_name);
+  std::fs::create_dir_all(&cache_dir)?;
+  
+  let file_paths = read_template_entry_paths(&surface_template.path)?;
   
   // file_paths.sort_by(|a, b| {
   //   let a_extension = a.extension();
@@ -651,24 +660,24 @@ fn extract_file_paths(app: &tauri::AppHandle, surface_template: &SurfaceTemplate
 
 #[tauri::command]
 pub async fn cmd_get_surface_templates(editor_version: String, app_state: tauri::State<'_, AppState>) -> Result<Vec<SurfaceTemplate>, errors::AnyError> {
-  let mut templates = get_core_templates(editor_version.clone(), &app_state)?;
-  templates.extend(get_user_templates(editor_version.clone(), &app_state)?);
+  let mut templates = get_core_templates(editor_version.clone(), app_state.inner())?;
+  templates.extend(get_user_templates(editor_version.clone(), app_state.inner())?);
   Ok(templates)
 }
 
 #[tauri::command]
-pub async fn cmd_get_template_information(app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>, surface_template: SurfaceTemplate) -> Result<TgzPackageJsonRecord, errors::AnyError> {
-  let template = extract_template_information(&app_handle, &app_state, &surface_template)?;
+pub async fn cmd_get_template_information(app_state: tauri::State<'_, AppState>, surface_template: SurfaceTemplate) -> Result<TgzPackageJsonRecord, errors::AnyError> {
+  let template = extract_template_information(app_state.inner(), &surface_template)?;
   Ok(template)
 }
 
 #[tauri::command]
-pub async fn cmd_get_template_file_paths(app_handle: tauri::AppHandle, surface_template: SurfaceTemplate) -> Result<io_utils::FileDir, errors::AnyError> {
-  let mut paths = extract_file_paths(&app_handle, &surface_template)?;
+pub async fn cmd_get_template_file_paths(app_state: tauri::State<'_, AppState>, surface_template: SurfaceTemplate) -> Result<io_utils::FileDir, errors::AnyError> {
+  let mut paths = extract_file_paths(app_state.inner(), &surface_template)?;
   paths.sort();
   
   let paths = paths.children.get(0)
-    .ok_or(errors::str_error("Invalid template tgz"))?;
+    .ok_or(errors::template_invalid(&surface_template.path))?;
   
   let mut paths = *paths.clone();
   let mut id = 0u64;
@@ -683,21 +692,22 @@ pub fn cmd_delete_template(app_state: tauri::State<'_, AppState>, surface_templa
   
   let prefs = app::get_prefs(&app_state)?;
   let template_manifest_path = &prefs.hub_appdata_path
-    .ok_or(errors::str_error("hub_appdata_path not set"))?
+    .ok_or(errors::pref_missing(PrefsKey::HubAppDataPath))?
     .join("Templates")
     .join("manifest.json");
   
   let template_manifest_str = std::fs::read_to_string(&template_manifest_path)?;
-  let mut template_manifest_json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&template_manifest_str)?;
+  let mut template_manifest_json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&template_manifest_str)
+    .map_err(|_| errors::manifest_corrupt(template_manifest_path))?;
   
   if !template_manifest_json.contains_key(&editor_version) { 
     return Ok(());
   }
   
   let editor_version_entry = template_manifest_json.get_mut(&editor_version)
-    .ok_or(errors::str_error("Failed to get editor version entry"))?
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?
     .as_object_mut()
-    .ok_or(errors::str_error("Failed to get editor version entry"))?;
+    .ok_or(errors::manifest_corrupt(template_manifest_path))?;
   
   if let Some(dependency_map) = editor_version_entry.get_mut("dependencies") {
     if let Some(dependency_map) = dependency_map.as_object_mut() {
@@ -0,0 +1,374 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use tauri::Manager;
+
+use crate::{
+  app::{self, AppState},
+  context::HubContext,
+  errors,
+  jobs::{self, Job, JobEvent, JobKind, JobRequest, TestPlatform},
+};
+
+pub const TEST_REPORTS_MIGRATIONS: &[app::Migration] = &[app::migrate_to_versioned_file];
+
+// event name the frontend gets the parsed report on, after the job's own finished event
+pub const TEST_REPORT_EVENT: &str = "test-report";
+
+// reports kept per project, oldest are dropped first
+const MAX_REPORTS_PER_PROJECT: usize = 20;
+
+static REPORTS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum TestResult {
+  Passed,
+  Failed,
+  Skipped,
+  Inconclusive,
+}
+
+impl TestResult {
+  // nunit writes "Failed", "Failed(Child)", "Skipped:Ignored" and so on
+  fn parse(value: &str) -> Self {
+    let value = value
+      .split(|c| c == '(' || c == ':')
+      .next()
+      .unwrap_or_default();
+    match value {
+      "Passed" => TestResult::Passed,
+      "Failed" => TestResult::Failed,
+      "Skipped" | "Ignored" => TestResult::Skipped,
+      _ => TestResult::Inconclusive,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestFailure {
+  pub message: String,
+  pub stack_trace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+  pub name: String,
+  pub full_name: String,
+  pub result: TestResult,
+  // seconds
+  pub duration: f64,
+  pub failure: Option<TestFailure>,
+}
+
+// a fixture, the innermost suite that holds test cases
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSuite {
+  pub name: String,
+  pub full_name: String,
+  pub duration: f64,
+  pub cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunReport {
+  // id of the job that ran the tests
+  pub id: u128,
+  pub platform: Option<TestPlatform>,
+  pub editor_version: String,
+  pub result: TestResult,
+  pub total: usize,
+  pub passed: usize,
+  pub failed: usize,
+  pub skipped: usize,
+  pub inconclusive: usize,
+  pub duration: f64,
+  pub suites: Vec<TestSuite>,
+  pub finished_at: u128,
+}
+
+impl TestRunReport {
+  pub fn cases(&self) -> impl Iterator<Item = &TestCase> {
+    self.suites.iter().flat_map(|x| x.cases.iter())
+  }
+
+  fn failing_names(&self) -> HashSet<&str> {
+    self.cases()
+      .filter(|x| x.result == TestResult::Failed)
+      .map(|x| x.full_name.as_str())
+      .collect()
+  }
+}
+
+// what changed between the two most recent runs, by test full name
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunComparison {
+  pub previous_id: Option<u128>,
+  pub latest_id: u128,
+  pub newly_failing: Vec<String>,
+  pub still_failing: Vec<String>,
+  pub fixed: Vec<String>,
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+}
+
+// project path -> reports, oldest first
+pub type TestReports = HashMap<PathBuf, Vec<TestRunReport>>;
+
+fn attribute_f64(node: &roxmltree::Node, name: &str) -> f64 {
+  node.attribute(name)
+    .and_then(|x| x.parse().ok())
+    .unwrap_or_default()
+}
+
+fn child_text(node: &roxmltree::Node, name: &str) -> Option<String> {
+  node.children()
+    .find(|x| x.has_tag_name(name))
+    .and_then(|x| x.text())
+    .map(|x| x.trim().to_string())
+    .filter(|x| !x.is_empty())
+}
+
+fn parse_case(node: &roxmltree::Node) -> TestCase {
+  let name = node.attribute("name").unwrap_or_default().to_string();
+  let result = TestResult::parse(node.attribute("result").unwrap_or_default());
+  // failed tests keep their assertion message, skipped ones their ignore reason
+  let failure = node.children()
+    .find(|x| x.has_tag_name("failure") || x.has_tag_name("reason"))
+    .filter(|_| result != TestResult::Passed)
+    .map(|x| TestFailure {
+      message: child_text(&x, "message").unwrap_or_default(),
+      stack_trace: child_text(&x, "stack-trace"),
+    });
+
+  TestCase {
+    full_name: node.attribute("fullname").map(|x| x.to_string()).unwrap_or(name.clone()),
+    name,
+    result,
+    duration: attribute_f64(node, "duration"),
+    failure,
+  }
+}
+
+fn collect_suites(node: roxmltree::Node, suites: &mut Vec<TestSuite>) {
+  let cases = node.children()
+    .filter(|x| x.has_tag_name("test-case"))
+    .map(|x| parse_case(&x))
+    .collect::<Vec<_>>();
+
+  if !cases.is_empty() {
+    let name = node.attribute("name").unwrap_or_default().to_string();
+    suites.push(TestSuite {
+      full_name: node.attribute("fullname").map(|x| x.to_string()).unwrap_or(name.clone()),
+      name,
+      duration: attribute_f64(&node, "duration"),
+      cases,
+    });
+  }
+
+  for child in node.children().filter(|x| x.has_tag_name("test-suite")) {
+    collect_suites(child, suites);
+  }
+}
+
+// nunit 3 xml as written by unity's -testResults
+pub fn parse_results(xml: &str) -> Result<(TestResult, f64, Vec<TestSuite>), String> {
+  let document = roxmltree::Document::parse(xml).map_err(|x| x.to_string())?;
+  let root = document.root_element();
+  if !root.has_tag_name("test-run") {
+    return Err(format!("Expected <test-run>, found <{}>", root.tag_name().name()));
+  }
+
+  let mut suites = vec![];
+  for child in root.children().filter(|x| x.has_tag_name("test-suite")) {
+    collect_suites(child, &mut suites);
+  }
+
+  let result = TestResult::parse(root.attribute("result").unwrap_or_default());
+  Ok((result, attribute_f64(&root, "duration"), suites))
+}
+
+pub fn load_report(path: &Path, job: &Job) -> Result<TestRunReport, errors::AnyError> {
+  let xml = fs::read_to_string(path)?;
+  let (result, duration, suites) = parse_results(&xml).map_err(|x| errors::test_results_unreadable(path, &x))?;
+
+  let count = |result: TestResult| suites
+    .iter()
+    .flat_map(|x| x.cases.iter())
+    .filter(|x| x.result == result)
+    .count();
+
+  let platform = match job.kind {
+    JobKind::RunTests { platform, .. } => Some(platform),
+    _ => None,
+  };
+
+  Ok(TestRunReport {
+    id: job.id,
+    platform,
+    editor_version: job.editor_version.clone(),
+    result,
+    total: suites.iter().map(|x| x.cases.len()).sum(),
+    passed: count(TestResult::Passed),
+    failed: count(TestResult::Failed),
+    skipped: count(TestResult::Skipped),
+    inconclusive: count(TestResult::Inconclusive),
+    duration,
+    suites,
+    finished_at: job.finished_at.unwrap_or(job.started_at),
+  })
+}
+
+pub fn get_test_reports_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  app::get_save_path("test_reports", config_dir)
+}
+
+pub fn load_test_reports(ctx: &impl HubContext) -> anyhow::Result<TestReports> {
+  let path = get_test_reports_save_path(ctx.config_dir())?;
+  app::load_from_disk(path, TEST_REPORTS_MIGRATIONS)
+}
+
+fn save_report(project_path: &Path, report: &TestRunReport, ctx: &impl HubContext) -> Result<(), errors::AnyError> {
+  let _lock = REPORTS_LOCK.lock()
+    .map_err(|_| errors::state_locked("test reports"))?;
+
+  let mut reports = load_test_reports(ctx)?;
+  let project_reports = reports.entry(project_path.to_path_buf()).or_default();
+  project_reports.push(report.clone());
+  if project_reports.len() > MAX_REPORTS_PER_PROJECT {
+    let extra = project_reports.len() - MAX_REPORTS_PER_PROJECT;
+    project_reports.drain(..extra);
+  }
+
+  let path = get_test_reports_save_path(ctx.config_dir())?;
+  app::save_to_disk(path, &reports, TEST_REPORTS_MIGRATIONS)?;
+  Ok(())
+}
+
+pub fn get_project_reports(project_path: &Path, ctx: &impl HubContext) -> Result<Vec<TestRunReport>, errors::AnyError> {
+  let reports = load_test_reports(ctx)?;
+  Ok(reports.get(project_path).cloned().unwrap_or_default())
+}
+
+pub fn compare_runs(previous: Option<&TestRunReport>, latest: &TestRunReport) -> TestRunComparison {
+  let latest_failing = latest.failing_names();
+  let latest_names = latest.cases().map(|x| x.full_name.as_str()).collect::<HashSet<_>>();
+  let previous_failing = previous.map(|x| x.failing_names()).unwrap_or_default();
+  let previous_names = previous
+    .map(|x| x.cases().map(|y| y.full_name.as_str()).collect::<HashSet<_>>())
+    .unwrap_or_default();
+
+  let sorted = |names: Vec<&str>| {
+    let mut names = names.into_iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    names.sort();
+    names
+  };
+
+  TestRunComparison {
+    previous_id: previous.map(|x| x.id),
+    latest_id: latest.id,
+    newly_failing: sorted(latest_failing.difference(&previous_failing).copied().collect()),
+    still_failing: sorted(latest_failing.intersection(&previous_failing).copied().collect()),
+    // only tests that ran again and passed, a removed test isn't fixed
+    fixed: sorted(previous_failing
+      .iter()
+      .filter(|x| latest_names.contains(*x) && !latest_failing.contains(*x))
+      .copied()
+      .collect()),
+    added: sorted(latest_names.difference(&previous_names).copied().collect()),
+    removed: sorted(previous_names.difference(&latest_names).copied().collect()),
+  }
+}
+
+// the most recent run against the one before it on the same platform, edit and play mode
+// run different tests. every failure is new on a platform's first run
+pub fn compare_last_runs(project_path: &Path, ctx: &impl HubContext) -> Result<TestRunComparison, errors::AnyError> {
+  let reports = get_project_reports(project_path, ctx)?;
+  let (latest, rest) = reports
+    .split_last()
+    .ok_or_else(|| errors::no_test_runs(project_path))?;
+  let previous = rest
+    .iter()
+    .rev()
+    .find(|x| x.platform == latest.platform);
+  Ok(compare_runs(previous, latest))
+}
+
+// run the tests as a job, then parse and store the results it wrote
+pub fn run_tests(
+  job: Job,
+  env: &HashMap<String, String>,
+  app_state: &AppState,
+  emit: impl FnMut(JobEvent),
+) -> Result<(Job, Option<TestRunReport>), errors::AnyError> {
+  let job = jobs::run_job(job, env, app_state, emit)?;
+
+  // unity doesn't write results when it fails to start the run
+  let Some(results_path) = job.results_path.as_ref().filter(|x| x.is_file()) else {
+    return Ok((job, None));
+  };
+
+  let report = load_report(results_path, &job)?;
+  save_report(&job.project_path, &report, app_state)?;
+  Ok((job, Some(report)))
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_run_tests(
+  project_path: PathBuf,
+  platform: TestPlatform,
+  filter: Option<String>,
+  editor_version: Option<String>,
+  launch_profile: Option<String>,
+  app_handle: tauri::AppHandle,
+  app_state: tauri::State<AppState>,
+) -> Result<Job, errors::AnyError> {
+  let request = JobRequest {
+    project_path,
+    editor_version,
+    kind: JobKind::RunTests { platform, filter },
+    launch_profile,
+  };
+  let (job, env) = jobs::prepare_job(request, &app_state)?;
+
+  let running = job.clone();
+  std::thread::spawn(move || {
+    let app_state = app_handle.state::<AppState>();
+    let result = run_tests(running, &env, &app_state, |event| {
+      if let Err(err) = app_handle.emit_all(jobs::JOB_EVENT, event) {
+        println!("Failed to emit job event: {}", err);
+      }
+    });
+    match result {
+      Ok((_, Some(report))) => {
+        if let Err(err) = app_handle.emit_all(TEST_REPORT_EVENT, report) {
+          println!("Failed to emit test report: {}", err);
+        }
+      }
+      Ok((job, None)) => println!("Test job {} wrote no results", job.id),
+      Err(err) => println!("Test job failed: {}", err),
+    }
+  });
+
+  Ok(job)
+}
+
+#[tauri::command]
+pub fn cmd_get_test_reports(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<Vec<TestRunReport>, errors::AnyError> {
+  get_project_reports(&project_path, app_state.inner())
+}
+
+#[tauri::command]
+pub fn cmd_compare_last_test_runs(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<TestRunComparison, errors::AnyError> {
+  compare_last_runs(&project_path, app_state.inner())
+}
@@ -0,0 +1,235 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use tauri::Manager;
+
+use crate::{
+  app::AppState,
+  context::HubContext,
+  editor::{self, UnityEditorInstall},
+  errors,
+  process::{self, OpenState},
+  project, template,
+  version_switch::{self, VersionSwitch},
+};
+
+// event name the frontend gets deletion progress on
+pub const UNINSTALL_EVENT: &str = "editor-uninstall";
+
+// files removed between progress events
+const PROGRESS_EVERY: usize = 500;
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallProgress {
+  pub version: String,
+  pub removed: usize,
+  pub total: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Uninstall {
+  pub version: String,
+  pub install_path: PathBuf,
+  // projects moved to the reassigned editor, with their snapshots for rolling back
+  pub reassigned: Vec<VersionSwitch>,
+  pub removed_files: usize,
+  // false for a manually located editor outside the search roots, it's only forgotten
+  pub deleted: bool,
+}
+
+// projects that would break if the editor went away, registered ones and ones it has open right now
+pub fn get_dependent_projects(editor_version: &str, app_state: &AppState) -> Result<Vec<PathBuf>, errors::AnyError> {
+  let mut projects = app_state.projects()?
+    .into_iter()
+    .filter(|x| x.version == editor_version)
+    .map(|x| x.path)
+    .collect::<Vec<_>>();
+
+  let running = process::get_launched(app_state)?
+    .into_iter()
+    .chain(process::find_running_editors(app_state))
+    .filter(|x| x.editor_version.as_deref() == Some(editor_version))
+    .map(|x| x.project_path);
+  for project_path in running {
+    if !projects.contains(&project_path) {
+      projects.push(project_path);
+    }
+  }
+
+  Ok(projects)
+}
+
+// the folder holding the whole install, only when it's a <version> folder directly in a
+// search root. anything else, e.g. /Applications for /Applications/Unity.app, holds more
+// than the editor and is never deleted
+fn get_install_path(editor: &UnityEditorInstall, app_state: &AppState) -> Result<Option<PathBuf>, errors::AnyError> {
+  let install_path = editor::get_root_folder(&editor.exe_path)
+    .ok_or(errors::editor_invalid(&editor.exe_path))?;
+
+  let prefs = app_state.prefs()?;
+  let in_search_root = install_path
+    .parent()
+    .is_some_and(|x| editor::get_search_roots(&prefs).iter().any(|y| y == x));
+  let named_after_version = install_path
+    .file_name()
+    .is_some_and(|x| x.to_string_lossy() == editor.version);
+  Ok((in_search_root && named_after_version).then_some(install_path))
+}
+
+// the manual_editor_paths entries that resolve to this editor
+fn get_manual_paths(editor: &UnityEditorInstall, app_state: &AppState) -> Result<Vec<PathBuf>, errors::AnyError> {
+  Ok(app_state.prefs()?
+    .manual_editor_paths
+    .into_iter()
+    .filter(|x| editor::read_manual_editor(x).is_some_and(|y| y.exe_path == editor.exe_path))
+    .collect())
+}
+
+// deepest entries first, so every folder is empty by the time it's removed.
+// on failure the error comes with how many entries were already gone
+fn delete_with_progress(install_path: &Path, mut progress: impl FnMut(usize, usize)) -> Result<usize, (usize, errors::AnyError)> {
+  let entries = walkdir::WalkDir::new(install_path)
+    .contents_first(true)
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|x| (0, errors::AnyError::Io(x.into())))?;
+  let total = entries.len();
+
+  for (index, entry) in entries.iter().enumerate() {
+    let removed = if entry.file_type().is_dir() {
+      fs::remove_dir(entry.path())
+    } else {
+      fs::remove_file(entry.path())
+    };
+    removed.map_err(|x| (index, x.into()))?;
+
+    let removed = index + 1;
+    if removed % PROGRESS_EVERY == 0 || removed == total {
+      progress(removed, total);
+    }
+  }
+
+  Ok(total)
+}
+
+// newest first, returns the projects that are still on the new editor
+fn roll_back(switched: &[(PathBuf, VersionSwitch)], app_state: &AppState) -> Vec<PathBuf> {
+  switched
+    .iter()
+    .rev()
+    .filter(|(project_path, record)| version_switch::rollback(project_path.clone(), record.id, app_state).is_err())
+    .map(|(project_path, _)| project_path.clone())
+    .collect()
+}
+
+// put the projects back after `err`, the install is still whole. projects that
+// couldn't be put back are reported instead of `err`
+fn roll_back_or_report(version: &str, err: errors::AnyError, switched: &[(PathBuf, VersionSwitch)], app_state: &AppState) -> errors::AnyError {
+  let stuck = roll_back(switched, app_state);
+  if stuck.is_empty() {
+    err
+  } else {
+    errors::uninstall_incomplete(version, 0, stuck, &err.to_string())
+  }
+}
+
+// refuses while projects depend on the editor unless they can be moved to `reassign_to` first
+pub fn uninstall_editor(editor_version: &str, reassign_to: Option<&str>, app_state: &AppState, mut emit: impl FnMut(UninstallProgress)) -> Result<Uninstall, errors::AnyError> {
+  let editor = app_state.find_editor(editor_version)?;
+  let install_path = get_install_path(&editor, app_state)?;
+  let manual_paths = get_manual_paths(&editor, app_state)?;
+  if install_path.is_none() && manual_paths.is_empty() {
+    return Err(errors::editor_invalid(&editor.exe_path));
+  }
+
+  let dependents = get_dependent_projects(&editor.version, app_state)?;
+  let mut switched = vec![];
+  if !dependents.is_empty() {
+    let Some(reassign_to) = reassign_to.filter(|x| *x != editor.version) else {
+      return Err(errors::editor_in_use(&editor.version, dependents));
+    };
+
+    // an open project can't be switched underneath its editor, nor one with a lock left behind
+    let open = dependents
+      .iter()
+      .filter(|x| !matches!(process::get_open_state(x, app_state), Ok(OpenState::Closed)))
+      .cloned()
+      .collect::<Vec<_>>();
+    if !open.is_empty() {
+      return Err(errors::editor_in_use(&editor.version, open));
+    }
+
+    app_state.find_editor(reassign_to)?;
+    // projects that can't be switched, e.g. open from outside the hub, are checked before any is
+    let registered = app_state.projects()?;
+    let unswitchable = dependents
+      .iter()
+      .filter(|x| !registered.iter().any(|y| &y.path == *x) || project::load(x).is_err())
+      .cloned()
+      .collect::<Vec<_>>();
+    if !unswitchable.is_empty() {
+      return Err(errors::editor_in_use(&editor.version, unswitchable));
+    }
+
+    for project_path in dependents {
+      match version_switch::switch_editor_version(project_path.clone(), reassign_to.to_string(), app_state) {
+        Ok(record) => switched.push((project_path, record)),
+        Err(err) => return Err(roll_back_or_report(&editor.version, err, &switched, app_state)),
+      }
+    }
+  }
+
+  let removed_files = match &install_path {
+    Some(install_path) => {
+      let deleted = delete_with_progress(install_path, |removed, total| emit(UninstallProgress {
+        version: editor.version.clone(),
+        removed,
+        total,
+      }));
+      match deleted {
+        Ok(removed_files) => removed_files,
+        Err((0, err)) => return Err(roll_back_or_report(&editor.version, err, &switched, app_state)),
+        // the old editor is half gone, projects stay on the new one
+        Err((removed, err)) => {
+          let still_switched = switched.into_iter().map(|(x, _)| x).collect();
+          return Err(errors::uninstall_incomplete(&editor.version, removed, still_switched, &err.to_string()));
+        }
+      }
+    }
+    None => 0,
+  };
+
+  editor::remove_size_cache(&editor.exe_path, app_state)?;
+  template::remove_editor_version_packages(app_state, &editor.version)?;
+
+  for path in manual_paths {
+    editor::remove_manual_editor(path, app_state)?;
+  }
+  editor::refresh_editor_installs(app_state)?;
+
+  Ok(Uninstall {
+    version: editor.version,
+    deleted: install_path.is_some(),
+    install_path: install_path.unwrap_or(editor.exe_path),
+    reassigned: switched.into_iter().map(|(_, x)| x).collect(),
+    removed_files,
+  })
+}
+
+// commands
+
+#[tauri::command]
+pub async fn cmd_get_editor_dependent_projects(editor_version: String, app_state: tauri::State<'_, AppState>) -> Result<Vec<PathBuf>, errors::AnyError> {
+  get_dependent_projects(&editor_version, &app_state)
+}
+
+// progress arrives as editor-uninstall events
+#[tauri::command]
+pub async fn cmd_uninstall_editor(editor_version: String, reassign_to: Option<String>, app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<Uninstall, errors::AnyError> {
+  uninstall_editor(&editor_version, reassign_to.as_deref(), &app_state, |progress| {
+    if let Err(err) = app_handle.emit_all(UNINSTALL_EVENT, progress) {
+      println!("Failed to emit uninstall progress: {}", err);
+    }
+  })
+}
@@ -0,0 +1,161 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::errors;
+
+// the letter between the patch and build number, in release order
+// 2023.1.0a12 < 2023.1.0b3 < 2023.1.0c1 < 2023.1.0f1 < 2023.1.0p2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ReleaseStream {
+  Alpha,
+  Beta,
+  Candidate,
+  Final,
+  Patch,
+}
+
+impl ReleaseStream {
+  fn from_char(c: char) -> Option<Self> {
+    match c {
+      'a' => Some(ReleaseStream::Alpha),
+      'b' => Some(ReleaseStream::Beta),
+      'c' => Some(ReleaseStream::Candidate),
+      'f' => Some(ReleaseStream::Final),
+      'p' => Some(ReleaseStream::Patch),
+      _ => None,
+    }
+  }
+
+  fn as_char(&self) -> char {
+    match self {
+      ReleaseStream::Alpha => 'a',
+      ReleaseStream::Beta => 'b',
+      ReleaseStream::Candidate => 'c',
+      ReleaseStream::Final => 'f',
+      ReleaseStream::Patch => 'p',
+    }
+  }
+}
+
+// how unity labels a release in the hub
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ReleaseKind {
+  Alpha,
+  Beta,
+  Lts,
+  Tech,
+}
+
+// 2022.3.10f1, 6000.0.23f1, 2023.1.0a12, 2022.3.10f1c1
+// fields are in comparison order so Ord can be derived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct UnityVersion {
+  pub major: u32,
+  pub minor: u32,
+  pub patch: u32,
+  pub stream: ReleaseStream,
+  pub build: u32,
+  // the c<n> suffix of builds made for china, sorts after the global build
+  pub china_build: Option<u32>,
+}
+
+impl UnityVersion {
+  pub fn kind(&self) -> ReleaseKind {
+    match self.stream {
+      ReleaseStream::Alpha => ReleaseKind::Alpha,
+      ReleaseStream::Beta | ReleaseStream::Candidate => ReleaseKind::Beta,
+      ReleaseStream::Final | ReleaseStream::Patch => {
+        let is_lts = match self.major {
+          // 2017.4 through 2019.4
+          2017..=2019 => self.minor == 4,
+          // 2020.3 through 2022.3, 2023.3 was renamed unity 6 before it reached lts
+          2020..=2022 => self.minor == 3,
+          // 6000.0 onwards
+          6000.. => self.minor == 0,
+          _ => false,
+        };
+        if is_lts { ReleaseKind::Lts } else { ReleaseKind::Tech }
+      }
+    }
+  }
+
+  pub fn is_prerelease(&self) -> bool {
+    matches!(self.kind(), ReleaseKind::Alpha | ReleaseKind::Beta)
+  }
+
+  // same yearly line, e.g. 2022.3.x
+  pub fn same_minor(&self, other: &UnityVersion) -> bool {
+    self.major == other.major && self.minor == other.minor
+  }
+}
+
+impl FromStr for UnityVersion {
+  type Err = errors::AnyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || errors::version_invalid(s);
+
+    let mut parts = s.trim().splitn(3, '.');
+    let major = parts.next().ok_or_else(invalid)?.parse::<u32>().map_err(|_| invalid())?;
+    let minor = parts.next().ok_or_else(invalid)?.parse::<u32>().map_err(|_| invalid())?;
+    let rest = parts.next().ok_or_else(invalid)?;
+
+    // 10f1 -> 10, f, 1
+    let stream_index = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (patch, rest) = rest.split_at(stream_index);
+    let mut rest = rest.chars();
+    let stream = rest.next().and_then(ReleaseStream::from_char).ok_or_else(invalid)?;
+    // 1c1 -> 1, 1
+    let (build, china_build) = match rest.as_str().split_once('c') {
+      Some((build, china_build)) => (build, Some(china_build)),
+      None => (rest.as_str(), None),
+    };
+
+    if patch.is_empty() || build.is_empty() || china_build.is_some_and(|x| x.is_empty()) {
+      return Err(invalid());
+    }
+
+    Ok(UnityVersion {
+      major,
+      minor,
+      patch: patch.parse().map_err(|_| invalid())?,
+      stream,
+      build: build.parse().map_err(|_| invalid())?,
+      china_build: china_build.map(|x| x.parse()).transpose().map_err(|_| invalid())?,
+    })
+  }
+}
+
+impl fmt::Display for UnityVersion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{}.{}{}{}", self.major, self.minor, self.patch, self.stream.as_char(), self.build)?;
+    if let Some(china_build) = self.china_build {
+      write!(f, "c{}", china_build)?;
+    }
+    Ok(())
+  }
+}
+
+impl TryFrom<String> for UnityVersion {
+  type Error = errors::AnyError;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl From<UnityVersion> for String {
+  fn from(value: UnityVersion) -> Self {
+    value.to_string()
+  }
+}
+
+// order two version strings, anything that doesn't parse goes before every real version
+pub fn compare(a: &str, b: &str) -> Ordering {
+  match (a.parse::<UnityVersion>(), b.parse::<UnityVersion>()) {
+    (Ok(a), Ok(b)) => a.cmp(&b),
+    (Ok(_), Err(_)) => Ordering::Greater,
+    (Err(_), Ok(_)) => Ordering::Less,
+    (Err(_), Err(_)) => a.cmp(b),
+  }
+}
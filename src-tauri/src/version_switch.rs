@@ -0,0 +1,250 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+use crate::{
+  app::{self, AppState},
+  context::HubContext,
+  errors, io_utils,
+  process, project,
+};
+
+pub const VERSION_HISTORY_MIGRATIONS: &[app::Migration] = &[app::migrate_to_versioned_file];
+
+// files unity rewrites when a project changes editor, relative to the project root
+const SNAPSHOT_DIRS: [&str; 1] = ["ProjectSettings"];
+const SNAPSHOT_FILES: [&str; 2] = ["Packages/manifest.json", "Packages/packages-lock.json"];
+
+// one editor switch of a project, the snapshot holds the files from before it
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionSwitch {
+  pub id: u128,
+  pub from_version: String,
+  pub from_revision: Option<String>,
+  pub to_version: String,
+  pub to_revision: Option<String>,
+  pub snapshot_path: PathBuf,
+  pub switched_at: u128,
+  pub rolled_back_at: Option<u128>,
+}
+
+// project path -> switches, oldest first
+pub type VersionHistory = HashMap<PathBuf, Vec<VersionSwitch>>;
+
+pub fn get_version_history_save_path(config_dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+  app::get_save_path("version_history", config_dir)
+}
+
+pub fn load_version_history(ctx: &impl HubContext) -> anyhow::Result<VersionHistory> {
+  let path = get_version_history_save_path(ctx.config_dir())?;
+  app::load_from_disk(path, VERSION_HISTORY_MIGRATIONS)
+}
+
+fn save_version_history(history: &VersionHistory, ctx: &impl HubContext) -> anyhow::Result<()> {
+  let path = get_version_history_save_path(ctx.config_dir())?;
+  app::save_to_disk(path, history, VERSION_HISTORY_MIGRATIONS)
+}
+
+pub fn get_project_history(project_path: &Path, ctx: &impl HubContext) -> Result<Vec<VersionSwitch>, errors::AnyError> {
+  let history = load_version_history(ctx)?;
+  Ok(history.get(project_path).cloned().unwrap_or_default())
+}
+
+fn get_project_version_path(project_path: &Path) -> PathBuf {
+  project_path
+    .join("ProjectSettings")
+    .join("ProjectVersion")
+    .with_extension("txt")
+}
+
+// m_EditorVersionWithRevision: 2022.3.10f1 (ff3792e53c62)
+pub fn read_project_revision(project_path: &Path) -> Option<String> {
+  let contents = fs::read_to_string(get_project_version_path(project_path)).ok()?;
+  let line = contents
+    .lines()
+    .find_map(|x| x.strip_prefix("m_EditorVersionWithRevision:"))?;
+  let (_, after_paren) = line.rsplit_once('(')?;
+  let (revision, _) = after_paren.split_once(')')?;
+  Some(revision.trim().to_string()).filter(|x| !x.is_empty())
+}
+
+pub fn write_project_version(project_path: &Path, version: &str, revision: Option<&str>) -> Result<(), errors::AnyError> {
+  // without a known changeset unity fills it in on the next open
+  let with_revision = match revision {
+    Some(revision) => format!("{} ({})", version, revision),
+    None => version.to_string(),
+  };
+  let contents = format!(
+    "m_EditorVersion: {}\nm_EditorVersionWithRevision: {}\n",
+    version, with_revision,
+  );
+  io_utils::write_atomic(get_project_version_path(project_path), contents)?;
+  Ok(())
+}
+
+// <config_dir>/snapshots/<id>, the id is the time unless another switch in the same
+// millisecond already took it, e.g. an uninstall reassigning several projects
+fn create_snapshot_dir(config_dir: &Path) -> Result<(u128, PathBuf), errors::AnyError> {
+  let snapshots_path = config_dir.join("snapshots");
+  fs::create_dir_all(&snapshots_path)?;
+
+  let mut id = io_utils::now_millis();
+  loop {
+    let snapshot_path = snapshots_path.join(id.to_string());
+    match fs::create_dir(&snapshot_path) {
+      Ok(()) => return Ok((id, snapshot_path)),
+      Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+      Err(err) => return Err(err.into()),
+    }
+  }
+}
+
+// <config_dir>/snapshots/<id>/ mirrors the project layout
+fn take_snapshot(project_path: &Path, snapshot_path: &Path) -> Result<(), errors::AnyError> {
+  for dir in SNAPSHOT_DIRS {
+    let from = project_path.join(dir);
+    if from.is_dir() {
+      io_utils::copy_dir_all(&from, snapshot_path.join(dir))?;
+    }
+  }
+
+  for file in SNAPSHOT_FILES {
+    let from = project_path.join(file);
+    if from.is_file() {
+      let to = snapshot_path.join(file);
+      fs::create_dir_all(to.parent().unwrap_or(snapshot_path))?;
+      fs::copy(&from, &to)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn restore_snapshot(project_path: &Path, snapshot_path: &Path) -> Result<(), errors::AnyError> {
+  if !snapshot_path.is_dir() {
+    return Err(errors::snapshot_missing(snapshot_path));
+  }
+
+  for dir in SNAPSHOT_DIRS {
+    let from = snapshot_path.join(dir);
+    if !from.is_dir() {
+      continue;
+    }
+    let to = project_path.join(dir);
+    if to.exists() {
+      fs::remove_dir_all(&to)?;
+    }
+    io_utils::copy_dir_all(&from, &to)?;
+  }
+
+  // a file that didn't exist before the switch shouldn't exist after rolling back
+  for file in SNAPSHOT_FILES {
+    let from = snapshot_path.join(file);
+    let to = project_path.join(file);
+    if from.is_file() {
+      fs::create_dir_all(to.parent().unwrap_or(project_path))?;
+      fs::copy(&from, &to)?;
+    } else if to.is_file() {
+      fs::remove_file(&to)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn set_project_version(app_state: &AppState, project_path: &Path, version: &str) -> Result<(), errors::AnyError> {
+  let mut projects = app_state.projects.lock()
+    .map_err(|_| errors::state_locked("projects"))?;
+  let project = projects
+    .iter_mut()
+    .find(|x| x.path == project_path)
+    .ok_or(errors::project_not_found(project_path))?;
+  project.version = version.to_string();
+  app::save_projects_to_disk(&projects, &app_state.config_dir)?;
+  Ok(())
+}
+
+// snapshot the files an editor switch touches, then point the project at the new editor
+pub fn switch_editor_version(project_path: PathBuf, editor_version: String, app_state: &AppState) -> Result<VersionSwitch, errors::AnyError> {
+  let project = project::load(&project_path)?;
+  let editor = app_state.find_editor(&editor_version)?;
+  // the editor rewrites these files itself while it has the project
+  process::ensure_closed(&project_path, app_state)?;
+
+  if !app::get_projects(app_state)?.iter().any(|x| x.path == project_path) {
+    return Err(errors::project_not_found(&project_path));
+  }
+
+  let (id, snapshot_path) = create_snapshot_dir(&app_state.config_dir)?;
+  take_snapshot(&project_path, &snapshot_path)?;
+
+  let record = VersionSwitch {
+    id,
+    from_version: project.version.clone(),
+    from_revision: read_project_revision(&project_path),
+    to_version: editor.version.clone(),
+    to_revision: editor.revision.clone(),
+    snapshot_path,
+    switched_at: id,
+    rolled_back_at: None,
+  };
+
+  write_project_version(&project_path, &record.to_version, record.to_revision.as_deref())?;
+  set_project_version(app_state, &project_path, &record.to_version)?;
+
+  let mut history = load_version_history(app_state)?;
+  history
+    .entry(project_path)
+    .or_default()
+    .push(record.clone());
+  save_version_history(&history, app_state)?;
+
+  Ok(record)
+}
+
+// put back the files from before a switch, including the old ProjectVersion.txt.
+// only the newest switch that's still in place, an older snapshot would undo the ones after it
+pub fn rollback(project_path: PathBuf, id: u128, app_state: &AppState) -> Result<VersionSwitch, errors::AnyError> {
+  process::ensure_closed(&project_path, app_state)?;
+
+  let mut history = load_version_history(app_state)?;
+  let switches = history
+    .get_mut(&project_path)
+    .ok_or(errors::version_switch_not_found(&project_path, id))?;
+  let latest = switches
+    .iter()
+    .rev()
+    .find(|x| x.rolled_back_at.is_none())
+    .map(|x| x.id);
+  let record = switches
+    .iter_mut()
+    .find(|x| x.id == id)
+    .ok_or(errors::version_switch_not_found(&project_path, id))?;
+  if record.rolled_back_at.is_some() || latest != Some(id) {
+    return Err(errors::version_switch_not_latest(&project_path, id));
+  }
+
+  restore_snapshot(&project_path, &record.snapshot_path)?;
+  // the snapshot had no ProjectVersion.txt to restore, at least put the version back
+  if !record.snapshot_path.join("ProjectSettings").join("ProjectVersion.txt").is_file() {
+    write_project_version(&project_path, &record.from_version, record.from_revision.as_deref())?;
+  }
+  set_project_version(app_state, &project_path, &record.from_version)?;
+
+  record.rolled_back_at = Some(io_utils::now_millis());
+  let record = record.clone();
+  save_version_history(&history, app_state)?;
+
+  Ok(record)
+}
+
+// commands
+
+#[tauri::command]
+pub fn cmd_get_project_version_history(project_path: PathBuf, app_state: tauri::State<AppState>) -> Result<Vec<VersionSwitch>, errors::AnyError> {
+  get_project_history(&project_path, app_state.inner())
+}
+
+#[tauri::command]
+pub fn cmd_rollback_project_version(project_path: PathBuf, id: u128, app_state: tauri::State<AppState>) -> Result<VersionSwitch, errors::AnyError> {
+  rollback(project_path, id, &app_state)
+}
@@ -0,0 +1,305 @@
+// fixture generator for a fake unity install on disk
+// Hub/Editor/<version>/..., UnityHub/Templates/..., plus config and cache dirs
+#![allow(dead_code)]
+
+use std::{fs, os::unix::fs::PermissionsExt, path::{Path, PathBuf}};
+
+use nomnom_unity_hub::{
+  app::AppState,
+  context::HubContext,
+  editor::{self, UnityEditorInstall},
+  errors,
+  prefs::Prefs,
+  project::Project,
+};
+
+// stands in for the editor executable, `-createProject <path>` makes a bare project.
+// FAKE_EDITOR_LOG gets the arguments it was started with.
+// batch jobs write a couple of lines to -logFile and exit with FAKE_EDITOR_EXIT_CODE,
+// test runs copy FAKE_EDITOR_TEST_RESULTS to -testResults.
+// FAKE_EDITOR_SLEEP keeps it running like an open editor
+const FAKE_EDITOR_SCRIPT: &str = r#"#!/bin/sh
+if [ -n "$FAKE_EDITOR_LOG" ]; then
+  echo "$@" > "$FAKE_EDITOR_LOG.tmp" && mv "$FAKE_EDITOR_LOG.tmp" "$FAKE_EDITOR_LOG"
+fi
+log_file=""
+method=""
+results=""
+while [ $# -gt 0 ]; do
+  case "$1" in
+    -createProject) mkdir -p "$2/Assets" "$2/Packages" "$2/ProjectSettings"; shift ;;
+    -logFile) log_file="$2"; shift ;;
+    -executeMethod) method="$2"; shift ;;
+    -testResults) results="$2"; shift ;;
+  esac
+  shift
+done
+if [ -n "$log_file" ]; then
+  echo "Batchmode quit successfully invoked" > "$log_file"
+  echo "DisplayProgressbar: Compiling Scripts" >> "$log_file"
+  if [ -n "$method" ]; then
+    echo "Invoking $method" >> "$log_file"
+  fi
+  printf "Exiting batchmode" >> "$log_file"
+fi
+if [ -n "$results" ] && [ -n "$FAKE_EDITOR_TEST_RESULTS" ]; then
+  cp "$FAKE_EDITOR_TEST_RESULTS" "$results"
+fi
+if [ -n "$FAKE_EDITOR_SLEEP" ]; then
+  sleep "$FAKE_EDITOR_SLEEP"
+fi
+exit "${FAKE_EDITOR_EXIT_CODE:-0}"
+"#;
+
+pub struct FakeHub {
+  pub root: tempfile::TempDir,
+  pub prefs: Prefs,
+  pub projects: Vec<Project>,
+  config_dir: PathBuf,
+  cache_dir: PathBuf,
+}
+
+impl HubContext for FakeHub {
+  fn prefs(&self) -> Result<Prefs, errors::AnyError> {
+    Ok(self.prefs.clone())
+  }
+
+  // always scan, so tests see editors added after construction
+  fn editors(&self) -> Result<Vec<UnityEditorInstall>, errors::AnyError> {
+    editor::find_editor_installs(self)
+  }
+
+  fn projects(&self) -> Result<Vec<Project>, errors::AnyError> {
+    Ok(self.projects.clone())
+  }
+
+  fn config_dir(&self) -> &Path {
+    &self.config_dir
+  }
+
+  fn cache_dir(&self) -> &Path {
+    &self.cache_dir
+  }
+}
+
+impl FakeHub {
+  pub fn new() -> Self {
+    let root = tempfile::tempdir().unwrap();
+    let path = root.path().to_path_buf();
+
+    let prefs = Prefs {
+      new_project_path: Some(path.join("Projects")),
+      hub_path: None,
+      hub_editors_path: Some(path.join("Hub").join("Editor")),
+      hub_appdata_path: Some(path.join("UnityHub")),
+      project_sort_type: None,
+      editor_search_paths: Vec::new(),
+      manual_editor_paths: Vec::new(),
+      launch_profiles: Vec::new(),
+      default_launch_profile: None,
+      project_launch_profiles: Default::default(),
+      release_notes_url: None,
+    };
+
+    for dir in [
+      path.join("Projects"),
+      path.join("Hub").join("Editor"),
+      path.join("UnityHub").join("Templates"),
+      path.join("config"),
+      path.join("cache"),
+    ] {
+      fs::create_dir_all(dir).unwrap();
+    }
+    fs::write(path.join("UnityHub").join("Templates").join("manifest.json"), "{}").unwrap();
+
+    Self {
+      config_dir: path.join("config"),
+      cache_dir: path.join("cache"),
+      root,
+      prefs,
+      projects: Vec::new(),
+    }
+  }
+
+  pub fn path(&self) -> &Path {
+    self.root.path()
+  }
+
+  pub fn projects_dir(&self) -> PathBuf {
+    self.path().join("Projects")
+  }
+
+  pub fn editor_dir(&self, version: &str) -> PathBuf {
+    self.path().join("Hub").join("Editor").join(version)
+  }
+
+  pub fn package_manager_dir(&self, version: &str) -> PathBuf {
+    self.editor_dir(version)
+      .join("Unity.app")
+      .join("Contents")
+      .join("Resources")
+      .join("PackageManager")
+  }
+
+  pub fn user_templates_dir(&self) -> PathBuf {
+    self.path().join("UnityHub").join("Templates")
+  }
+
+  // Hub/Editor/<version>/modules.json + Unity.app/Contents/...
+  pub fn add_editor(&self, version: &str) -> PathBuf {
+    add_editor_at(&self.editor_dir(version), version)
+  }
+
+  // Projects/<name> on disk, not registered with any app state
+  pub fn add_project(&self, name: &str, editor_version: &str) -> PathBuf {
+    let project_path = self.projects_dir().join(name);
+    fs::create_dir_all(project_path.join("Assets")).unwrap();
+    fs::create_dir_all(project_path.join("ProjectSettings")).unwrap();
+    fs::write(
+      project_path.join("ProjectSettings").join("ProjectVersion.txt"),
+      format!("m_EditorVersion: {}\n", editor_version),
+    ).unwrap();
+    project_path
+  }
+
+  // the app's state in the config and cache dirs, with these prefs and the editors added so far
+  pub fn app_state(&self) -> AppState {
+    let app_state = AppState::load(self.config_dir.clone(), self.cache_dir.clone()).unwrap();
+    *app_state.prefs.lock().unwrap() = self.prefs.clone();
+    editor::refresh_editor_installs(&app_state).unwrap();
+    app_state
+  }
+
+  // ProjectTemplates/<name>-<version>.tgz inside the editor
+  pub fn add_core_template(&self, editor_version: &str, name: &str, version: &str) -> PathBuf {
+    let path = self.package_manager_dir(editor_version)
+      .join("ProjectTemplates")
+      .join(format!("{}-{}.tgz", name, version));
+    write_template_tgz(&path, name, version, editor_version);
+    path
+  }
+
+  // UnityHub/Templates/<name>-<version>.tgz, registered in manifest.json
+  pub fn add_user_template(&self, editor_version: &str, name: &str, version: &str) -> PathBuf {
+    let path = self.user_templates_dir().join(format!("{}-{}.tgz", name, version));
+    write_template_tgz(&path, name, version, editor_version);
+
+    let manifest_path = self.user_templates_dir().join("manifest.json");
+    let mut manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    manifest
+      .as_object_mut()
+      .unwrap()
+      .entry(editor_version.to_string())
+      .or_insert(serde_json::json!({ "dependencies": {} }))["dependencies"][name] = serde_json::json!(version);
+    fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    path
+  }
+}
+
+// an editor install at any folder, e.g. a second search root or a standalone install
+pub fn add_editor_at(editor_dir: &Path, version: &str) -> PathBuf {
+  let editor_dir = editor_dir.to_path_buf();
+  let contents = editor_dir.join("Unity.app").join("Contents");
+  let package_manager_dir = contents.join("Resources").join("PackageManager");
+
+  fs::create_dir_all(contents.join("Linux")).unwrap();
+  fs::create_dir_all(package_manager_dir.join("Editor")).unwrap();
+  fs::create_dir_all(package_manager_dir.join("ProjectTemplates")).unwrap();
+
+  let info_plist = format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n<dict>\n\t<key>CFBundleName</key>\n\t<string>Unity</string>\n\t<key>CFBundleVersion</key>\n\t<string>{}</string>\n\t<key>CFBundleGetInfoString</key>\n\t<string>Unity version {} (abc123def456)</string>\n</dict>\n</plist>\n",
+    version, version,
+  );
+  fs::write(contents.join("Info.plist"), info_plist).unwrap();
+
+  let modules = serde_json::json!([
+    {
+      "name": "Linux Build Support (IL2CPP)",
+      "id": "linux-il2cpp",
+      "description": "Allows building your Unity projects for the Linux-IL2CPP platform",
+      "category": "Platforms",
+      "visible": true,
+      "selected": true
+    },
+    {
+      "name": "WebGL Build Support",
+      "id": "webgl",
+      "description": "Allows building your Unity projects for the WebGL platform",
+      "category": "Platforms",
+      "visible": true,
+      "selected": false
+    }
+  ]);
+  fs::write(editor_dir.join("modules.json"), modules.to_string()).unwrap();
+
+  let manifest = serde_json::json!({
+    "schemaVersion": 1,
+    "packages": {
+      "com.unity.ugui": { "isDiscoverable": true, "version": "1.0.0", "isDefault": true },
+      "com.unity.timeline": { "isDiscoverable": true, "version": "1.7.5", "isDefault": true },
+      "com.unity.test-framework": { "isDiscoverable": true, "version": "1.1.33" },
+      "com.unity.modules.audio": { "mustBeBundled": true, "version": "1.0.0" }
+    }
+  });
+  fs::write(
+    package_manager_dir.join("Editor").join("manifest.json"),
+    manifest.to_string(),
+  ).unwrap();
+
+  let exe = contents.join("Linux").join("Unity");
+  fs::write(&exe, FAKE_EDITOR_SCRIPT).unwrap();
+  fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+
+  editor_dir
+}
+
+// same layout the hub ships: package/package.json + package/ProjectData~/...
+pub fn write_template_tgz(path: &Path, name: &str, version: &str, editor_version: &str) {
+  let package_json = serde_json::json!({
+    "name": name,
+    "displayName": "Fake Template",
+    "version": version,
+    "type": "template",
+    "host": "hub",
+    "unity": editor_version,
+    "description": "A template made for tests",
+    "dependencies": {
+      "com.unity.ugui": "1.0.0",
+      "com.unity.timeline": "1.7.5"
+    }
+  });
+  let manifest_json = serde_json::json!({
+    "dependencies": {
+      "com.unity.ugui": "1.0.0",
+      "com.unity.timeline": "1.7.5"
+    }
+  });
+  let packages_lock_json = serde_json::json!({
+    "dependencies": {
+      "com.unity.ugui": { "version": "1.0.0", "depth": 0, "source": "builtin", "dependencies": {} },
+      "com.unity.timeline": { "version": "1.7.6", "depth": 0, "source": "registry", "dependencies": {}, "url": "https://packages.unity.com" },
+      "com.unity.modules.audio": { "version": "1.0.0", "depth": 1, "source": "builtin", "dependencies": {} }
+    }
+  });
+  let project_settings = "%YAML 1.1\nPlayerSettings:\n  companyName: Unity Technologies\n  productName: Template\n";
+
+  let files: Vec<(&str, String)> = vec![
+    ("package/package.json", package_json.to_string()),
+    ("package/ProjectData~/Packages/manifest.json", manifest_json.to_string()),
+    ("package/ProjectData~/Packages/packages-lock.json", packages_lock_json.to_string()),
+    ("package/ProjectData~/Assets/Scenes/SampleScene.unity", "%YAML 1.1\n".to_string()),
+    ("package/ProjectData~/ProjectSettings/ProjectSettings.asset", project_settings.to_string()),
+  ];
+
+  let tgz = flate2::write::GzEncoder::new(fs::File::create(path).unwrap(), flate2::Compression::default());
+  let mut tar = tar::Builder::new(tgz);
+  for (entry, contents) in files {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, entry, contents.as_bytes()).unwrap();
+  }
+  tar.into_inner().unwrap().finish().unwrap();
+}
@@ -0,0 +1,160 @@
+// logs and crash dumps archived when a launched editor exits abnormally
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{collections::HashMap, fs, path::PathBuf, time::{Duration, SystemTime}};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  crash::{self, CrashSources},
+  errors,
+  launch::{self, LaunchProfile},
+  process::{self, EditorSession},
+  project,
+};
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+// open with its own -logFile and exit code, then wait for the editor and reap it
+fn open_and_exit(hub: &FakeHub, app_state: &AppState, project_path: &PathBuf, exit_code: &str) -> Vec<EditorSession> {
+  let log = hub.path().join("game.log");
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_EXIT_CODE".to_string(), exit_code.to_string());
+  launch::save_profile(LaunchProfile {
+    name: "logged".to_string(),
+    arguments: vec!["-logFile".to_string(), log.to_string_lossy().to_string()],
+    env,
+    project_path: None,
+  }, app_state).unwrap();
+
+  project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("logged".to_string()), app_state).unwrap();
+  for launched in app_state.processes.lock().unwrap().iter_mut() {
+    launched.child.wait().unwrap();
+  }
+  process::reap_exited(app_state).unwrap()
+}
+
+#[test]
+fn abnormal_exit_archives_the_log() {
+  let (hub, app_state, project_path) = setup();
+
+  let sessions = open_and_exit(&hub, &app_state, &project_path, "139");
+  assert!(sessions[0].crashed);
+  assert_eq!(sessions[0].log_path, Some(hub.path().join("game.log")));
+
+  let reports = crash::get_crash_reports(&project_path, &app_state).unwrap();
+  assert_eq!(reports.len(), 1);
+  let report = &reports[0];
+  assert_eq!(report.editor_version, "2022.3.10f1");
+  assert_eq!(report.exit_code, Some(139));
+  assert_eq!(report.files, vec![PathBuf::from("game.log")]);
+  assert!(report.archive_path.starts_with(hub.path().join("cache").join("crashes").join("Game")));
+  assert!(report.archive_path.join("game.log").is_file());
+  assert_eq!(report.log_tail.last().map(|x| x.as_str()), Some("Exiting batchmode"));
+
+  crash::delete_crash_report(&project_path, report.id, &app_state).unwrap();
+  assert!(crash::get_crash_reports(&project_path, &app_state).unwrap().is_empty());
+  let err = crash::delete_crash_report(&project_path, report.id, &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::CrashReportNotFound { .. }));
+}
+
+#[test]
+fn clean_exit_archives_nothing() {
+  let (hub, app_state, project_path) = setup();
+
+  let sessions = open_and_exit(&hub, &app_state, &project_path, "0");
+  assert!(!sessions[0].crashed);
+  assert!(crash::get_crash_reports(&project_path, &app_state).unwrap().is_empty());
+}
+
+#[test]
+fn only_crash_dumps_from_the_session_are_copied() {
+  let (hub, app_state, project_path) = setup();
+  let started_at = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .unwrap()
+    .as_millis();
+
+  let crash_dir = hub.path().join("Crashes");
+  let old = crash_dir.join("Crash_old");
+  fs::create_dir_all(&old).unwrap();
+  fs::File::open(&old).unwrap().set_modified(SystemTime::now() - Duration::from_secs(3600)).unwrap();
+  fs::create_dir_all(crash_dir.join("Crash_new")).unwrap();
+  fs::write(crash_dir.join("Crash_new").join("crash.dmp"), "dump").unwrap();
+
+  let log_dir = hub.path().join("Logs");
+  fs::create_dir_all(&log_dir).unwrap();
+  fs::write(log_dir.join("Editor.log"), (1..=80).map(|x| format!("line {}\n", x)).collect::<String>()).unwrap();
+  fs::write(log_dir.join("Editor-prev.log"), "previous run\n").unwrap();
+
+  let session = EditorSession {
+    pid: 1234,
+    project_path: project_path.clone(),
+    editor_version: "2022.3.10f1".to_string(),
+    log_path: None,
+    started_at,
+    ended_at: Some(started_at + 1000),
+    exit_code: None,
+    crashed: true,
+  };
+  let sources = CrashSources {
+    logs: vec![log_dir.join("Editor.log"), log_dir.join("Editor-prev.log")],
+    crash_dirs: vec![crash_dir],
+    ..Default::default()
+  };
+
+  let report = crash::archive_crash(&session, &sources, &app_state).unwrap();
+  assert_eq!(report.files, vec![
+    PathBuf::from("Editor.log"),
+    PathBuf::from("Editor-prev.log"),
+    PathBuf::from("Crashes").join("Crash_new"),
+  ]);
+  assert!(report.archive_path.join("Crashes").join("Crash_new").join("crash.dmp").is_file());
+  assert_eq!(report.log_tail.len(), 50);
+  assert_eq!(report.log_tail.first().map(|x| x.as_str()), Some("line 31"));
+}
+
+#[test]
+fn crash_folders_shared_with_other_apps_are_filtered() {
+  let (hub, app_state, project_path) = setup();
+  let started_at = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .unwrap()
+    .as_millis();
+
+  // the way DiagnosticReports looks on macOS
+  let reports_dir = hub.path().join("DiagnosticReports");
+  fs::create_dir_all(&reports_dir).unwrap();
+  fs::write(reports_dir.join("Unity-2026-10-18-101500.ips"), "unity").unwrap();
+  fs::write(reports_dir.join("Safari-2026-10-18-101501.ips"), "safari").unwrap();
+
+  let session = EditorSession {
+    pid: 1234,
+    project_path,
+    editor_version: "2022.3.10f1".to_string(),
+    log_path: None,
+    started_at,
+    ended_at: Some(started_at + 1000),
+    exit_code: None,
+    crashed: true,
+  };
+  let sources = CrashSources {
+    logs: vec![],
+    crash_dirs: vec![reports_dir],
+    crash_prefix: Some("Unity".to_string()),
+  };
+
+  let report = crash::archive_crash(&session, &sources, &app_state).unwrap();
+  assert_eq!(report.files, vec![PathBuf::from("Crashes").join("Unity-2026-10-18-101500.ips")]);
+}
@@ -0,0 +1,143 @@
+// classifying Editor.log lines and following a log as it grows
+mod common;
+
+use std::{
+  fs,
+  io::Write,
+  path::PathBuf,
+  sync::{atomic::{AtomicBool, Ordering}, mpsc, Arc},
+  time::Duration,
+};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  context::HubContext,
+  editor_log::{self, LogLineKind},
+  launch::{self, LaunchProfile},
+};
+
+const EDITOR_LOG: &str = "\
+[Package Manager] Done resolving packages
+Assets/Scripts/Player.cs(12,5): error CS0103: The name 'speed' does not exist in the current context
+Assets/Scripts/Enemy.cs(3,1): warning CS0414: The field 'Enemy.hp' is assigned but its value is never used
+NullReferenceException: Object reference not set to an instance of an object
+Player.Update () (at Assets/Scripts/Player.cs:42)
+
+An error occurred while resolving packages:
+  Project has invalid dependencies:
+    com.unity.foo: Package [com.unity.foo@1.0.0] cannot be found
+Refreshing native plugins compatible for Editor in 2.31 ms
+WARNING: Shader Unsupported: 'Hidden/Foo'";
+
+fn kinds(lines: &[editor_log::LogLine]) -> Vec<LogLineKind> {
+  lines.iter().map(|x| x.kind).collect()
+}
+
+#[test]
+fn classifies_errors_exceptions_warnings_and_package_errors() {
+  let lines = editor_log::classify_lines(EDITOR_LOG.lines().map(|x| x.to_string()));
+  assert_eq!(kinds(&lines), vec![
+    LogLineKind::Info,
+    LogLineKind::CompileError,
+    LogLineKind::Warning,
+    LogLineKind::Exception,
+    LogLineKind::Exception,
+    LogLineKind::Info,
+    LogLineKind::PackageError,
+    LogLineKind::PackageError,
+    LogLineKind::PackageError,
+    LogLineKind::Info,
+    LogLineKind::Warning,
+  ]);
+
+  assert_eq!(lines[1].file, Some(PathBuf::from("Assets/Scripts/Player.cs")));
+  assert_eq!((lines[1].line, lines[1].column), (Some(12), Some(5)));
+  assert_eq!(lines[2].file, Some(PathBuf::from("Assets/Scripts/Enemy.cs")));
+  // the stack frame points at the throwing line
+  assert_eq!(lines[4].file, Some(PathBuf::from("Assets/Scripts/Player.cs")));
+  assert_eq!(lines[4].line, Some(42));
+  assert_eq!(lines[4].number, 5);
+}
+
+#[test]
+fn reads_a_log_filtered_by_kind() {
+  let hub = FakeHub::new();
+  let path = hub.path().join("Editor.log");
+  fs::write(&path, EDITOR_LOG).unwrap();
+
+  let all = editor_log::read_log(&path, None).unwrap();
+  assert_eq!(all.len(), 11);
+  // the last line has no newline
+  assert_eq!(all.last().unwrap().kind, LogLineKind::Warning);
+
+  let errors = editor_log::read_log(&path, Some(&[LogLineKind::CompileError, LogLineKind::PackageError])).unwrap();
+  assert_eq!(errors.iter().map(|x| x.number).collect::<Vec<_>>(), vec![2, 7, 8, 9]);
+
+  assert!(editor_log::read_log(&hub.path().join("missing.log"), None).is_err());
+}
+
+#[test]
+fn lists_profile_log_files_for_a_project() {
+  let hub = FakeHub::new();
+  let project_path = hub.projects_dir().join("Game");
+  fs::create_dir_all(&project_path).unwrap();
+  let app_state = AppState::load(hub.path().join("config"), hub.path().join("cache")).unwrap();
+  let log_path = project_path.join("Logs").join("editor.log");
+
+  launch::save_profile(LaunchProfile {
+    name: "Logged".to_string(),
+    arguments: vec!["-logFile".to_string(), log_path.to_string_lossy().to_string()],
+    ..Default::default()
+  }, &app_state).unwrap();
+  launch::save_profile(LaunchProfile {
+    name: "Relative".to_string(),
+    arguments: vec!["-logFile".to_string(), "Logs/relative.log".to_string()],
+    ..Default::default()
+  }, &app_state).unwrap();
+  launch::save_profile(LaunchProfile {
+    name: "Stdout".to_string(),
+    arguments: vec!["-logFile".to_string(), "-".to_string()],
+    ..Default::default()
+  }, &app_state).unwrap();
+
+  let sources = editor_log::get_log_sources(Some(&project_path), &app_state).unwrap();
+  let logged = sources.iter().find(|x| x.name == "Logged").unwrap();
+  assert_eq!(logged.path, log_path);
+  assert!(!logged.exists);
+  assert!(!sources.iter().any(|x| x.name == "Stdout"));
+  // the editor is handed the same path, not one relative to wherever the app started
+  let relative = sources.iter().find(|x| x.name == "Relative").unwrap();
+  assert_eq!(relative.path, project_path.join("Logs").join("relative.log"));
+  let profile = launch::resolve_profile(&app_state.prefs().unwrap(), &project_path, Some("Relative")).unwrap();
+  let arguments = launch::build_arguments(&project_path, profile.as_ref());
+  assert_eq!(arguments.last().map(PathBuf::from), Some(relative.path.clone()));
+}
+
+#[test]
+fn tails_only_new_lines() {
+  let hub = FakeHub::new();
+  let path = hub.path().join("Editor.log");
+  fs::write(&path, "old line\n").unwrap();
+
+  let stop = Arc::new(AtomicBool::new(false));
+  let (sender, receiver) = mpsc::channel();
+  let handle = {
+    let path = path.clone();
+    let stop = stop.clone();
+    std::thread::spawn(move || editor_log::tail_log(&path, &stop, |lines| sender.send(lines).unwrap()))
+  };
+  std::thread::sleep(Duration::from_millis(100));
+
+  let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+  writeln!(file, "Assets/A.cs(1,1): error CS1002: ; expected").unwrap();
+  file.flush().unwrap();
+
+  let lines = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+  stop.store(true, Ordering::Relaxed);
+  handle.join().unwrap();
+
+  assert_eq!(lines.len(), 1);
+  assert_eq!(lines[0].kind, LogLineKind::CompileError);
+  assert_eq!(lines[0].file, Some(PathBuf::from("Assets/A.cs")));
+}
@@ -0,0 +1,129 @@
+// disk size per top-level folder and per module, measured again when anything inside changed
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  time::{Duration, SystemTime},
+};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  context::HubContext,
+  editor::{self, SizeSectionKind},
+};
+
+const MB: usize = 1024 * 1024;
+
+// one megabyte of real data, so allocated size isn't just a block
+fn write_mb(path: &Path) {
+  fs::create_dir_all(path.parent().unwrap()).unwrap();
+  fs::write(path, vec![1u8; MB]).unwrap();
+}
+
+fn setup() -> (FakeHub, PathBuf) {
+  let hub = FakeHub::new();
+  let contents = hub.add_editor("2022.3.10f1").join("Unity.app").join("Contents");
+  write_mb(&contents.join("PlaybackEngines").join("WebGLSupport").join("player.data"));
+  write_mb(&contents.join("PlaybackEngines").join("LinuxStandaloneSupport").join("player.data"));
+  write_mb(&contents.join("MonoBleedingEdge").join("lib").join("mono.so"));
+  write_mb(&contents.join("Resources").join("unity default resources"));
+  (hub, contents)
+}
+
+fn section_size(size: &editor::EditorSize, path: &str) -> u64 {
+  size.sections.iter().find(|x| x.path == Path::new(path)).unwrap().size
+}
+
+#[test]
+fn splits_modules_out_of_their_folder() {
+  let (hub, _) = setup();
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+
+  let size = editor::get_size_breakdown(&install, &hub).unwrap();
+  let modules = size.sections
+    .iter()
+    .filter(|x| x.kind == SizeSectionKind::Module)
+    .map(|x| x.path.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(modules.len(), 3);
+  assert!(modules.contains(&PathBuf::from("Unity.app/Contents/PlaybackEngines/WebGLSupport")));
+  assert!(modules.contains(&PathBuf::from("Unity.app/Contents/MonoBleedingEdge")));
+
+  let webgl = section_size(&size, "Unity.app/Contents/PlaybackEngines/WebGLSupport");
+  assert!(webgl >= MB as u64);
+  // the app folder holds the resources but not the modules inside it
+  let app = section_size(&size, "Unity.app");
+  assert!(app >= MB as u64 && app < 2 * MB as u64);
+  assert!(size.total >= size.sections.iter().map(|x| x.size).sum::<u64>());
+
+  assert_eq!(editor::estimate_size(&install, &hub).unwrap(), size.total);
+}
+
+#[test]
+fn hardlinks_are_counted_once() {
+  let (hub, contents) = setup();
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+  let before = editor::get_size_breakdown(&install, &hub).unwrap().total;
+
+  fs::hard_link(
+    contents.join("MonoBleedingEdge").join("lib").join("mono.so"),
+    contents.join("Resources").join("mono.so"),
+  ).unwrap();
+
+  let after = editor::measure_size(&hub.editor_dir("2022.3.10f1"), None).unwrap().total;
+  assert!(after < before + MB as u64);
+}
+
+#[test]
+fn unchanged_installs_are_not_measured_again() {
+  let (hub, _) = setup();
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+  let root = hub.editor_dir("2022.3.10f1");
+
+  let mut size = editor::get_size_breakdown(&install, &hub).unwrap();
+  // pretend earlier measurements, an unchanged install keeps them
+  for section in size.sections.iter_mut() {
+    section.size = 7;
+  }
+  assert_eq!(editor::measure_size(&root, Some(&size)).unwrap(), size);
+}
+
+#[test]
+fn a_change_deep_inside_measures_everything_again() {
+  let (hub, contents) = setup();
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+  let root = hub.editor_dir("2022.3.10f1");
+
+  let mut size = editor::get_size_breakdown(&install, &hub).unwrap();
+  for section in size.sections.iter_mut() {
+    section.size = 7;
+  }
+
+  // only the mtime of the innermost folder changes
+  let data = contents.join("PlaybackEngines").join("WebGLSupport").join("Data").join("extra.data");
+  write_mb(&data);
+  fs::File::open(&data).unwrap().set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+
+  let remeasured = editor::measure_size(&root, Some(&size)).unwrap();
+  assert!(section_size(&remeasured, "Unity.app/Contents/PlaybackEngines/WebGLSupport") >= 2 * MB as u64);
+  assert!(section_size(&remeasured, "Unity.app/Contents/MonoBleedingEdge") >= MB as u64);
+  assert!(section_size(&remeasured, "Unity.app") >= MB as u64);
+}
+
+#[test]
+fn old_single_number_cache_is_replaced() {
+  let (hub, _) = setup();
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+  let cache_path = hub.cache_dir().join("editors.json");
+  fs::create_dir_all(hub.cache_dir()).unwrap();
+  fs::write(&cache_path, format!("{{\"{}\": 12}}", install.exe_path.display())).unwrap();
+
+  let size = editor::get_size_breakdown(&install, &hub).unwrap();
+  assert!(size.total > 12);
+
+  let cache: serde_json::Value = serde_json::from_str(&fs::read_to_string(&cache_path).unwrap()).unwrap();
+  assert_eq!(cache[install.exe_path.to_str().unwrap()]["total"], size.total);
+}
@@ -0,0 +1,238 @@
+// editor discovery across search roots and manually located installs
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+
+use common::{add_editor_at, FakeHub};
+use nomnom_unity_hub::{app::AppState, context::HubContext, editor, errors};
+
+fn app_state_for(hub: &FakeHub) -> AppState {
+  let app_state = AppState::load(hub.path().join("config"), hub.path().join("cache")).unwrap();
+  *app_state.prefs.lock().unwrap() = hub.prefs.clone();
+  app_state
+}
+
+fn versions(editors: Vec<editor::UnityEditorInstall>) -> Vec<String> {
+  editors.into_iter().map(|x| x.version).collect()
+}
+
+#[test]
+fn scans_every_search_root() {
+  let mut hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let second_drive = hub.path().join("SecondDrive");
+  add_editor_at(&second_drive.join("2021.3.5f1"), "2021.3.5f1");
+
+  // an unplugged drive is skipped rather than failing the whole scan
+  hub.prefs.editor_search_paths = vec![second_drive, hub.path().join("Unplugged")];
+
+  assert_eq!(versions(editor::find_editor_installs(&hub).unwrap()), vec!["2022.3.10f1", "2021.3.5f1"]);
+}
+
+#[test]
+fn unreadable_versions_fall_back_to_the_folder_name() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let broken = hub.add_editor("2021.3.5f1");
+  fs::remove_file(broken.join("Unity.app").join("Contents").join("Info.plist")).unwrap();
+  // no editor inside at all
+  fs::create_dir_all(hub.editor_dir("not-an-editor")).unwrap();
+
+  let editors = editor::find_editor_installs(&hub).unwrap();
+  assert_eq!(versions(editors.clone()), vec!["2022.3.10f1", "2021.3.5f1"]);
+  assert!(editors[0].version_warning.is_none());
+  assert!(editors[1].version_warning.as_ref().unwrap().contains("Could not verify"));
+
+  // a located editor needs a real version, its folder name means nothing
+  assert!(editor::read_manual_editor(&broken.join("Unity.app")).is_none());
+}
+
+#[test]
+fn version_can_come_from_playback_engine_ivy_xml() {
+  let hub = FakeHub::new();
+  let editor_dir = hub.add_editor("2022.3.10f1");
+  let contents = editor_dir.join("Unity.app").join("Contents");
+  fs::remove_file(contents.join("Info.plist")).unwrap();
+
+  let engine = contents.join("PlaybackEngines").join("LinuxStandaloneSupport");
+  fs::create_dir_all(&engine).unwrap();
+  fs::write(
+    engine.join("ivy.xml"),
+    r#"<ivy-module version="2.0"><info version="2022.3.10f1" organisation="Unity" module="LinuxStandalone" e:unityVersion="2022.3.10f1" /></ivy-module>"#,
+  ).unwrap();
+
+  assert_eq!(editor::read_install_version(&contents.parent().unwrap()).as_deref(), Some("2022.3.10f1"));
+  assert_eq!(versions(editor::find_editor_installs(&hub).unwrap()), vec!["2022.3.10f1"]);
+}
+
+#[test]
+fn manual_editor_reads_version_from_install() {
+  let mut hub = FakeHub::new();
+  // standalone installs have no modules.json and a meaningless folder name
+  let standalone = add_editor_at(&hub.path().join("Standalone").join("unity"), "2020.3.1f1");
+  fs::remove_file(standalone.join("modules.json")).unwrap();
+
+  let exe = standalone.join("Unity.app").join("Contents").join("Linux").join("Unity");
+  hub.prefs.manual_editor_paths = vec![exe];
+
+  let editors = editor::find_editor_installs(&hub).unwrap();
+  assert_eq!(editors.len(), 1);
+  assert_eq!(editors[0].version, "2020.3.1f1");
+  assert_eq!(editors[0].exe_path, standalone.join("Unity.app"));
+  assert!(editors[0].modules.is_empty());
+}
+
+#[test]
+fn same_install_found_twice_is_listed_once() {
+  let mut hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  // a symlinked root and a manual path both lead to the same install
+  let linked_root = hub.path().join("Linked");
+  std::os::unix::fs::symlink(hub.path().join("Hub").join("Editor"), &linked_root).unwrap();
+  hub.prefs.editor_search_paths = vec![linked_root];
+  hub.prefs.manual_editor_paths = vec![hub.editor_dir("2022.3.10f1")];
+
+  assert_eq!(versions(editor::find_editor_installs(&hub).unwrap()), vec!["2022.3.10f1"]);
+}
+
+#[test]
+fn add_and_remove_search_roots_and_manual_editors() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let second_drive = hub.path().join("SecondDrive");
+  add_editor_at(&second_drive.join("2021.3.5f1"), "2021.3.5f1");
+  let standalone = add_editor_at(&hub.path().join("Standalone"), "2020.3.1f1");
+
+  let app_state = app_state_for(&hub);
+
+  let editors = editor::add_search_path(second_drive.clone(), &app_state).unwrap();
+  assert_eq!(versions(editors), vec!["2022.3.10f1", "2021.3.5f1"]);
+
+  let located = editor::add_manual_editor(standalone.clone(), &app_state).unwrap();
+  assert_eq!(located.version, "2020.3.1f1");
+  assert_eq!(app_state.editors.lock().unwrap().len(), 3);
+
+  // persisted for the next launch
+  let prefs = nomnom_unity_hub::app::load_prefs_from_disk(hub.path().join("config")).unwrap();
+  assert_eq!(prefs.editor_search_paths, vec![second_drive.clone()]);
+  assert_eq!(prefs.manual_editor_paths, vec![standalone.clone()]);
+
+  editor::remove_search_path(second_drive, &app_state).unwrap();
+  let editors = editor::remove_manual_editor(standalone, &app_state).unwrap();
+  assert_eq!(versions(editors), vec!["2022.3.10f1"]);
+}
+
+#[test]
+fn locating_something_that_is_not_an_editor_fails() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+  let not_an_editor = hub.path().join("Projects");
+
+  let err = editor::add_manual_editor(not_an_editor, &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::EditorInvalid { .. }));
+  assert!(app_state.prefs.lock().unwrap().manual_editor_paths.is_empty());
+
+  let err = editor::add_search_path(hub.path().join("Missing"), &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::PrefInvalid { .. }));
+}
+
+#[test]
+fn renamed_folder_uses_installed_version_and_warns() {
+  let hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  add_editor_at(&hub.editor_dir("2022.3-lts"), "2022.3.10f1");
+
+  let editors = editor::find_editor_installs(&hub).unwrap();
+  assert_eq!(versions(editors.clone()), vec!["2022.3.10f1", "2021.3.5f1"]);
+  assert!(editors[0].version_warning.as_ref().unwrap().contains("2022.3-lts"));
+  assert!(editors[1].version_warning.is_none());
+
+  // projects match on the real version
+  let found = hub.find_editor("2022.3.10f1").unwrap();
+  assert_eq!(found.exe_path, hub.editor_dir("2022.3-lts").join("Unity.app"));
+}
+
+#[test]
+fn resolves_exact_then_closest_patch_in_stream() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.5f1");
+  hub.add_editor("2022.3.20f1");
+  hub.add_editor("2022.3.8f1");
+  hub.add_editor("2021.3.5f1");
+
+  let exact = editor::resolve_editor("2022.3.8f1", &hub).unwrap();
+  assert_eq!(exact.editor.version, "2022.3.8f1");
+  assert_eq!(exact.change, editor::VersionChange::Exact);
+  assert!(exact.warning.is_none());
+
+  // the nearest newer patch wins over older ones
+  let upgrade = editor::resolve_editor("2022.3.10f1", &hub).unwrap();
+  assert_eq!(upgrade.editor.version, "2022.3.20f1");
+  assert_eq!(upgrade.change, editor::VersionChange::PatchUpgrade);
+  assert!(upgrade.warning.is_some());
+
+  let downgrade = editor::resolve_editor("2022.3.30f1", &hub).unwrap();
+  assert_eq!(downgrade.editor.version, "2022.3.20f1");
+  assert_eq!(downgrade.change, editor::VersionChange::PatchDowngrade);
+}
+
+#[test]
+fn other_streams_are_offered_but_never_picked() {
+  let hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  hub.add_editor("2023.2.1f1");
+  hub.add_editor("6000.0.23f1");
+
+  let err = editor::resolve_editor("2022.3.10f1", &hub).unwrap_err();
+  let errors::AnyError::EditorNotFound { version, candidates } = &err else {
+    panic!("expected EditorNotFound, got {:?}", err);
+  };
+  assert_eq!(version, "2022.3.10f1");
+
+  let offered = candidates
+    .iter()
+    .map(|x| (x.version.as_str(), x.change))
+    .collect::<Vec<_>>();
+  assert_eq!(offered, vec![
+    ("2023.2.1f1", editor::VersionChange::Upgrade),
+    ("6000.0.23f1", editor::VersionChange::Upgrade),
+    ("2021.3.5f1", editor::VersionChange::Downgrade),
+  ]);
+
+  // the ui reads them from the error details
+  let json = serde_json::to_value(&err).unwrap();
+  assert_eq!(json["details"]["candidates"][0]["version"], "2023.2.1f1");
+  assert_eq!(json["details"]["candidates"][0]["change"], "Upgrade");
+}
+
+#[test]
+fn opening_a_project_with_another_stream_warns() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  hub.add_editor("2023.2.1f1");
+
+  let project_path = hub.projects_dir().join("Game");
+  fs::create_dir_all(project_path.join("Assets")).unwrap();
+  fs::create_dir_all(project_path.join("ProjectSettings")).unwrap();
+  fs::write(project_path.join("ProjectSettings").join("ProjectVersion.txt"), "m_EditorVersion: 2022.3.10f1\n").unwrap();
+
+  let app_state = app_state_for(&hub);
+  editor::refresh_editor_installs(&app_state).unwrap();
+  nomnom_unity_hub::project::add_project(project_path.clone(), &app_state).unwrap();
+
+  let resolution = nomnom_unity_hub::project::open_project(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  assert_eq!(resolution.change, editor::VersionChange::Exact);
+  // the project stays open until the first editor exits
+  for launched in app_state.processes.lock().unwrap().iter_mut() {
+    launched.child.wait().unwrap();
+  }
+
+  // the user picked a newer stream from the candidates
+  let resolution = nomnom_unity_hub::project::open_project(project_path, "2023.2.1f1".to_string(), &app_state).unwrap();
+  assert_eq!(resolution.requested, "2022.3.10f1");
+  assert_eq!(resolution.change, editor::VersionChange::Upgrade);
+  assert!(resolution.warning.unwrap().contains("upgraded"));
+}
@@ -0,0 +1,314 @@
+// end-to-end checks against a fabricated hub, the editor layout is the linux one
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{add_editor_at, FakeHub};
+use nomnom_unity_hub::{
+  context::HubContext,
+  editor,
+  errors::{self, AnyError},
+  generate::{self, NewTemplateInfo, ProjectInfoForGeneration, TemplateInfoForGeneration},
+  package::{MinimalPackage, PackageType},
+  template,
+};
+
+fn package(name: &str, version: &str) -> MinimalPackage {
+  MinimalPackage {
+    name: name.to_string(),
+    version: version.to_string(),
+    is_discoverable: true,
+    _type: PackageType::Default,
+  }
+}
+
+fn read_json(path: PathBuf) -> serde_json::Value {
+  serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap()
+}
+
+#[test]
+fn find_editor_installs_lists_valid_editors_newest_first() {
+  let hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  hub.add_editor("2022.3.10f1");
+  hub.add_editor("2022.3.9f1");
+  // no editor inside, not an install
+  fs::create_dir_all(hub.editor_dir("2023.1.0f1").join("Downloads")).unwrap();
+  fs::create_dir_all(hub.editor_dir(".cache")).unwrap();
+
+  let editors = editor::find_editor_installs(&hub).unwrap();
+  let versions = editors.iter().map(|x| x.version.as_str()).collect::<Vec<_>>();
+
+  assert_eq!(versions, vec!["2022.3.10f1", "2022.3.9f1", "2021.3.5f1"]);
+  assert_eq!(editors[0].modules.len(), 2);
+  assert_eq!(editors[0].exe_path, hub.editor_dir("2022.3.10f1").join("Unity.app"));
+}
+
+#[test]
+fn find_editor_installs_skips_a_missing_editors_folder() {
+  let mut hub = FakeHub::new();
+  let second_drive = hub.path().join("D").join("Unity");
+  add_editor_at(&second_drive.join("2021.3.5f1"), "2021.3.5f1");
+  hub.prefs.editor_search_paths = vec![second_drive.clone()];
+
+  hub.prefs.hub_editors_path = Some(hub.path().join("missing"));
+  let editors = editor::find_editor_installs(&hub).unwrap();
+  assert_eq!(editors.iter().map(|x| x.version.as_str()).collect::<Vec<_>>(), vec!["2021.3.5f1"]);
+
+  hub.prefs.hub_editors_path = None;
+  assert_eq!(editor::find_editor_installs(&hub).unwrap().len(), 1);
+}
+
+#[test]
+fn search_roots_are_deduplicated() {
+  let mut hub = FakeHub::new();
+  let editors_path = hub.prefs.hub_editors_path.clone().unwrap();
+  let second_drive = hub.path().join("D");
+  hub.prefs.editor_search_paths = vec![second_drive.clone(), editors_path.clone(), second_drive.clone()];
+
+  assert_eq!(editor::get_search_roots(&hub.prefs), vec![editors_path, second_drive]);
+}
+
+#[test]
+fn get_core_templates_reads_editor_project_templates() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  hub.add_editor("2021.3.5f1");
+  hub.add_core_template("2022.3.10f1", "com.unity.template.3d", "8.1.3");
+  hub.add_core_template("2022.3.10f1", "com.unity.template.2d", "7.0.4");
+  hub.add_core_template("2021.3.5f1", "com.unity.template.urp", "14.0.8");
+
+  let mut templates = template::get_core_templates("2022.3.10f1".to_string(), &hub).unwrap();
+  templates.sort_by(|x, y| x.name.cmp(&y.name));
+
+  assert_eq!(templates.len(), 2);
+  assert_eq!(templates[0].name, "com.unity.template.2d");
+  assert_eq!(templates[0].version, "7.0.4");
+  assert_eq!(templates[1].name, "com.unity.template.3d");
+  assert_eq!(templates[1].editor_version, "2022.3.10f1");
+}
+
+#[test]
+fn get_core_templates_rejects_unknown_editor() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let err = template::get_core_templates("6000.0.1f1".to_string(), &hub).unwrap_err();
+  assert!(matches!(err, AnyError::EditorNotFound { ref version, .. } if version == "6000.0.1f1"));
+
+  // the frontend gets { code, message, details }
+  let json = serde_json::to_value(&err).unwrap();
+  assert_eq!(json["code"], "EditorNotFound");
+  assert_eq!(json["message"], "Editor version 6000.0.1f1 is not installed");
+  assert_eq!(json["details"]["version"], "6000.0.1f1");
+}
+
+#[test]
+fn typed_errors_keep_their_code_through_anyhow() {
+  let err = AnyError::from(anyhow::Error::from(errors::project_not_found("/tmp/Game")));
+  assert_eq!(err.code(), "ProjectNotFound");
+
+  let err = AnyError::from(anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound)));
+  assert_eq!(err.code(), "Io");
+  assert_eq!(AnyError::from(anyhow::anyhow!("something else")).code(), "Other");
+}
+
+#[test]
+fn corrupt_package_lists_are_reported_as_corrupt() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let manifest = hub.path().join("manifest.json");
+  let lock = hub.path().join("packages-lock.json");
+
+  let err = template::extract_packages(&hub, &"2022.3.10f1".to_string(), &"{ not json".to_string(), &String::new(), &manifest, &lock).unwrap_err();
+  assert!(matches!(err, AnyError::ManifestCorrupt { ref path } if *path == manifest));
+
+  let err = template::extract_packages(&hub, &"2022.3.10f1".to_string(), &"{}".to_string(), &"[".to_string(), &manifest, &lock).unwrap_err();
+  assert!(matches!(err, AnyError::ManifestCorrupt { ref path } if *path == lock));
+}
+
+#[test]
+fn get_user_templates_only_returns_templates_in_manifest() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  hub.add_user_template("2022.3.10f1", "com.me.template.game", "1.0.0");
+  hub.add_user_template("2021.3.5f1", "com.me.template.old", "0.1.0");
+  // on disk but never registered with the hub
+  common::write_template_tgz(
+    &hub.user_templates_dir().join("com.me.template.stray-1.0.0.tgz"),
+    "com.me.template.stray",
+    "1.0.0",
+    "2022.3.10f1",
+  );
+
+  let templates = template::get_user_templates("2022.3.10f1".to_string(), &hub).unwrap();
+  assert_eq!(templates.len(), 1);
+  assert_eq!(templates[0].name, "com.me.template.game");
+  assert_eq!(templates[0].version, "1.0.0");
+
+  let templates = template::get_user_templates("6000.0.1f1".to_string(), &hub).unwrap();
+  assert!(templates.is_empty());
+}
+
+#[test]
+fn template_file_names_split_into_name_and_version() {
+  let (name, version) = template::get_info_from_file_name("com.unity.template.3d-8.1.3.tgz").unwrap();
+  assert_eq!((name.as_str(), version.as_str()), ("com.unity.template.3d", "8.1.3"));
+
+  // short or foreign names are errors, not out of bounds slices
+  assert!(template::get_info_from_file_name("a-b").is_err());
+  assert!(template::get_info_from_file_name("notes-1.0.txt").is_err());
+  assert!(template::get_info_from_file_name(".tgz").is_err());
+}
+
+#[test]
+fn extract_template_information_merges_package_json_and_lock() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let path = hub.add_core_template("2022.3.10f1", "com.unity.template.3d", "8.1.3");
+  let surface_template = template::SurfaceTemplate {
+    name: "com.unity.template.3d".to_string(),
+    version: "8.1.3".to_string(),
+    path,
+    editor_version: "2022.3.10f1".to_string(),
+  };
+
+  let record = template::extract_template_information(&hub, &surface_template).unwrap();
+  let dependencies = record.tgz_package.dependencies.unwrap();
+
+  // direct lock entries win over package.json, transitive ones are skipped
+  assert_eq!(dependencies.get("com.unity.ugui").unwrap(), "1.0.0");
+  assert_eq!(dependencies.get("com.unity.timeline").unwrap(), "1.7.6");
+  assert!(!dependencies.contains_key("com.unity.modules.audio"));
+
+  // every lock entry is cached per editor version for later generation
+  let cached = template::read_editor_version_packages(&hub, &"2022.3.10f1".to_string()).unwrap();
+  assert!(cached.packages.contains_key("com.unity.modules.audio"));
+  assert!(hub.cache_dir().join("templates").join("2022_3_10f1.json").is_file());
+}
+
+#[test]
+fn generate_project_from_template() {
+  let hub = FakeHub::new();
+  let editor_dir = hub.add_editor("2022.3.10f1");
+  let template_path = hub.add_core_template("2022.3.10f1", "com.unity.template.3d", "8.1.3");
+  let editor = hub.find_editor("2022.3.10f1").unwrap();
+  assert_eq!(editor.exe_path, editor_dir.join("Unity.app"));
+
+  let surface_template = template::SurfaceTemplate {
+    name: "com.unity.template.3d".to_string(),
+    version: "8.1.3".to_string(),
+    path: template_path.clone(),
+    editor_version: "2022.3.10f1".to_string(),
+  };
+  template::extract_template_information(&hub, &surface_template).unwrap();
+
+  let project_info = ProjectInfoForGeneration {
+    name: "MyGame".to_string(),
+    path: hub.projects_dir(),
+  };
+  let template_info = TemplateInfoForGeneration {
+    template: Some(surface_template),
+    editor_version: editor,
+    packages: vec![package("com.unity.ugui", "1.0.0"), package("com.unity.inputsystem", "1.7.0")],
+    selected_files: template::read_template_entry_paths(&template_path).unwrap(),
+    is_empty: false,
+  };
+
+  let project_path = generate::generate_project(&hub, &project_info, &template_info).unwrap();
+  assert_eq!(project_path, hub.projects_dir().join("MyGame"));
+  assert!(project_path.join("Assets").join("Scenes").join("SampleScene.unity").is_file());
+  assert!(project_path.join(".gitignore").is_file());
+
+  let project_version = fs::read_to_string(project_path.join("ProjectSettings").join("ProjectVersion.txt")).unwrap();
+  assert_eq!(project_version, "m_EditorVersion: 2022.3.10f1");
+
+  let project_settings = fs::read_to_string(project_path.join("ProjectSettings").join("ProjectSettings.asset")).unwrap();
+  assert!(project_settings.contains("productName: MyGame"));
+  assert!(project_settings.contains("companyName: DefaultCompany"));
+
+  let manifest = read_json(project_path.join("Packages").join("manifest.json"));
+  assert_eq!(manifest["dependencies"]["com.unity.ugui"], "1.0.0");
+  assert_eq!(manifest["dependencies"]["com.unity.inputsystem"], "1.7.0");
+  assert!(manifest["dependencies"].get("com.unity.timeline").is_none());
+
+  // only packages the editor already knows about get locked
+  let lock = read_json(project_path.join("Packages").join("packages-lock.json"));
+  assert!(lock["dependencies"].get("com.unity.ugui").is_some());
+  assert!(lock["dependencies"].get("com.unity.inputsystem").is_none());
+
+  // generating on top of an existing project is refused
+  let err = generate::generate_project(&hub, &project_info, &template_info).unwrap_err();
+  assert!(matches!(err, AnyError::ProjectExists { ref path } if *path == project_path));
+}
+
+#[test]
+fn generate_empty_project_runs_editor() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let editor = hub.find_editor("2022.3.10f1").unwrap();
+
+  let project_info = ProjectInfoForGeneration {
+    name: "Blank".to_string(),
+    path: hub.projects_dir(),
+  };
+  let template_info = TemplateInfoForGeneration {
+    template: None,
+    editor_version: editor,
+    packages: vec![package("com.unity.ugui", "1.0.0")],
+    selected_files: Vec::new(),
+    is_empty: true,
+  };
+
+  let project_path = generate::generate_project(&hub, &project_info, &template_info).unwrap();
+  let manifest = read_json(project_path.join("Packages").join("manifest.json"));
+  assert_eq!(manifest["dependencies"]["com.unity.ugui"], "1.0.0");
+  assert!(project_path.join("ProjectSettings").join("ProjectVersion.txt").is_file());
+}
+
+#[test]
+fn generate_template_registers_with_hub() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let template_path = hub.add_core_template("2022.3.10f1", "com.unity.template.3d", "8.1.3");
+  let editor = hub.find_editor("2022.3.10f1").unwrap();
+
+  let new_template_info = NewTemplateInfo {
+    template: TemplateInfoForGeneration {
+      template: Some(template::SurfaceTemplate {
+        name: "com.unity.template.3d".to_string(),
+        version: "8.1.3".to_string(),
+        path: template_path.clone(),
+        editor_version: "2022.3.10f1".to_string(),
+      }),
+      editor_version: editor,
+      packages: vec![package("com.unity.ugui", "1.0.0")],
+      selected_files: template::read_template_entry_paths(&template_path).unwrap(),
+      is_empty: false,
+    },
+    name: "com.me.template.game".to_string(),
+    display_name: "Game".to_string(),
+    version: "2.0.0".to_string(),
+    description: "My game template".to_string(),
+  };
+
+  let output = generate::generate_template(&hub, &new_template_info).unwrap();
+  assert_eq!(output, hub.user_templates_dir().join("com.me.template.game-2.0.0.tgz"));
+  assert!(output.is_file());
+
+  let manifest = read_json(hub.user_templates_dir().join("manifest.json"));
+  assert_eq!(manifest["2022.3.10f1"]["dependencies"]["com.me.template.game"], "2.0.0");
+
+  // the new template is now discoverable and readable like any other
+  let templates = template::get_user_templates("2022.3.10f1".to_string(), &hub).unwrap();
+  assert_eq!(templates.len(), 1);
+
+  let record = template::extract_template_information(&hub, &templates[0]).unwrap();
+  let dependencies = record.tgz_package.dependencies.unwrap();
+  assert_eq!(dependencies.get("com.unity.ugui").unwrap(), "1.0.0");
+
+  let entries = template::read_template_entry_paths(&output).unwrap();
+  assert!(entries.contains(&PathBuf::from("package/ProjectData~/Assets/Scenes/SampleScene.unity")));
+}
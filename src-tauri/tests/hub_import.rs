@@ -0,0 +1,140 @@
+// importing the official hub's projects-v1.json, editors-v2.json and secondaryInstallPath.json
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+
+use common::{add_editor_at, FakeHub};
+use nomnom_unity_hub::{app::AppState, editor, errors, hub_import};
+
+fn app_state_for(hub: &FakeHub) -> AppState {
+  let app_state = AppState::load(hub.path().join("config"), hub.path().join("cache")).unwrap();
+  *app_state.prefs.lock().unwrap() = hub.prefs.clone();
+  app_state
+}
+
+fn make_project(hub: &FakeHub, name: &str, version: &str) -> std::path::PathBuf {
+  let path = hub.projects_dir().join(name);
+  fs::create_dir_all(path.join("Assets")).unwrap();
+  fs::create_dir_all(path.join("ProjectSettings")).unwrap();
+  fs::write(
+    path.join("ProjectSettings").join("ProjectVersion.txt"),
+    format!("m_EditorVersion: {}\nm_EditorVersionWithRevision: {} (abc)\n", version, version),
+  ).unwrap();
+  path
+}
+
+fn write_hub_file(hub: &FakeHub, name: &str, value: serde_json::Value) {
+  fs::write(hub.path().join("UnityHub").join(name), value.to_string()).unwrap();
+}
+
+#[test]
+fn imports_projects_and_reports_duplicates_and_missing() {
+  let hub = FakeHub::new();
+  let game = make_project(&hub, "Game", "2022.3.10f1");
+  let demo = make_project(&hub, "Demo", "2021.3.5f1");
+  let gone = hub.projects_dir().join("Gone");
+
+  write_hub_file(&hub, "projects-v1.json", serde_json::json!({
+    "schema_version": "v1",
+    "data": {
+      game.to_str().unwrap(): {
+        "title": "Game", "path": game, "version": "2022.3.10f1",
+        "isFavorite": true, "lastModified": 2000, "architecture": "x86_64"
+      },
+      demo.to_str().unwrap(): {
+        "title": "Demo", "path": demo, "version": "2021.3.5f1",
+        "isFavorite": false, "lastModified": 1000
+      },
+      gone.to_str().unwrap(): { "title": "Gone", "path": gone, "version": "2020.3.1f1" }
+    }
+  }));
+
+  let app_state = app_state_for(&hub);
+  app_state.projects.lock().unwrap().push(nomnom_unity_hub::project::load(&demo).unwrap());
+
+  let report = hub_import::import_from_hub(&app_state).unwrap();
+  assert_eq!(report.added_projects, vec![game.clone()]);
+  assert_eq!(report.duplicate_projects, vec![demo.clone()]);
+  assert_eq!(report.missing_projects, vec![gone]);
+
+  let projects = app_state.projects.lock().unwrap().clone();
+  assert_eq!(projects.len(), 2);
+  let imported = projects.iter().find(|x| x.path == game).unwrap();
+  assert_eq!(imported.version, "2022.3.10f1");
+  assert!(imported.is_pinned);
+  assert_eq!(imported.last_opened_at, 2000);
+
+  // persisted so a restart keeps them
+  let on_disk = nomnom_unity_hub::app::load_projects_from_disk(hub.path().join("config")).unwrap();
+  assert_eq!(on_disk.len(), 2);
+
+  // importing again changes nothing
+  let report = hub_import::import_from_hub(&app_state).unwrap();
+  assert!(report.added_projects.is_empty());
+  assert_eq!(report.duplicate_projects.len(), 2);
+}
+
+#[test]
+fn imports_secondary_install_path_and_located_editors() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  // a second drive laid out like the hub's editor folder
+  let secondary = hub.path().join("SecondDrive");
+  add_editor_at(&secondary.join("2021.3.5f1"), "2021.3.5f1");
+
+  // a standalone install the user pointed the hub at
+  let standalone = add_editor_at(&hub.path().join("Standalone").join("2020.3.1f1"), "2020.3.1f1");
+  let standalone_exe = standalone.join("Unity.app").join("Contents").join("Linux").join("Unity");
+
+  let hub_installed_exe = hub.editor_dir("2022.3.10f1").join("Unity.app");
+  let missing_exe = hub.path().join("Nowhere").join("Unity");
+
+  write_hub_file(&hub, "secondaryInstallPath.json", serde_json::json!(secondary));
+  write_hub_file(&hub, "editors-v2.json", serde_json::json!({
+    "schema_version": "v2",
+    "data": [
+      { "version": "2022.3.10f1", "location": [hub_installed_exe], "manual": false },
+      { "version": "2020.3.1f1", "location": [standalone_exe], "manual": true },
+      { "version": "2019.4.0f1", "location": missing_exe, "manual": true }
+    ]
+  }));
+
+  let app_state = app_state_for(&hub);
+  let report = hub_import::import_from_hub(&app_state).unwrap();
+  assert_eq!(report.added_editor_paths, vec![secondary.clone(), standalone_exe.clone()]);
+  assert_eq!(report.duplicate_editor_paths, vec![hub_installed_exe]);
+  assert_eq!(report.missing_editor_paths, vec![missing_exe]);
+
+  let prefs = app_state.prefs.lock().unwrap().clone();
+  assert_eq!(prefs.editor_search_paths, vec![secondary]);
+  assert_eq!(prefs.manual_editor_paths, vec![standalone_exe]);
+
+  let versions = editor::refresh_editor_installs(&app_state)
+    .unwrap()
+    .into_iter()
+    .map(|x| x.version)
+    .collect::<Vec<_>>();
+  assert_eq!(versions, vec!["2022.3.10f1", "2021.3.5f1", "2020.3.1f1"]);
+}
+
+#[test]
+fn missing_hub_files_import_nothing() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+
+  let report = hub_import::import_from_hub(&app_state).unwrap();
+  assert!(report.added_projects.is_empty());
+  assert!(report.added_editor_paths.is_empty());
+}
+
+#[test]
+fn corrupt_hub_file_is_reported() {
+  let hub = FakeHub::new();
+  fs::write(hub.path().join("UnityHub").join("projects-v1.json"), "{ not json").unwrap();
+
+  let err = hub_import::import_from_hub(&app_state_for(&hub)).unwrap_err();
+  assert!(matches!(err, errors::AnyError::ManifestCorrupt { .. }));
+}
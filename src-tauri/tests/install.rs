@@ -0,0 +1,191 @@
+// installing editors and modules offline from archives on disk
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{
+  fs,
+  io::Write,
+  os::unix::fs::PermissionsExt,
+  path::{Path, PathBuf},
+};
+
+use common::{add_editor_at, FakeHub};
+use nomnom_unity_hub::{
+  app::AppState,
+  context::HubContext,
+  editor,
+  errors::AnyError,
+  install::{self, InstallRequest, ModuleArchive},
+};
+
+fn app_state_for(hub: &FakeHub) -> AppState {
+  let app_state = AppState::load(hub.path().join("config"), hub.path().join("cache")).unwrap();
+  *app_state.prefs.lock().unwrap() = hub.prefs.clone();
+  app_state
+}
+
+// Unity-<version>/Unity.app/..., packed the way the archives on the share are
+fn pack_editor_tar_xz(hub: &FakeHub, version: &str) -> PathBuf {
+  let source = hub.path().join("share").join(format!("Unity-{}", version));
+  add_editor_at(&source, version);
+
+  let archive = hub.path().join("share").join(format!("Unity-{}.tar.xz", version));
+  let encoder = xz2::write::XzEncoder::new(fs::File::create(&archive).unwrap(), 1);
+  let mut tar = tar::Builder::new(encoder);
+  tar.append_dir_all(format!("Unity-{}", version), &source).unwrap();
+  tar.into_inner().unwrap().finish().unwrap();
+  fs::remove_dir_all(&source).unwrap();
+  archive
+}
+
+fn pack_zip(path: &Path, files: &[(&str, &str)]) {
+  fs::create_dir_all(path.parent().unwrap()).unwrap();
+  let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+  for (name, contents) in files {
+    zip.start_file(*name, zip::write::FileOptions::default().unix_permissions(0o755)).unwrap();
+    zip.write_all(contents.as_bytes()).unwrap();
+  }
+  zip.finish().unwrap();
+}
+
+#[test]
+fn installs_an_editor_and_its_modules() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+  let archive = pack_editor_tar_xz(&hub, "2022.3.30f1");
+  let android = hub.path().join("share").join("UnitySetup-Android-Support-2022.3.30f1.zip");
+  pack_zip(&android, &[("Unity.app/Contents/PlaybackEngines/AndroidPlayer/ivy.xml", "<ivy-module />")]);
+
+  let mut progress = vec![];
+  let installed = install::install_from_archives(&InstallRequest {
+    archive,
+    modules: vec![ModuleArchive {
+      path: android,
+      id: "android".to_string(),
+      name: "Android Build Support".to_string(),
+      ..Default::default()
+    }],
+  }, &app_state, |x| progress.push(x)).unwrap();
+
+  let install_path = hub.editor_dir("2022.3.30f1");
+  assert_eq!(installed.version, "2022.3.30f1");
+  assert_eq!(installed.exe_path, install_path.join("Unity.app"));
+  assert!(installed.version_warning.is_none());
+  assert!(install_path.join("Unity.app").join("Contents").join("PlaybackEngines").join("AndroidPlayer").join("ivy.xml").is_file());
+
+  // permissions survive, the editor can still be started
+  let exe = install_path.join("Unity.app").join("Contents").join("Linux").join("Unity");
+  assert_ne!(fs::metadata(exe).unwrap().permissions().mode() & 0o111, 0);
+
+  let android = installed.modules.iter().find(|x| x.id == "android").unwrap();
+  assert!(android.selected && android.visible);
+  assert_eq!(android.category, "Platforms");
+  // modules the archive came with stay listed
+  assert!(installed.modules.iter().any(|x| x.id == "webgl" && !x.selected));
+
+  assert_eq!(progress.first().unwrap().step, 1);
+  let last = progress.last().unwrap();
+  assert_eq!((last.step, last.steps, last.done), (2, 2, last.total));
+
+  assert_eq!(app_state.editors().unwrap().len(), 1);
+  // nothing left over from extracting
+  let leftovers = fs::read_dir(hub.editor_dir("2022.3.30f1").parent().unwrap()).unwrap().count();
+  assert_eq!(leftovers, 1);
+}
+
+#[test]
+fn installs_from_a_zip() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+  let source = hub.path().join("share").join("2021.3.5f1");
+  add_editor_at(&source, "2021.3.5f1");
+  let plist = fs::read_to_string(source.join("Unity.app").join("Contents").join("Info.plist")).unwrap();
+
+  let archive = hub.path().join("share").join("Unity-2021.3.5f1.zip");
+  pack_zip(&archive, &[("Unity.app/Contents/Info.plist", &plist)]);
+
+  let installed = install::install_from_archives(&InstallRequest { archive, modules: vec![] }, &app_state, |_| {}).unwrap();
+  assert_eq!(installed.version, "2021.3.5f1");
+  assert_eq!(editor::get_root_folder(&installed.exe_path), Some(hub.editor_dir("2021.3.5f1")));
+  // the zip had no modules.json, an empty one is written
+  assert!(hub.editor_dir("2021.3.5f1").join("modules.json").is_file());
+}
+
+#[test]
+fn refuses_an_installed_version_and_cleans_up() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.30f1");
+  let app_state = app_state_for(&hub);
+  let archive = pack_editor_tar_xz(&hub, "2022.3.30f1");
+
+  let err = install::install_from_archives(&InstallRequest { archive, modules: vec![] }, &app_state, |_| {}).unwrap_err();
+  assert!(matches!(err, AnyError::EditorExists { .. }));
+  let entries = fs::read_dir(hub.editor_dir("2022.3.30f1").parent().unwrap()).unwrap().count();
+  assert_eq!(entries, 1);
+
+  let unsupported = hub.path().join("share").join("UnitySetup64.exe");
+  fs::write(&unsupported, "").unwrap();
+  let err = install::install_from_archives(&InstallRequest { archive: unsupported, modules: vec![] }, &app_state, |_| {}).unwrap_err();
+  assert!(matches!(err, AnyError::ArchiveUnsupported { .. }));
+
+  // an editor without a readable version has nothing to name its folder after
+  let unreadable = hub.path().join("share").join("Unity-broken.zip");
+  pack_zip(&unreadable, &[("Unity-broken/Unity.app/Contents/MacOS/Unity", "")]);
+  let err = install::install_from_archives(&InstallRequest { archive: unreadable, modules: vec![] }, &app_state, |_| {}).unwrap_err();
+  assert!(matches!(err, AnyError::EditorInvalid { .. }));
+}
+
+#[test]
+fn a_failed_module_leaves_nothing_installed() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+  let archive = pack_editor_tar_xz(&hub, "2022.3.30f1");
+  let android = hub.path().join("share").join("UnitySetup-Android-Support-2022.3.30f1.zip");
+  let module = ModuleArchive {
+    path: android.clone(),
+    id: "android".to_string(),
+    name: "Android Build Support".to_string(),
+    ..Default::default()
+  };
+  let request = InstallRequest { archive, modules: vec![module] };
+
+  // the module archive isn't on the share yet
+  assert!(install::install_from_archives(&request, &app_state, |_| {}).is_err());
+  assert!(fs::read_dir(hub.editor_dir("2022.3.30f1").parent().unwrap()).unwrap().next().is_none());
+  assert!(app_state.editors().unwrap().is_empty());
+
+  // a download that broke off halfway
+  fs::write(&android, "PK not really a zip").unwrap();
+  let err = install::install_from_archives(&request, &app_state, |_| {}).unwrap_err();
+  assert!(matches!(err, AnyError::ExtractionFailed { .. }));
+
+  // so a retry isn't refused as already installed
+  pack_zip(&android, &[("Unity.app/Contents/PlaybackEngines/AndroidPlayer/ivy.xml", "<ivy-module />")]);
+  let installed = install::install_from_archives(&request, &app_state, |_| {}).unwrap();
+  assert!(installed.modules.iter().any(|x| x.id == "android" && x.selected));
+}
+
+#[test]
+fn modules_cant_be_installed_outside_the_editor() {
+  let hub = FakeHub::new();
+  let app_state = app_state_for(&hub);
+  let archive = pack_editor_tar_xz(&hub, "2022.3.30f1");
+  let android = hub.path().join("share").join("UnitySetup-Android-Support-2022.3.30f1.zip");
+  pack_zip(&android, &[("ivy.xml", "<ivy-module />")]);
+
+  for destination in ["../../outside", "/tmp/outside", "Unity.app/../../outside"] {
+    let module = ModuleArchive {
+      path: android.clone(),
+      id: "android".to_string(),
+      name: "Android Build Support".to_string(),
+      destination: Some(PathBuf::from(destination)),
+      ..Default::default()
+    };
+    let request = InstallRequest { archive: archive.clone(), modules: vec![module] };
+    let err = install::install_from_archives(&request, &app_state, |_| {}).unwrap_err();
+    assert!(matches!(err, AnyError::ModuleDestinationInvalid { .. }), "{}", destination);
+  }
+  assert!(!hub.editor_dir("2022.3.30f1").exists());
+  assert!(!hub.path().join("outside").exists());
+}
@@ -0,0 +1,156 @@
+// batch mode jobs run against the fake editor
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  editor,
+  jobs::{self, JobEvent, JobKind, JobRequest, JobStatus, TestPlatform},
+  launch::{self, LaunchProfile},
+  process::{self, OpenState, ProcessSource},
+  project,
+};
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+fn request(project_path: &PathBuf, kind: JobKind, launch_profile: Option<&str>) -> JobRequest {
+  JobRequest {
+    project_path: project_path.clone(),
+    editor_version: None,
+    kind,
+    launch_profile: launch_profile.map(|x| x.to_string()),
+  }
+}
+
+#[test]
+fn execute_method_streams_log_and_records_history() {
+  let (_hub, app_state, project_path) = setup();
+
+  let kind = JobKind::ExecuteMethod { method: "Build.Perform".to_string() };
+  let (job, env) = jobs::prepare_job(request(&project_path, kind, None), &app_state).unwrap();
+  assert_eq!(job.status, JobStatus::Running);
+  assert_eq!(job.editor_version, "2022.3.10f1");
+  assert_eq!(&job.arguments[..2], &["-projectPath".to_string(), project_path.to_string_lossy().to_string()]);
+  assert!(job.arguments.contains(&"-batchmode".to_string()));
+  assert!(job.arguments.contains(&"-quit".to_string()));
+  assert!(job.log_path.starts_with(project_path.join("Logs")));
+
+  // recorded as running before the editor starts
+  let history = jobs::get_project_jobs(&project_path, &app_state).unwrap();
+  assert_eq!(history.len(), 1);
+  assert_eq!(history[0].status, JobStatus::Running);
+
+  let mut events = vec![];
+  let finished = jobs::run_job(job, &env, &app_state, |x| events.push(x)).unwrap();
+  assert_eq!(finished.status, JobStatus::Succeeded);
+  assert_eq!(finished.exit_code, Some(0));
+  assert!(finished.finished_at.is_some());
+
+  assert!(matches!(events.first(), Some(JobEvent::Started { .. })));
+  assert!(matches!(events.last(), Some(JobEvent::Finished { .. })));
+  let lines = events.iter()
+    .filter_map(|x| match x {
+      JobEvent::Log { line, .. } => Some(line.as_str()),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  assert!(lines.contains(&"Invoking Build.Perform"));
+  // the last line has no newline and still comes through
+  assert_eq!(lines.last(), Some(&"Exiting batchmode"));
+  assert!(events.iter().any(|x| matches!(x, JobEvent::Progress { message, .. } if message == "Compiling Scripts")));
+
+  let history = jobs::get_project_jobs(&project_path, &app_state).unwrap();
+  assert_eq!(history.len(), 1);
+  assert_eq!(history[0].status, JobStatus::Succeeded);
+}
+
+#[test]
+fn test_run_exit_codes_are_mapped() {
+  let (_hub, app_state, project_path) = setup();
+
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_EXIT_CODE".to_string(), "2".to_string());
+  launch::save_profile(LaunchProfile {
+    name: "failing".to_string(),
+    arguments: vec!["-nographics".to_string()],
+    env,
+    project_path: None,
+  }, &app_state).unwrap();
+
+  let kind = JobKind::RunTests { platform: TestPlatform::EditMode, filter: None };
+  let (job, env) = jobs::prepare_job(request(&project_path, kind, Some("failing")), &app_state).unwrap();
+  // the test runner quits by itself, -quit would stop it from running
+  assert!(!job.arguments.contains(&"-quit".to_string()));
+  assert!(job.arguments.contains(&"-testResults".to_string()));
+  assert_eq!(job.arguments.last().map(|x| x.as_str()), Some("-nographics"));
+  assert!(job.results_path.is_some());
+
+  let finished = jobs::run_job(job, &env, &app_state, |_| {}).unwrap();
+  assert_eq!(finished.status, JobStatus::TestsFailed);
+  assert_eq!(finished.exit_code, Some(2));
+
+  let build = JobKind::Build { target: "Android".to_string(), method: "Builds.Android".to_string() };
+  assert_eq!(jobs::status_from_exit_code(&build, Some(2)).0, JobStatus::Failed);
+  assert_eq!(jobs::status_from_exit_code(&build, None).0, JobStatus::Failed);
+}
+
+#[test]
+fn builds_run_their_method() {
+  let build = JobKind::Build { target: "Android".to_string(), method: "Builds.Android".to_string() };
+  let arguments = jobs::build_arguments(&PathBuf::from("Game"), &build, &PathBuf::from("build.log"), None);
+  let execute = arguments.iter().position(|x| x == "-executeMethod").unwrap();
+  assert_eq!(arguments[execute + 1], "Builds.Android");
+
+  // switching the target alone builds nothing
+  let without_method = serde_json::json!({ "type": "build", "target": "Android" });
+  assert!(serde_json::from_value::<JobKind>(without_method).is_err());
+}
+
+#[test]
+fn running_jobs_keep_the_project_open() {
+  let (hub, app_state, project_path) = setup();
+  hub.add_editor("2022.3.20f1");
+  editor::refresh_editor_installs(&app_state).unwrap();
+
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_SLEEP".to_string(), "0.5".to_string());
+  launch::save_profile(LaunchProfile {
+    name: "slow".to_string(),
+    arguments: vec![],
+    env,
+    project_path: None,
+  }, &app_state).unwrap();
+
+  let kind = JobKind::ExecuteMethod { method: "Build.Perform".to_string() };
+  let mut job_request = request(&project_path, kind, Some("slow"));
+  job_request.editor_version = Some("2022.3.20f1".to_string());
+  let (job, env) = jobs::prepare_job(job_request, &app_state).unwrap();
+  // upgrades the project without anyone there to confirm it
+  assert!(job.warning.is_some());
+
+  let mut states = vec![];
+  jobs::run_job(job, &env, &app_state, |event| {
+    if let JobEvent::Log { .. } = event {
+      states.push(process::get_open_state(&project_path, &app_state).unwrap());
+    }
+  }).unwrap();
+  assert!(matches!(&states[0], OpenState::Open { process } if process.source == ProcessSource::Launched));
+
+  assert_eq!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::Closed);
+  assert!(app_state.processes.lock().unwrap().is_empty());
+  // the watcher doesn't mistake the job for an editor session
+  assert!(process::reap_exited(&app_state).unwrap().is_empty());
+}
@@ -0,0 +1,108 @@
+// launch profiles applied when opening a project
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+use common::FakeHub;
+use nomnom_unity_hub::{app::AppState, errors, launch::{self, LaunchProfile}, project};
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+fn profile(name: &str, arguments: &[&str], project_path: Option<&Path>) -> LaunchProfile {
+  LaunchProfile {
+    name: name.to_string(),
+    arguments: arguments.iter().map(|x| x.to_string()).collect(),
+    env: HashMap::new(),
+    project_path: project_path.map(|x| x.to_path_buf()),
+  }
+}
+
+// the fake editor is spawned, give it a moment to write its arguments
+fn wait_for(path: &Path) -> String {
+  for _ in 0..100 {
+    if let Ok(contents) = fs::read_to_string(path) {
+      return contents.trim().to_string();
+    }
+    std::thread::sleep(std::time::Duration::from_millis(20));
+  }
+  panic!("editor never started");
+}
+
+#[test]
+fn project_profile_shadows_global_one() {
+  let (_hub, app_state, project_path) = setup();
+  launch::save_profile(profile("Vulkan", &["-force-vulkan"], None), &app_state).unwrap();
+  launch::save_profile(profile("Vulkan", &["-force-vulkan", "-nographics"], Some(&project_path)), &app_state).unwrap();
+  launch::save_profile(profile("Other", &["-other"], Some(Path::new("/somewhere/else"))), &app_state).unwrap();
+
+  let prefs = app_state.prefs.lock().unwrap().clone();
+  let visible = launch::get_profiles_for_project(&prefs, &project_path);
+  assert_eq!(visible.len(), 2);
+
+  let resolved = launch::resolve_profile(&prefs, &project_path, Some("Vulkan")).unwrap().unwrap();
+  assert_eq!(resolved.arguments, vec!["-force-vulkan", "-nographics"]);
+
+  let elsewhere = launch::resolve_profile(&prefs, Path::new("/another"), Some("Vulkan")).unwrap().unwrap();
+  assert_eq!(elsewhere.arguments, vec!["-force-vulkan"]);
+
+  let err = launch::resolve_profile(&prefs, &project_path, Some("Other")).unwrap_err();
+  assert!(matches!(err, errors::AnyError::LaunchProfileNotFound { .. }));
+}
+
+#[test]
+fn saving_replaces_profile_in_same_scope() {
+  let (_hub, app_state, _project_path) = setup();
+  launch::save_profile(profile("Batch", &["-batchmode"], None), &app_state).unwrap();
+  let profiles = launch::save_profile(profile("Batch", &["-batchmode", "-nographics"], None), &app_state).unwrap();
+  assert_eq!(profiles.len(), 1);
+  assert_eq!(profiles[0].arguments.len(), 2);
+
+  let on_disk = nomnom_unity_hub::app::load_prefs_from_disk(&app_state.config_dir).unwrap();
+  assert_eq!(on_disk.launch_profiles, profiles);
+}
+
+#[test]
+fn open_applies_selected_profile_arguments_and_env() {
+  let (hub, app_state, project_path) = setup();
+  let log = hub.path().join("editor-args.txt");
+
+  let mut vulkan = profile("Vulkan", &["-force-vulkan", "-logFile", "-"], None);
+  vulkan.env.insert("FAKE_EDITOR_LOG".to_string(), log.to_string_lossy().to_string());
+  launch::save_profile(vulkan, &app_state).unwrap();
+  launch::set_project_profile(project_path.clone(), Some("Vulkan".to_string()), &app_state).unwrap();
+
+  project::open_project(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  assert_eq!(wait_for(&log), format!("-projectPath {} -force-vulkan -logFile -", project_path.display()));
+}
+
+#[test]
+fn default_profile_is_used_and_cleared_on_delete() {
+  let (hub, app_state, project_path) = setup();
+  let log = hub.path().join("editor-args.txt");
+
+  let mut batch = profile("Batch", &["-batchmode"], None);
+  batch.env.insert("FAKE_EDITOR_LOG".to_string(), log.to_string_lossy().to_string());
+  launch::save_profile(batch, &app_state).unwrap();
+  app_state.prefs.lock().unwrap().default_launch_profile = Some("Batch".to_string());
+
+  project::open_project(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  assert!(wait_for(&log).ends_with("-batchmode"));
+
+  launch::delete_profile("Batch".to_string(), None, &app_state).unwrap();
+  assert!(app_state.prefs.lock().unwrap().default_launch_profile.is_none());
+
+  // an explicit pick that doesn't exist fails before anything is launched
+  let err = project::open_project_with_profile(project_path, "2022.3.10f1".to_string(), Some("Batch".to_string()), &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::LaunchProfileNotFound { .. }));
+}
@@ -0,0 +1,131 @@
+// every persisted file must upgrade from any older schema
+use std::fs;
+
+use nomnom_unity_hub::{
+  app::{self, Migration},
+  errors, jobs, test_report, version_switch,
+};
+
+fn read_json(path: std::path::PathBuf) -> serde_json::Value {
+  serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn schema_version_defaults_to_zero() {
+  assert_eq!(app::schema_version(&serde_json::json!([])), 0);
+  assert_eq!(app::schema_version(&serde_json::json!({ "hubPath": "/hub" })), 0);
+  assert_eq!(app::schema_version(&serde_json::json!({ "schemaVersion": 3, "data": {} })), 3);
+}
+
+#[test]
+fn migrations_run_in_order_from_file_version() {
+  fn add_a(value: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+    let mut data = value.get("data").cloned().unwrap_or(value.clone());
+    data["steps"] = serde_json::json!(format!("{}a", data["steps"].as_str().unwrap_or("")));
+    Ok(serde_json::json!({ "schemaVersion": 1, "data": data }))
+  }
+  fn add_b(mut value: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+    let steps = value["data"]["steps"].as_str().unwrap().to_string();
+    value["data"]["steps"] = serde_json::json!(format!("{}b", steps));
+    value["schemaVersion"] = serde_json::json!(2);
+    Ok(value)
+  }
+  let migrations: &[Migration] = &[add_a, add_b];
+
+  let from_v0 = app::migrate(serde_json::json!({}), migrations).unwrap();
+  assert_eq!(from_v0, serde_json::json!({ "schemaVersion": 2, "data": { "steps": "ab" } }));
+
+  let from_v1 = app::migrate(serde_json::json!({ "schemaVersion": 1, "data": { "steps": "" } }), migrations).unwrap();
+  assert_eq!(from_v1["data"]["steps"], "b");
+
+  let latest = serde_json::json!({ "schemaVersion": 2, "data": { "steps": "" } });
+  assert_eq!(app::migrate(latest.clone(), migrations).unwrap(), latest);
+
+  let newer = serde_json::json!({ "schemaVersion": 3, "data": {} });
+  assert!(app::migrate(newer, migrations).is_err());
+}
+
+#[test]
+fn prefs_v0_to_v1() {
+  let dir = tempfile::tempdir().unwrap();
+  let v0 = serde_json::json!({
+    "newProjectPath": "/home/me/Projects",
+    "hubPath": "/opt/hub",
+    "hubEditorsPath": "/opt/hub/Editor",
+    "hubAppdataPath": "/home/me/.config/UnityHub",
+    "projectSortType": "Name"
+  });
+  fs::write(dir.path().join("prefs.json"), v0.to_string()).unwrap();
+
+  let prefs = app::load_prefs_from_disk(dir.path()).unwrap();
+  assert_eq!(prefs.hub_editors_path.unwrap().to_str().unwrap(), "/opt/hub/Editor");
+  assert_eq!(prefs.new_project_path.unwrap().to_str().unwrap(), "/home/me/Projects");
+
+  let on_disk = read_json(dir.path().join("prefs.json"));
+  assert_eq!(on_disk["schemaVersion"], app::PREFS_MIGRATIONS.len());
+  assert_eq!(on_disk["data"]["hubPath"], "/opt/hub");
+  // the pre-migration file is kept
+  assert_eq!(read_json(dir.path().join("prefs.json.bak")), v0);
+}
+
+#[test]
+fn user_cache_v0_to_v1() {
+  let dir = tempfile::tempdir().unwrap();
+  let v0 = serde_json::json!({
+    "lastEditorVersion": "2022.3.10f1",
+    "gitPackages": [
+      { "name": "com.me.tools", "version": "https://github.com/me/tools.git", "isDiscoverable": false, "type": "git" }
+    ],
+    "localPackages": []
+  });
+  fs::write(dir.path().join("user_cache.json"), v0.to_string()).unwrap();
+
+  let user_cache = app::load_user_cache_from_disk(dir.path()).unwrap();
+  assert_eq!(user_cache.last_editor_version.as_deref(), Some("2022.3.10f1"));
+  assert_eq!(user_cache.git_packages.len(), 1);
+
+  let on_disk = read_json(dir.path().join("user_cache.json"));
+  assert_eq!(on_disk["schemaVersion"], app::USER_CACHE_MIGRATIONS.len());
+  assert_eq!(on_disk["data"]["gitPackages"][0]["name"], "com.me.tools");
+}
+
+#[test]
+fn projects_v0_to_v1() {
+  let dir = tempfile::tempdir().unwrap();
+  let v0 = serde_json::json!([
+    { "name": "Game", "path": "/projects/Game", "version": "2022.3.10f1", "isPinned": true, "addedAt": 1, "lastOpenedAt": 2 },
+    { "name": "Demo", "path": "/projects/Demo", "version": "2021.3.5f1" }
+  ]);
+  fs::write(dir.path().join("projects.json"), v0.to_string()).unwrap();
+
+  let projects = app::load_projects_from_disk(dir.path()).unwrap();
+  assert_eq!(projects.len(), 2);
+  assert!(projects[0].is_pinned);
+  assert_eq!(projects[1].last_opened_at, 0);
+
+  let on_disk = read_json(dir.path().join("projects.json"));
+  assert_eq!(on_disk["schemaVersion"], app::PROJECTS_MIGRATIONS.len());
+  assert_eq!(on_disk["data"][0]["name"], "Game");
+}
+
+#[test]
+fn newer_files_are_versioned_from_the_start() {
+  // schemaVersion 0 would read as an unversioned file and get wrapped again
+  for migrations in [jobs::JOB_HISTORY_MIGRATIONS, test_report::TEST_REPORTS_MIGRATIONS, version_switch::VERSION_HISTORY_MIGRATIONS] {
+    assert!(!migrations.is_empty());
+  }
+}
+
+#[test]
+fn file_from_newer_app_is_refused_and_left_alone() {
+  let dir = tempfile::tempdir().unwrap();
+  let future = serde_json::json!({ "schemaVersion": 99, "data": [{ "name": "Game" }] });
+  fs::write(dir.path().join("projects.json"), future.to_string()).unwrap();
+
+  let err = errors::AnyError::from(app::load_projects_from_disk(dir.path()).unwrap_err());
+  assert!(matches!(err, errors::AnyError::SchemaTooNew { version: 99, .. }));
+
+  // not quarantined, backed up or replaced
+  assert_eq!(read_json(dir.path().join("projects.json")), future);
+  assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,72 @@
+// modules a project's build target needs vs what the editor has installed
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  modules::{self, BuildTarget},
+};
+
+fn setup(build_settings: Option<(&str, &str)>) -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  if let Some((folder, contents)) = build_settings {
+    fs::create_dir_all(project_path.join(folder)).unwrap();
+    fs::write(project_path.join(folder).join("EditorUserBuildSettings.asset"), contents).unwrap();
+  }
+
+  let app_state = hub.app_state();
+
+  (hub, app_state, project_path)
+}
+
+fn build_settings(target: i32) -> String {
+  format!("%YAML 1.1\n--- !u!162 &1\nEditorUserBuildSettings:\n  m_ActiveBuildTargetGroup: 0\n  m_ActiveBuildTarget: {}\n", target)
+}
+
+#[test]
+fn reports_an_unselected_platform_module_as_missing() {
+  let (_hub, app_state, project_path) = setup(Some(("ProjectSettings", &build_settings(20))));
+
+  let check = modules::check_project_modules(&project_path, "2022.3.10f1", &app_state).unwrap();
+  assert_eq!(check.build_target, Some(BuildTarget { id: 20, name: Some("WebGL".to_string()) }));
+  assert!(check.modules_listed);
+  assert_eq!(check.missing.len(), 1);
+  assert_eq!(check.missing[0].id, "webgl");
+  assert_eq!(check.missing[0].name, "WebGL Build Support");
+}
+
+#[test]
+fn host_standalone_target_needs_nothing() {
+  let (_hub, app_state, project_path) = setup(Some(("Library", &build_settings(24))));
+
+  let check = modules::check_project_modules(&project_path, "2022.3.10f1", &app_state).unwrap();
+  assert_eq!(check.build_target.unwrap().name.as_deref(), Some("StandaloneLinux64"));
+  assert!(check.required.is_empty());
+  assert!(check.missing.is_empty());
+}
+
+#[test]
+fn missing_module_without_an_entry_falls_back_to_its_id() {
+  let (_hub, app_state, project_path) = setup(Some(("ProjectSettings", &build_settings(13))));
+
+  let check = modules::check_project_modules(&project_path, "2022.3.10f1", &app_state).unwrap();
+  assert_eq!(check.missing.iter().map(|x| x.name.as_str()).collect::<Vec<_>>(), vec!["android"]);
+}
+
+#[test]
+fn unknown_build_settings_report_nothing() {
+  let (_hub, app_state, project_path) = setup(None);
+
+  let check = modules::check_project_modules(&project_path, "2022.3.10f1", &app_state).unwrap();
+  assert_eq!(check.build_target, None);
+  assert!(check.missing.is_empty());
+
+  assert!(modules::check_project_modules(&project_path, "6000.0.1f1", &app_state).is_err());
+}
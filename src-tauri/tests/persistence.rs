@@ -0,0 +1,82 @@
+// prefs, projects and user cache must survive crashes and bad edits
+use std::fs;
+
+use nomnom_unity_hub::{app, project::Project};
+
+fn project(name: &str) -> Project {
+  Project {
+    name: name.to_string(),
+    path: format!("/projects/{}", name).into(),
+    version: "2022.3.10f1".to_string(),
+    ..Default::default()
+  }
+}
+
+fn files_in(dir: &std::path::Path) -> Vec<String> {
+  let mut names = fs::read_dir(dir)
+    .unwrap()
+    .map(|x| x.unwrap().file_name().to_string_lossy().to_string())
+    .collect::<Vec<_>>();
+  names.sort();
+  names
+}
+
+#[test]
+fn save_keeps_previous_version_as_backup() {
+  let dir = tempfile::tempdir().unwrap();
+  app::save_projects_to_disk(&vec![project("A")], dir.path()).unwrap();
+  app::save_projects_to_disk(&vec![project("A"), project("B")], dir.path()).unwrap();
+
+  // no temp file left behind
+  assert_eq!(files_in(dir.path()), vec!["projects.json", "projects.json.bak"]);
+
+  let backup: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.path().join("projects.json.bak")).unwrap()).unwrap();
+  assert_eq!(backup["data"].as_array().unwrap().len(), 1);
+
+  let projects = app::load_projects_from_disk(dir.path()).unwrap();
+  assert_eq!(projects.len(), 2);
+}
+
+#[test]
+fn corrupt_file_is_quarantined_and_backup_restored() {
+  let dir = tempfile::tempdir().unwrap();
+  app::save_projects_to_disk(&vec![project("A")], dir.path()).unwrap();
+  app::save_projects_to_disk(&vec![project("A"), project("B")], dir.path()).unwrap();
+
+  // simulate a torn write
+  fs::write(dir.path().join("projects.json"), "[{\"name\": \"A\", \"pa").unwrap();
+
+  let projects = app::load_projects_from_disk(dir.path()).unwrap();
+  assert_eq!(projects.len(), 1);
+  assert_eq!(projects[0].name, "A");
+
+  let files = files_in(dir.path());
+  let quarantined = files
+    .iter()
+    .find(|x| x.starts_with("projects.json.corrupt-"))
+    .expect("corrupt file should be kept");
+  assert_eq!(fs::read_to_string(dir.path().join(quarantined)).unwrap(), "[{\"name\": \"A\", \"pa");
+
+  // the restored list is written back as the main file
+  let on_disk: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.path().join("projects.json")).unwrap()).unwrap();
+  assert_eq!(on_disk["data"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn corrupt_file_without_backup_falls_back_to_default() {
+  let dir = tempfile::tempdir().unwrap();
+  fs::write(dir.path().join("projects.json"), "not json").unwrap();
+
+  let projects = app::load_projects_from_disk(dir.path()).unwrap();
+  assert!(projects.is_empty());
+  assert!(files_in(dir.path()).iter().any(|x| x.starts_with("projects.json.corrupt-")));
+}
+
+#[test]
+fn missing_file_is_created_with_defaults() {
+  let dir = tempfile::tempdir().unwrap();
+
+  let user_cache = app::load_user_cache_from_disk(dir.path()).unwrap();
+  assert!(user_cache.git_packages.is_empty());
+  assert_eq!(files_in(dir.path()), vec!["user_cache.json"]);
+}
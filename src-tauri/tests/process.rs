@@ -0,0 +1,224 @@
+// telling whether a project is open from launched editors, the process table and the lockfile
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  errors,
+  launch::{self, LaunchProfile},
+  process::{self, OpenState, ProcessSource},
+  project,
+};
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  // keeps the fake editor running like a real one
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_SLEEP".to_string(), "30".to_string());
+  launch::save_profile(LaunchProfile {
+    name: "stay open".to_string(),
+    arguments: vec![],
+    env,
+    project_path: None,
+  }, &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+fn kill_launched(app_state: &AppState) {
+  for launched in app_state.processes.lock().unwrap().iter_mut() {
+    launched.child.kill().unwrap();
+    launched.child.wait().unwrap();
+  }
+}
+
+#[test]
+fn launched_editor_is_tracked_until_it_exits() {
+  let (_hub, app_state, project_path) = setup();
+  assert_eq!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::Closed);
+
+  project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("stay open".to_string()), &app_state).unwrap();
+  let pid = app_state.processes.lock().unwrap()[0].child.id();
+
+  let OpenState::Open { process } = process::get_open_state(&project_path, &app_state).unwrap() else {
+    panic!("project should be open");
+  };
+  assert_eq!(process.source, ProcessSource::Launched);
+  assert_eq!(process.pid, Some(pid));
+  assert_eq!(process.editor_version.as_deref(), Some("2022.3.10f1"));
+
+  kill_launched(&app_state);
+  assert_eq!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::Closed);
+
+  // killed, so it counts as a crash
+  let sessions = process::reap_exited(&app_state).unwrap();
+  assert_eq!(sessions.len(), 1);
+  assert_eq!(sessions[0].pid, pid);
+  assert_eq!(sessions[0].exit_code, None);
+  assert!(sessions[0].crashed);
+  assert!(app_state.processes.lock().unwrap().is_empty());
+}
+
+#[test]
+fn sessions_add_up_in_the_usage_report() {
+  let (hub, app_state, project_path) = setup();
+
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_SLEEP".to_string(), "0.2".to_string());
+  launch::save_profile(LaunchProfile {
+    name: "short".to_string(),
+    arguments: vec![],
+    env,
+    project_path: None,
+  }, &app_state).unwrap();
+
+  for _ in 0..2 {
+    project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("short".to_string()), &app_state).unwrap();
+    let session = process::get_session(&project_path, &app_state).unwrap().unwrap();
+    assert_eq!(session.editor_version, "2022.3.10f1");
+    assert_eq!(session.ended_at, None);
+
+    for launched in app_state.processes.lock().unwrap().iter_mut() {
+      launched.child.wait().unwrap();
+    }
+    let sessions = process::reap_exited(&app_state).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].exit_code, Some(0));
+    assert!(!sessions[0].crashed);
+  }
+
+  let usage = project::get_usage_report(&app_state).unwrap();
+  assert_eq!(usage.len(), 1);
+  assert_eq!(usage[0].path, project_path);
+  assert_eq!(usage[0].session_count, 2);
+  assert!(usage[0].total_open_time >= 400);
+  assert_eq!(usage[0].average_session, usage[0].total_open_time / 2);
+
+  // persisted with the project
+  let on_disk = nomnom_unity_hub::app::load_projects_from_disk(hub.path().join("config")).unwrap();
+  assert_eq!(on_disk[0].session_count, 2);
+}
+
+#[test]
+fn editor_started_elsewhere_is_found_by_project_path() {
+  let (hub, app_state, project_path) = setup();
+  project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("stay open".to_string()), &app_state).unwrap();
+  let pid = app_state.processes.lock().unwrap()[0].child.id();
+
+  // a second hub that didn't start it
+  let other = hub.app_state();
+  let OpenState::Open { process } = process::get_open_state(&project_path, &other).unwrap() else {
+    panic!("project should be open");
+  };
+  assert_eq!(process.source, ProcessSource::ProcessTable);
+  assert_eq!(process.pid, Some(pid));
+  assert_eq!(process.editor_version.as_deref(), Some("2022.3.10f1"));
+
+  // a project with the same folder name elsewhere isn't mistaken for it
+  let twin = hub.path().join("Elsewhere").join("Game");
+  fs::create_dir_all(&twin).unwrap();
+  assert_eq!(process::get_open_state(&twin, &other).unwrap(), OpenState::Closed);
+
+  kill_launched(&app_state);
+}
+
+#[test]
+fn leftover_lockfile_is_stale() {
+  let (_hub, app_state, project_path) = setup();
+  let lockfile = process::get_lockfile_path(&project_path);
+  fs::create_dir_all(lockfile.parent().unwrap()).unwrap();
+  fs::write(&lockfile, "").unwrap();
+
+  assert_eq!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::StaleLock { lockfile });
+}
+
+#[test]
+fn locked_lockfile_means_open() {
+  use std::os::fd::AsRawFd;
+
+  let (_hub, app_state, project_path) = setup();
+  let lockfile = process::get_lockfile_path(&project_path);
+  fs::create_dir_all(lockfile.parent().unwrap()).unwrap();
+  // what a running editor started outside the hub holds
+  let held = fs::File::create(&lockfile).unwrap();
+  assert_eq!(unsafe { libc::flock(held.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) }, 0);
+
+  let OpenState::Open { process } = process::get_open_state(&project_path, &app_state).unwrap() else {
+    panic!("expected the project to be open");
+  };
+  assert_eq!(process.source, ProcessSource::Lockfile);
+  assert!(process::remove_stale_lock(&project_path, &app_state).is_err());
+  assert!(lockfile.exists());
+
+  drop(held);
+  assert_eq!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::StaleLock { lockfile: lockfile.clone() });
+
+  // fcntl record locks too, F_GETLK wouldn't report our own
+  let held = fs::OpenOptions::new().write(true).open(&lockfile).unwrap();
+  let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+  lock.l_type = libc::F_WRLCK as libc::c_short;
+  lock.l_whence = libc::SEEK_SET as libc::c_short;
+  assert_eq!(unsafe { libc::fcntl(held.as_raw_fd(), libc::F_SETLK, &lock) }, 0);
+  assert!(matches!(process::get_open_state(&project_path, &app_state).unwrap(), OpenState::Open { .. }));
+}
+
+#[test]
+fn quitting_by_signal_is_not_a_crash() {
+  let (_hub, app_state, project_path) = setup();
+  project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("stay open".to_string()), &app_state).unwrap();
+
+  // what logging out or a window manager's close sends
+  for launched in app_state.processes.lock().unwrap().iter_mut() {
+    assert_eq!(unsafe { libc::kill(launched.child.id() as libc::pid_t, libc::SIGTERM) }, 0);
+    launched.child.wait().unwrap();
+  }
+  let sessions = process::reap_exited(&app_state).unwrap();
+  assert_eq!(sessions.len(), 1);
+  assert_eq!(sessions[0].exit_code, None);
+  assert!(!sessions[0].crashed);
+}
+
+#[test]
+fn opening_an_open_project_is_refused() {
+  let (_hub, app_state, project_path) = setup();
+  project::open_project_with_profile(project_path.clone(), "2022.3.10f1".to_string(), Some("stay open".to_string()), &app_state).unwrap();
+  let pid = app_state.processes.lock().unwrap()[0].child.id();
+
+  let err = project::open_project(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap_err();
+  let errors::AnyError::ProjectAlreadyOpen { process, .. } = err else {
+    panic!("expected ProjectAlreadyOpen, got {:?}", err);
+  };
+  assert_eq!(process.pid, Some(pid));
+  assert_eq!(app_state.processes.lock().unwrap().len(), 1);
+
+  // the lock belongs to a live editor
+  let err = process::remove_stale_lock(&project_path, &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::ProjectAlreadyOpen { .. }));
+
+  kill_launched(&app_state);
+}
+
+#[test]
+fn stale_lock_blocks_opening_until_removed() {
+  let (_hub, app_state, project_path) = setup();
+  let lockfile = process::get_lockfile_path(&project_path);
+  fs::create_dir_all(lockfile.parent().unwrap()).unwrap();
+  fs::write(&lockfile, "").unwrap();
+
+  let err = project::open_project(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::ProjectLockStale { .. }));
+
+  process::remove_stale_lock(&project_path, &app_state).unwrap();
+  assert!(!lockfile.exists());
+  project::open_project(project_path, "2022.3.10f1".to_string(), &app_state).unwrap();
+}
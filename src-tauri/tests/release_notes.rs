@@ -0,0 +1,141 @@
+// release notes from the editor docs or a mirror, cached and searchable
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  context::HubContext,
+  errors,
+  release_notes::{self, ReleaseNotesSource},
+};
+
+const NOTES_HTML: &str = "<html><body>\
+<h2>Fixes</h2>\
+<ul><li>Editor: Fixed a crash when &lt;b&gt; tags were nested.</li>\
+<li>Android: Fixed Gradle builds on ARM64.</li></ul>\
+<p>Known issues</p><p>None.</p>\
+</body></html>";
+
+fn add_bundled_notes(hub: &FakeHub, version: &str, contents: &str) {
+  let docs = hub.editor_dir(version)
+    .join("Unity.app")
+    .join("Contents")
+    .join("Documentation")
+    .join("en");
+  fs::create_dir_all(&docs).unwrap();
+  fs::write(docs.join("ReleaseNotes.html"), contents).unwrap();
+}
+
+#[test]
+fn strips_html_into_lines() {
+  assert_eq!(release_notes::html_to_text(NOTES_HTML), "\
+Fixes
+- Editor: Fixed a crash when <b> tags were nested.
+- Android: Fixed Gradle builds on ARM64.
+Known issues
+None.");
+}
+
+#[test]
+fn bundled_notes_are_cached_until_refreshed() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  add_bundled_notes(&hub, "2022.3.10f1", NOTES_HTML);
+
+  let notes = release_notes::get_release_notes("2022.3.10f1", false, &hub).unwrap();
+  assert!(matches!(notes.source, ReleaseNotesSource::Bundled { .. }));
+  assert!(notes.text.contains("Fixed Gradle builds"));
+  assert!(hub.cache_dir().join("release_notes").join("2022.3.10f1.json").is_file());
+
+  add_bundled_notes(&hub, "2022.3.10f1", "<p>Rewritten</p>");
+  assert_eq!(release_notes::get_release_notes("2022.3.10f1", false, &hub).unwrap(), notes);
+  assert_eq!(release_notes::get_release_notes("2022.3.10f1", true, &hub).unwrap().text, "Rewritten");
+}
+
+#[test]
+fn falls_back_to_a_mirror_on_disk() {
+  let mut hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  let mirror = hub.path().join("share").join("notes");
+  fs::create_dir_all(&mirror).unwrap();
+  fs::write(mirror.join("2021.3.5f1.txt"), "Fixed shader stripping\n").unwrap();
+
+  // nothing bundled and no mirror configured
+  let err = release_notes::get_release_notes("2021.3.5f1", false, &hub).unwrap_err();
+  assert!(matches!(err, errors::AnyError::ReleaseNotesNotFound { .. }));
+
+  hub.prefs.release_notes_url = Some(format!("file://{}/{{version}}.txt", mirror.display()));
+  let notes = release_notes::get_release_notes("2021.3.5f1", false, &hub).unwrap();
+  assert_eq!(notes.source, ReleaseNotesSource::Mirror {
+    url: format!("file://{}/2021.3.5f1.txt", mirror.display()),
+  });
+  assert_eq!(notes.text, "Fixed shader stripping");
+
+  // versions the mirror doesn't have are still missing
+  let err = release_notes::get_release_notes("2020.1.0f1", false, &hub).unwrap_err();
+  assert!(matches!(err, errors::AnyError::ReleaseNotesNotFound { .. }));
+
+  // a version is part of the cache file name and the mirror path
+  fs::write(hub.path().join("share").join("secret.txt"), "not notes").unwrap();
+  let err = release_notes::get_release_notes("../secret", false, &hub).unwrap_err();
+  assert!(matches!(err, errors::AnyError::VersionInvalid { .. }));
+  assert!(release_notes::record_failed_fetch("../../escaped", "https://notes.example.com", &hub).is_err());
+  assert!(!hub.cache_dir().join("escaped.failed.json").exists());
+}
+
+#[test]
+fn searches_every_installed_version() {
+  let hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  hub.add_editor("2022.3.10f1");
+  hub.add_editor("2023.1.0f1");
+  add_bundled_notes(&hub, "2021.3.5f1", "<li>Android: Fixed a memory leak.</li><li>UI: Fixed text.</li>");
+  add_bundled_notes(&hub, "2022.3.10f1", NOTES_HTML);
+
+  let matches = release_notes::search("android", &hub).unwrap();
+  assert_eq!(matches.iter().map(|x| x.version.as_str()).collect::<Vec<_>>(), vec!["2022.3.10f1", "2021.3.5f1"]);
+  assert_eq!(matches[1].lines, vec!["- Android: Fixed a memory leak."]);
+
+  assert!(release_notes::search("  ", &hub).unwrap().is_empty());
+  assert!(release_notes::search("vulkan", &hub).unwrap().is_empty());
+}
+
+#[test]
+fn prefers_the_release_notes_file_over_other_pages() {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  let docs = hub.editor_dir("2022.3.10f1")
+    .join("Unity.app")
+    .join("Contents")
+    .join("Documentation")
+    .join("en");
+  fs::create_dir_all(docs.join("Manual")).unwrap();
+  fs::write(docs.join("Manual").join("WhatsNew2022.html"), "<p>New features</p>").unwrap();
+  fs::write(docs.join("Manual").join("UpgradeGuideReleaseNotes.html"), "<p>Upgrading</p>").unwrap();
+  fs::write(docs.join("Manual").join("changelog.md"), "Changes").unwrap();
+  fs::write(docs.join("ReleaseNotes.html"), NOTES_HTML).unwrap();
+
+  let install = hub.find_editor("2022.3.10f1").unwrap();
+  assert_eq!(release_notes::find_bundled(&install), Some(docs.join("ReleaseNotes.html")));
+
+  fs::remove_file(docs.join("ReleaseNotes.html")).unwrap();
+  assert_eq!(release_notes::find_bundled(&install), Some(docs.join("Manual").join("UpgradeGuideReleaseNotes.html")));
+}
+
+#[test]
+fn failed_fetches_are_remembered_per_url() {
+  let hub = FakeHub::new();
+  let url = "https://notes.example.com/2022.3.10f1.html";
+
+  assert!(!release_notes::recently_failed("2022.3.10f1", url, &hub));
+  release_notes::record_failed_fetch("2022.3.10f1", url, &hub).unwrap();
+  assert!(release_notes::recently_failed("2022.3.10f1", url, &hub));
+
+  // a different mirror or version is tried right away
+  assert!(!release_notes::recently_failed("2022.3.10f1", "https://other.example.com/2022.3.10f1.html", &hub));
+  assert!(!release_notes::recently_failed("2021.3.5f1", url, &hub));
+  assert!(release_notes::get_cached("2022.3.10f1", &hub).is_none());
+}
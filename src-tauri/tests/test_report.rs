@@ -0,0 +1,197 @@
+// unity test runner results parsed into reports and compared between runs
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+use common::FakeHub;
+use nomnom_unity_hub::{
+  app::AppState,
+  errors,
+  jobs::{self, JobKind, JobRequest, JobStatus, TestPlatform},
+  launch::{self, LaunchProfile},
+  project,
+  test_report::{self, TestResult},
+};
+
+const FIRST_RUN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<test-run id="2" testcasecount="3" result="Failed(Child)" total="3" passed="2" failed="1" duration="1.5">
+  <test-suite type="TestSuite" name="Game" fullname="Game" result="Failed" duration="1.5">
+    <test-suite type="Assembly" name="Game.Tests.dll" fullname="Game.Tests.dll" result="Failed" duration="1.4">
+      <test-suite type="TestFixture" name="PlayerTests" fullname="Game.Tests.PlayerTests" result="Failed" duration="1.2">
+        <test-case name="Jumps" fullname="Game.Tests.PlayerTests.Jumps" result="Passed" duration="0.2" />
+        <test-case name="Runs" fullname="Game.Tests.PlayerTests.Runs" result="Failed" duration="0.9">
+          <failure>
+            <message><![CDATA[Expected: 5
+  But was:  3]]></message>
+            <stack-trace><![CDATA[at Game.Tests.PlayerTests.Runs () [0x00001] in Assets/Tests/PlayerTests.cs:12]]></stack-trace>
+          </failure>
+        </test-case>
+      </test-suite>
+      <test-suite type="TestFixture" name="SaveTests" fullname="Game.Tests.SaveTests" result="Passed" duration="0.1">
+        <test-case name="Loads" fullname="Game.Tests.SaveTests.Loads" result="Passed" duration="0.1" />
+      </test-suite>
+    </test-suite>
+  </test-suite>
+</test-run>
+"#;
+
+const SECOND_RUN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<test-run id="2" testcasecount="3" result="Failed(Child)" duration="1.1">
+  <test-suite type="TestFixture" name="PlayerTests" fullname="Game.Tests.PlayerTests" result="Failed" duration="1.0">
+    <test-case name="Jumps" fullname="Game.Tests.PlayerTests.Jumps" result="Failed" duration="0.2">
+      <failure><message>Fell through the floor</message></failure>
+    </test-case>
+    <test-case name="Runs" fullname="Game.Tests.PlayerTests.Runs" result="Passed" duration="0.8" />
+    <test-case name="Swims" fullname="Game.Tests.PlayerTests.Swims" result="Skipped" label="Ignored" duration="0">
+      <reason><message>Water isn't in yet</message></reason>
+    </test-case>
+  </test-suite>
+</test-run>
+"#;
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+// the fake editor copies the results file a profile points it at
+fn run_with_results(hub: &FakeHub, app_state: &AppState, project_path: &Path, name: &str, xml: &str, exit_code: &str, platform: TestPlatform) -> (jobs::Job, Option<test_report::TestRunReport>) {
+  let results = hub.path().join(format!("{}.xml", name));
+  fs::write(&results, xml).unwrap();
+
+  let mut env = HashMap::new();
+  env.insert("FAKE_EDITOR_TEST_RESULTS".to_string(), results.to_string_lossy().to_string());
+  env.insert("FAKE_EDITOR_EXIT_CODE".to_string(), exit_code.to_string());
+  launch::save_profile(LaunchProfile {
+    name: name.to_string(),
+    arguments: vec![],
+    env,
+    project_path: None,
+  }, app_state).unwrap();
+
+  let request = JobRequest {
+    project_path: project_path.to_path_buf(),
+    editor_version: None,
+    kind: JobKind::RunTests { platform, filter: None },
+    launch_profile: Some(name.to_string()),
+  };
+  let (job, env) = jobs::prepare_job(request, app_state).unwrap();
+  test_report::run_tests(job, &env, app_state, |_| {}).unwrap()
+}
+
+#[test]
+fn parses_suites_cases_and_failures() {
+  let (hub, app_state, project_path) = setup();
+
+  let (job, report) = run_with_results(&hub, &app_state, &project_path, "first", FIRST_RUN, "2", TestPlatform::EditMode);
+  assert_eq!(job.status, JobStatus::TestsFailed);
+
+  let report = report.unwrap();
+  assert_eq!(report.id, job.id);
+  assert_eq!(report.platform, Some(TestPlatform::EditMode));
+  assert_eq!(report.result, TestResult::Failed);
+  assert_eq!((report.total, report.passed, report.failed, report.skipped), (3, 2, 1, 0));
+  assert_eq!(report.duration, 1.5);
+
+  // only fixtures that hold cases, nested suites are flattened
+  let suites = report.suites.iter().map(|x| x.full_name.as_str()).collect::<Vec<_>>();
+  assert_eq!(suites, vec!["Game.Tests.PlayerTests", "Game.Tests.SaveTests"]);
+
+  let runs = report.cases().find(|x| x.name == "Runs").unwrap();
+  assert_eq!(runs.duration, 0.9);
+  let failure = runs.failure.as_ref().unwrap();
+  assert!(failure.message.starts_with("Expected: 5"));
+  assert!(failure.stack_trace.as_ref().unwrap().contains("PlayerTests.cs:12"));
+
+  let stored = test_report::get_project_reports(&project_path, &app_state).unwrap();
+  assert_eq!(stored, vec![report]);
+}
+
+#[test]
+fn compares_the_last_two_runs() {
+  let (hub, app_state, project_path) = setup();
+
+  let err = test_report::compare_last_runs(&project_path, &app_state).unwrap_err();
+  assert!(matches!(err, errors::AnyError::NoTestRuns { .. }));
+
+  run_with_results(&hub, &app_state, &project_path, "first", FIRST_RUN, "2", TestPlatform::EditMode);
+  let first = test_report::compare_last_runs(&project_path, &app_state).unwrap();
+  assert_eq!(first.previous_id, None);
+  assert_eq!(first.newly_failing, vec!["Game.Tests.PlayerTests.Runs"]);
+
+  let (_, second) = run_with_results(&hub, &app_state, &project_path, "second", SECOND_RUN, "2", TestPlatform::EditMode);
+  let second = second.unwrap();
+  let swims = second.cases().find(|x| x.name == "Swims").unwrap();
+  assert_eq!(swims.result, TestResult::Skipped);
+  assert_eq!(swims.failure.as_ref().unwrap().message, "Water isn't in yet");
+
+  let comparison = test_report::compare_last_runs(&project_path, &app_state).unwrap();
+  assert!(comparison.previous_id.is_some());
+  assert_eq!(comparison.latest_id, second.id);
+  assert_eq!(comparison.newly_failing, vec!["Game.Tests.PlayerTests.Jumps"]);
+  assert!(comparison.still_failing.is_empty());
+  assert_eq!(comparison.fixed, vec!["Game.Tests.PlayerTests.Runs"]);
+  assert_eq!(comparison.added, vec!["Game.Tests.PlayerTests.Swims"]);
+  assert_eq!(comparison.removed, vec!["Game.Tests.SaveTests.Loads"]);
+}
+
+#[test]
+fn compares_against_the_last_run_on_the_same_platform() {
+  let (hub, app_state, project_path) = setup();
+
+  let (_, first) = run_with_results(&hub, &app_state, &project_path, "first", FIRST_RUN, "2", TestPlatform::EditMode);
+  // play mode runs other tests, its first run has nothing to compare with
+  run_with_results(&hub, &app_state, &project_path, "play", SECOND_RUN, "2", TestPlatform::PlayMode);
+  let comparison = test_report::compare_last_runs(&project_path, &app_state).unwrap();
+  assert_eq!(comparison.previous_id, None);
+
+  let (_, third) = run_with_results(&hub, &app_state, &project_path, "third", FIRST_RUN, "2", TestPlatform::EditMode);
+  let comparison = test_report::compare_last_runs(&project_path, &app_state).unwrap();
+  assert_eq!(comparison.previous_id, Some(first.unwrap().id));
+  assert_eq!(comparison.latest_id, third.unwrap().id);
+  assert!(comparison.newly_failing.is_empty() && comparison.added.is_empty() && comparison.removed.is_empty());
+  assert_eq!(comparison.still_failing, vec!["Game.Tests.PlayerTests.Runs"]);
+}
+
+#[test]
+fn missing_results_give_no_report() {
+  let (_hub, app_state, project_path) = setup();
+
+  let request = JobRequest {
+    project_path: project_path.clone(),
+    editor_version: None,
+    kind: JobKind::RunTests { platform: TestPlatform::PlayMode, filter: None },
+    launch_profile: None,
+  };
+  let (job, env) = jobs::prepare_job(request, &app_state).unwrap();
+  let (_, report) = test_report::run_tests(job, &env, &app_state, |_| {}).unwrap();
+  assert!(report.is_none());
+  assert!(test_report::get_project_reports(&project_path, &app_state).unwrap().is_empty());
+}
+
+#[test]
+fn broken_results_are_reported_as_unreadable() {
+  let (_hub, app_state, project_path) = setup();
+
+  let request = JobRequest {
+    project_path: project_path.clone(),
+    editor_version: None,
+    kind: JobKind::RunTests { platform: TestPlatform::EditMode, filter: None },
+    launch_profile: None,
+  };
+  let (job, _) = jobs::prepare_job(request, &app_state).unwrap();
+  // unity killed partway through writing them
+  let results_path = job.results_path.clone().unwrap();
+  fs::write(&results_path, "<test-run><test-suite").unwrap();
+
+  let err = test_report::load_report(&results_path, &job).unwrap_err();
+  assert!(matches!(err, errors::AnyError::TestResultsUnreadable { path, .. } if path == results_path));
+}
@@ -0,0 +1,134 @@
+// removing an editor install without leaving projects or caches pointing at it
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{add_editor_at, FakeHub};
+use nomnom_unity_hub::{
+  app::AppState,
+  context::HubContext,
+  editor,
+  errors::AnyError,
+  project,
+  template::{self, EditorVersionPackageList},
+  uninstall,
+};
+
+fn setup() -> (FakeHub, AppState, PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2022.3.10f1");
+  hub.add_editor("2022.3.20f1");
+
+  let project_path = hub.add_project("Game", "2022.3.10f1");
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+#[test]
+fn refuses_while_a_project_uses_the_version() {
+  let (hub, app_state, project_path) = setup();
+
+  let err = uninstall::uninstall_editor("2022.3.10f1", None, &app_state, |_| {}).unwrap_err();
+  match err {
+    AnyError::EditorInUse { version, projects } => {
+      assert_eq!(version, "2022.3.10f1");
+      assert_eq!(projects, vec![project_path]);
+    }
+    err => panic!("unexpected error: {:?}", err),
+  }
+  assert!(hub.editor_dir("2022.3.10f1").is_dir());
+}
+
+#[test]
+fn reassigns_projects_then_removes_the_install() {
+  let (hub, app_state, project_path) = setup();
+  let editor_dir = hub.editor_dir("2022.3.10f1");
+  let install = app_state.find_editor("2022.3.10f1").unwrap();
+
+  // caches the hub keeps for the version
+  editor::estimate_size(&install, &app_state).unwrap();
+  fs::create_dir_all(app_state.cache_dir().join("templates")).unwrap();
+  let packages = EditorVersionPackageList { packages: Default::default(), manifest_packages: Default::default() };
+  template::write_editor_version_packages(&app_state, &"2022.3.10f1".to_string(), &packages).unwrap();
+  template::write_editor_version_packages(&app_state, &"2022.3.20f1".to_string(), &packages).unwrap();
+
+  let mut progress = vec![];
+  let result = uninstall::uninstall_editor("2022.3.10f1", Some("2022.3.20f1"), &app_state, |x| progress.push(x)).unwrap();
+
+  assert_eq!(result.install_path, editor_dir);
+  assert!(!editor_dir.exists());
+  assert_eq!(result.reassigned.len(), 1);
+  assert_eq!(result.reassigned[0].to_version, "2022.3.20f1");
+  assert_eq!(project::load(&project_path).unwrap().version, "2022.3.20f1");
+
+  let last = progress.last().unwrap();
+  assert_eq!((last.removed, last.total), (result.removed_files, result.removed_files));
+
+  let versions = app_state.editors().unwrap().into_iter().map(|x| x.version).collect::<Vec<_>>();
+  assert_eq!(versions, vec!["2022.3.20f1"]);
+
+  let size_cache = fs::read_to_string(app_state.cache_dir().join("editors.json")).unwrap();
+  assert!(!size_cache.contains(install.exe_path.to_str().unwrap()));
+  let templates = app_state.cache_dir().join("templates");
+  assert!(!templates.join("2022_3_10f1.json").exists());
+  assert!(templates.join("2022_3_20f1.json").exists());
+}
+
+#[test]
+fn unused_version_needs_no_reassign() {
+  let (hub, app_state, _) = setup();
+
+  uninstall::uninstall_editor("2022.3.20f1", None, &app_state, |_| {}).unwrap();
+  assert!(!hub.editor_dir("2022.3.20f1").exists());
+  assert!(uninstall::uninstall_editor("2022.3.20f1", None, &app_state, |_| {}).is_err());
+}
+
+#[test]
+fn switches_nothing_when_a_dependent_cant_be_moved() {
+  let (hub, app_state, project_path) = setup();
+  let broken_path = hub.add_project("Broken", "2022.3.10f1");
+  project::add_project(broken_path.clone(), &app_state).unwrap();
+  fs::remove_dir_all(broken_path.join("Assets")).unwrap();
+
+  let err = uninstall::uninstall_editor("2022.3.10f1", Some("2022.3.20f1"), &app_state, |_| {}).unwrap_err();
+  match err {
+    AnyError::EditorInUse { projects, .. } => assert_eq!(projects, vec![broken_path]),
+    err => panic!("unexpected error: {:?}", err),
+  }
+  assert_eq!(project::load(&project_path).unwrap().version, "2022.3.10f1");
+  assert!(hub.editor_dir("2022.3.10f1").is_dir());
+}
+
+#[test]
+fn manual_editors_outside_the_search_roots_are_only_forgotten() {
+  let (hub, app_state, _) = setup();
+  // e.g. /Applications/Unity.app, the folder above it holds other things too
+  let tools = hub.path().join("Tools");
+  add_editor_at(&tools, "2021.3.5f1");
+  fs::write(tools.join("notes.txt"), "keep me").unwrap();
+  editor::add_manual_editor(tools.join("Unity.app"), &app_state).unwrap();
+
+  let result = uninstall::uninstall_editor("2021.3.5f1", None, &app_state, |_| {}).unwrap();
+  assert!(!result.deleted);
+  assert_eq!(result.removed_files, 0);
+  assert!(tools.join("notes.txt").is_file());
+  assert!(tools.join("Unity.app").is_dir());
+  assert!(app_state.prefs().unwrap().manual_editor_paths.is_empty());
+  assert!(app_state.find_editor("2021.3.5f1").is_err());
+}
+
+#[test]
+fn renamed_folders_in_a_search_root_are_not_deleted() {
+  let (hub, app_state, _) = setup();
+  let renamed = hub.editor_dir("2022.3-lts");
+  add_editor_at(&renamed, "2022.3.30f1");
+  editor::refresh_editor_installs(&app_state).unwrap();
+
+  let err = uninstall::uninstall_editor("2022.3.30f1", None, &app_state, |_| {}).unwrap_err();
+  assert!(matches!(err, AnyError::EditorInvalid { .. }));
+  assert!(renamed.join("Unity.app").is_dir());
+}
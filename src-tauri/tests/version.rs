@@ -0,0 +1,79 @@
+// unity version parsing, ordering and classification
+use std::cmp::Ordering;
+
+use nomnom_unity_hub::{
+  errors,
+  version::{self, ReleaseKind, ReleaseStream, UnityVersion},
+};
+
+fn v(s: &str) -> UnityVersion {
+  s.parse().unwrap()
+}
+
+#[test]
+fn parses_every_stream() {
+  assert_eq!(v("2022.3.10f1"), UnityVersion { major: 2022, minor: 3, patch: 10, stream: ReleaseStream::Final, build: 1, china_build: None });
+  assert_eq!(v("2023.1.0a12").stream, ReleaseStream::Alpha);
+  assert_eq!(v("2023.1.0a12").build, 12);
+  assert_eq!(v("2023.2.0b3").stream, ReleaseStream::Beta);
+  assert_eq!(v("2019.4.0c1").stream, ReleaseStream::Candidate);
+  assert_eq!(v("2018.4.36p2").stream, ReleaseStream::Patch);
+  assert_eq!(v("6000.0.23f1").major, 6000);
+  // china builds
+  assert_eq!(v("2022.3.10f1c1"), UnityVersion { china_build: Some(1), ..v("2022.3.10f1") });
+  assert_eq!(v("2020.3.48f1c2").china_build, Some(2));
+}
+
+#[test]
+fn display_round_trips() {
+  for s in ["2022.3.10f1", "6000.0.23f1", "2023.1.0a12", "2018.4.36p2", "2022.3.10f1c1"] {
+    assert_eq!(v(s).to_string(), s);
+  }
+  assert_eq!(serde_json::to_value(v("2022.3.10f1")).unwrap(), "2022.3.10f1");
+  assert_eq!(serde_json::from_value::<UnityVersion>(serde_json::json!("6000.0.23f1")).unwrap(), v("6000.0.23f1"));
+}
+
+#[test]
+fn rejects_malformed_versions() {
+  for s in ["", "2022", "2022.3", "2022.3.10", "2022.3-lts", "2022.3.10x1", "2022.3.f1", "2022.3.10f", "six.0.1f1", "2022.3.10f1c", "2022.3.10f1cx", "2022.3.10fc1"] {
+    let err = s.parse::<UnityVersion>().unwrap_err();
+    assert!(matches!(err, errors::AnyError::VersionInvalid { .. }), "{}", s);
+  }
+}
+
+#[test]
+fn orders_numerically_then_by_stream() {
+  assert!(v("2022.3.9f1") < v("2022.3.10f1"));
+  assert!(v("2021.3.35f1") < v("2022.1.0f1"));
+  assert!(v("2023.2.20f1") < v("6000.0.1f1"));
+  assert!(v("2023.1.0a12") < v("2023.1.0b1"));
+  assert!(v("2023.1.0b9") < v("2023.1.0c1"));
+  assert!(v("2023.1.0c1") < v("2023.1.0f1"));
+  assert!(v("2023.1.0f1") < v("2023.1.0p1"));
+  assert!(v("2023.1.0f1") < v("2023.1.0f2"));
+  assert!(v("2022.3.10f1") < v("2022.3.10f1c1"));
+  assert!(v("2022.3.10f1c1") < v("2022.3.11f1"));
+}
+
+#[test]
+fn classifies_release_kind() {
+  assert_eq!(v("2019.4.40f1").kind(), ReleaseKind::Lts);
+  assert_eq!(v("2019.3.15f1").kind(), ReleaseKind::Tech);
+  assert_eq!(v("2022.3.10f1").kind(), ReleaseKind::Lts);
+  assert_eq!(v("2023.2.20f1").kind(), ReleaseKind::Tech);
+  // only ever had betas before it became unity 6
+  assert_eq!(v("2023.3.0f1").kind(), ReleaseKind::Tech);
+  assert_eq!(v("2022.3.10f1c1").kind(), ReleaseKind::Lts);
+  assert_eq!(v("6000.0.23f1").kind(), ReleaseKind::Lts);
+  assert_eq!(v("6000.1.0f1").kind(), ReleaseKind::Tech);
+  assert_eq!(v("6000.1.0b2").kind(), ReleaseKind::Beta);
+  assert_eq!(v("6000.2.0a1").kind(), ReleaseKind::Alpha);
+  assert!(v("2023.1.0c1").is_prerelease());
+}
+
+#[test]
+fn compare_puts_unparseable_strings_first() {
+  assert_eq!(version::compare("2022.3.10f1", "2022.3.9f1"), Ordering::Greater);
+  assert_eq!(version::compare("2022.3-lts", "2019.4.0f1"), Ordering::Less);
+  assert_eq!(version::compare("abc", "abd"), Ordering::Less);
+}
@@ -0,0 +1,145 @@
+// switching a project's editor version and rolling it back
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+
+use common::FakeHub;
+use nomnom_unity_hub::{app::AppState, errors::AnyError, process, project, version_switch};
+
+fn setup() -> (FakeHub, AppState, std::path::PathBuf) {
+  let hub = FakeHub::new();
+  hub.add_editor("2021.3.5f1");
+  hub.add_editor("2022.3.10f1");
+
+  let project_path = hub.add_project("Game", "2021.3.5f1");
+  fs::create_dir_all(project_path.join("Packages")).unwrap();
+  fs::write(
+    project_path.join("ProjectSettings").join("ProjectVersion.txt"),
+    "m_EditorVersion: 2021.3.5f1\nm_EditorVersionWithRevision: 2021.3.5f1 (40eb3a945986)\n",
+  ).unwrap();
+  fs::write(project_path.join("ProjectSettings").join("ProjectSettings.asset"), "old settings").unwrap();
+  fs::write(project_path.join("Packages").join("manifest.json"), r#"{"dependencies":{}}"#).unwrap();
+
+  let app_state = hub.app_state();
+  project::add_project(project_path.clone(), &app_state).unwrap();
+
+  (hub, app_state, project_path)
+}
+
+#[test]
+fn switch_updates_project_version_and_records_history() {
+  let (_hub, app_state, project_path) = setup();
+
+  let record = version_switch::switch_editor_version(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  assert_eq!(record.from_version, "2021.3.5f1");
+  assert_eq!(record.from_revision.as_deref(), Some("40eb3a945986"));
+  assert_eq!(record.to_version, "2022.3.10f1");
+  assert_eq!(record.to_revision.as_deref(), Some("abc123def456"));
+
+  let version_txt = fs::read_to_string(project_path.join("ProjectSettings").join("ProjectVersion.txt")).unwrap();
+  assert_eq!(version_txt, "m_EditorVersion: 2022.3.10f1\nm_EditorVersionWithRevision: 2022.3.10f1 (abc123def456)\n");
+  assert_eq!(project::load(&project_path).unwrap().version, "2022.3.10f1");
+  assert_eq!(app_state.projects.lock().unwrap()[0].version, "2022.3.10f1");
+
+  // snapshot holds the files from before the switch
+  assert_eq!(fs::read_to_string(record.snapshot_path.join("ProjectSettings").join("ProjectSettings.asset")).unwrap(), "old settings");
+  assert!(record.snapshot_path.join("Packages").join("manifest.json").is_file());
+  assert!(!record.snapshot_path.join("Packages").join("packages-lock.json").exists());
+
+  let history = version_switch::get_project_history(&project_path, &app_state).unwrap();
+  assert_eq!(history.len(), 1);
+  assert_eq!(history[0].id, record.id);
+}
+
+#[test]
+fn rollback_restores_snapshot() {
+  let (_hub, app_state, project_path) = setup();
+  let record = version_switch::switch_editor_version(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+
+  // what the new editor would do on open
+  fs::write(project_path.join("ProjectSettings").join("ProjectSettings.asset"), "upgraded settings").unwrap();
+  fs::write(project_path.join("ProjectSettings").join("NewSettings.asset"), "new").unwrap();
+  fs::write(project_path.join("Packages").join("packages-lock.json"), "{}").unwrap();
+
+  let rolled_back = version_switch::rollback(project_path.clone(), record.id, &app_state).unwrap();
+  assert!(rolled_back.rolled_back_at.is_some());
+
+  assert_eq!(project::load(&project_path).unwrap().version, "2021.3.5f1");
+  assert_eq!(version_switch::read_project_revision(&project_path).as_deref(), Some("40eb3a945986"));
+  assert_eq!(fs::read_to_string(project_path.join("ProjectSettings").join("ProjectSettings.asset")).unwrap(), "old settings");
+  assert!(!project_path.join("ProjectSettings").join("NewSettings.asset").exists());
+  assert!(!project_path.join("Packages").join("packages-lock.json").exists());
+  assert_eq!(app_state.projects.lock().unwrap()[0].version, "2021.3.5f1");
+
+  let history = version_switch::get_project_history(&project_path, &app_state).unwrap();
+  assert!(history[0].rolled_back_at.is_some());
+}
+
+#[test]
+fn switch_to_missing_editor_changes_nothing() {
+  let (_hub, app_state, project_path) = setup();
+
+  assert!(version_switch::switch_editor_version(project_path.clone(), "6000.0.23f1".to_string(), &app_state).is_err());
+  assert_eq!(project::load(&project_path).unwrap().version, "2021.3.5f1");
+  assert!(version_switch::get_project_history(&project_path, &app_state).unwrap().is_empty());
+}
+
+#[test]
+fn back_to_back_switches_get_their_own_snapshots() {
+  let (hub, app_state, project_path) = setup();
+  let other_path = hub.add_project("Other", "2021.3.5f1");
+  fs::write(other_path.join("ProjectSettings").join("ProjectSettings.asset"), "other settings").unwrap();
+  project::add_project(other_path.clone(), &app_state).unwrap();
+
+  // switched within the same millisecond, the way an uninstall reassigns projects
+  let first = version_switch::switch_editor_version(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  let second = version_switch::switch_editor_version(other_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  assert_ne!(first.snapshot_path, second.snapshot_path);
+
+  version_switch::rollback(project_path.clone(), first.id, &app_state).unwrap();
+  assert_eq!(fs::read_to_string(project_path.join("ProjectSettings").join("ProjectSettings.asset")).unwrap(), "old settings");
+  version_switch::rollback(other_path.clone(), second.id, &app_state).unwrap();
+  assert_eq!(fs::read_to_string(other_path.join("ProjectSettings").join("ProjectSettings.asset")).unwrap(), "other settings");
+}
+
+#[test]
+fn only_the_newest_switch_rolls_back_once() {
+  let (_hub, app_state, project_path) = setup();
+  let first = version_switch::switch_editor_version(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+  let second = version_switch::switch_editor_version(project_path.clone(), "2021.3.5f1".to_string(), &app_state).unwrap();
+
+  // the first snapshot would undo the second switch
+  let err = version_switch::rollback(project_path.clone(), first.id, &app_state).unwrap_err();
+  assert!(matches!(err, AnyError::VersionSwitchNotLatest { .. }));
+
+  version_switch::rollback(project_path.clone(), second.id, &app_state).unwrap();
+  let err = version_switch::rollback(project_path.clone(), second.id, &app_state).unwrap_err();
+  assert!(matches!(err, AnyError::VersionSwitchNotLatest { .. }));
+
+  version_switch::rollback(project_path.clone(), first.id, &app_state).unwrap();
+  assert_eq!(project::load(&project_path).unwrap().version, "2021.3.5f1");
+
+  let err = version_switch::rollback(project_path.clone(), 42, &app_state).unwrap_err();
+  assert!(matches!(err, AnyError::VersionSwitchNotFound { .. }));
+}
+
+#[test]
+fn an_open_project_is_left_alone() {
+  use std::os::fd::AsRawFd;
+
+  let (_hub, app_state, project_path) = setup();
+  let record = version_switch::switch_editor_version(project_path.clone(), "2022.3.10f1".to_string(), &app_state).unwrap();
+
+  let lockfile = process::get_lockfile_path(&project_path);
+  fs::create_dir_all(lockfile.parent().unwrap()).unwrap();
+  let held = fs::File::create(&lockfile).unwrap();
+  assert_eq!(unsafe { libc::flock(held.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) }, 0);
+
+  let err = version_switch::switch_editor_version(project_path.clone(), "2021.3.5f1".to_string(), &app_state).unwrap_err();
+  assert!(matches!(err, AnyError::ProjectAlreadyOpen { .. }));
+  let err = version_switch::rollback(project_path.clone(), record.id, &app_state).unwrap_err();
+  assert!(matches!(err, AnyError::ProjectAlreadyOpen { .. }));
+  assert_eq!(project::load(&project_path).unwrap().version, "2022.3.10f1");
+}
@@ -137,21 +137,111 @@ export namespace TauriRouter {
 
   export async function open_project_in_editor(
     projectPath: string,
-    editorVersion: string
+    editorVersion: string,
+    launchProfile?: string
+  ): Promise<TauriTypes.EditorResolution> {
+    return invoke("cmd_open_project_in_editor", {
+      projectPath,
+      editorVersion,
+      launchProfile,
+    });
+  }
+
+  // launch profiles
+
+  export async function get_launch_profiles(
+    projectPath: string
+  ): Promise<TauriTypes.LaunchProfile[]> {
+    return invoke("cmd_get_launch_profiles", { projectPath });
+  }
+
+  export async function save_launch_profile(
+    profile: TauriTypes.LaunchProfile
+  ): Promise<TauriTypes.LaunchProfile[]> {
+    return invoke("cmd_save_launch_profile", { profile });
+  }
+
+  export async function delete_launch_profile(
+    name: string,
+    projectPath?: string
+  ): Promise<TauriTypes.LaunchProfile[]> {
+    return invoke("cmd_delete_launch_profile", { name, projectPath });
+  }
+
+  export async function set_project_launch_profile(
+    projectPath: string,
+    name?: string
   ): Promise<void> {
-    return invoke("cmd_open_project_in_editor", { projectPath, editorVersion });
+    return invoke("cmd_set_project_launch_profile", { projectPath, name });
   }
 
   export async function change_project_editor_version(
     projectPath: string,
     editorVersion: string
-  ): Promise<void> {
+  ): Promise<TauriTypes.VersionSwitch> {
     return invoke("cmd_change_project_editor_version", {
       projectPath,
       editorVersion,
     });
   }
 
+  export async function get_project_version_history(
+    projectPath: string
+  ): Promise<TauriTypes.VersionSwitch[]> {
+    return invoke("cmd_get_project_version_history", { projectPath });
+  }
+
+  export async function rollback_project_version(
+    projectPath: string,
+    id: number
+  ): Promise<TauriTypes.VersionSwitch> {
+    return invoke("cmd_rollback_project_version", { projectPath, id });
+  }
+
+  // batch jobs, progress arrives on the "job-event" event
+
+  export async function run_job(
+    request: TauriTypes.JobRequest
+  ): Promise<TauriTypes.Job> {
+    return invoke("cmd_run_job", { request });
+  }
+
+  export async function get_project_jobs(
+    projectPath: string
+  ): Promise<TauriTypes.Job[]> {
+    return invoke("cmd_get_project_jobs", { projectPath });
+  }
+
+  // test runs, the parsed report arrives on the "test-report" event
+
+  export async function run_tests(
+    projectPath: string,
+    platform: TauriTypes.TestPlatform,
+    filter?: string,
+    editorVersion?: string,
+    launchProfile?: string
+  ): Promise<TauriTypes.Job> {
+    return invoke("cmd_run_tests", {
+      projectPath,
+      platform,
+      filter,
+      editorVersion,
+      launchProfile,
+    });
+  }
+
+  export async function get_test_reports(
+    projectPath: string
+  ): Promise<TauriTypes.TestRunReport[]> {
+    return invoke("cmd_get_test_reports", { projectPath });
+  }
+
+  export async function compare_last_test_runs(
+    projectPath: string
+  ): Promise<TauriTypes.TestRunComparison> {
+    return invoke("cmd_compare_last_test_runs", { projectPath });
+  }
+
   export async function fetch_project_thumbnail(
     projectPath: string
   ): Promise<string> {
@@ -171,10 +261,75 @@ export namespace TauriRouter {
   }
 
   export async function is_open_in_editor(
+    projectPath: string
+  ): Promise<TauriTypes.OpenState> {
+    return invoke("cmd_is_open_in_editor", { projectPath });
+  }
+
+  export async function remove_stale_lock(projectPath: string): Promise<void> {
+    return invoke("cmd_remove_stale_lock", { projectPath });
+  }
+
+  export async function focus_project_editor(
+    projectPath: string
+  ): Promise<void> {
+    return invoke("cmd_focus_project_editor", { projectPath });
+  }
+
+  // modules the project's active build target needs but the editor doesn't have
+  export async function check_project_modules(
     projectPath: string,
     editorVersion: string
-  ): Promise<boolean> {
-    return invoke("cmd_is_open_in_editor", { projectPath, editorVersion });
+  ): Promise<TauriTypes.ModuleCheck> {
+    return invoke("cmd_check_project_modules", { projectPath, editorVersion });
+  }
+
+  // editor logs, watched logs send new lines on the "editor-log" event
+
+  export async function get_log_sources(
+    projectPath?: string
+  ): Promise<TauriTypes.LogSource[]> {
+    return invoke("cmd_get_log_sources", { projectPath });
+  }
+
+  export async function read_editor_log(
+    path: string,
+    kinds?: TauriTypes.LogLineKind[]
+  ): Promise<TauriTypes.LogLine[]> {
+    return invoke("cmd_read_editor_log", { path, kinds });
+  }
+
+  export async function watch_editor_log(path: string): Promise<void> {
+    return invoke("cmd_watch_editor_log", { path });
+  }
+
+  export async function unwatch_editor_log(path: string): Promise<void> {
+    return invoke("cmd_unwatch_editor_log", { path });
+  }
+
+  export async function get_crash_reports(
+    projectPath: string
+  ): Promise<TauriTypes.CrashReport[]> {
+    return invoke("cmd_get_crash_reports", { projectPath });
+  }
+
+  export async function delete_crash_report(
+    projectPath: string,
+    id: number
+  ): Promise<void> {
+    return invoke("cmd_delete_crash_report", { projectPath, id });
+  }
+
+  export async function get_usage_report(): Promise<
+    TauriTypes.ProjectUsage[]
+  > {
+    return invoke("cmd_get_usage_report");
+  }
+
+  export async function get_running_editors(): Promise<
+    TauriTypes.EditorProcess[]
+  > {
+    return invoke("cmd_get_running_editors");
   }
 
   export async function load_project_files_tree(
@@ -204,6 +359,87 @@ export namespace TauriRouter {
     return invoke("cmd_estimate_editor_size", { editorVersion });
   }
 
+  // registered projects on the version and projects it has open
+  export async function get_editor_dependent_projects(
+    editorVersion: string
+  ): Promise<string[]> {
+    return invoke("cmd_get_editor_dependent_projects", { editorVersion });
+  }
+
+  // deletes the install, progress arrives on the "editor-uninstall" event.
+  // fails with EditorInUse unless dependent projects can move to reassignTo
+  export async function uninstall_editor(
+    editorVersion: string,
+    reassignTo?: string
+  ): Promise<TauriTypes.Uninstall> {
+    return invoke("cmd_uninstall_editor", { editorVersion, reassignTo });
+  }
+
+  // extracts local archives into the hub editors folder, progress arrives on the "editor-install" event
+  export async function install_editor_from_archives(
+    request: TauriTypes.InstallRequest
+  ): Promise<TauriTypes.UnityEditorInstall> {
+    return invoke("cmd_install_editor_from_archives", { request });
+  }
+
+  // cached, bundled with the editor docs, or from the releaseNotesUrl pref
+  export async function get_release_notes(
+    editorVersion: string,
+    refresh?: boolean
+  ): Promise<TauriTypes.ReleaseNotes> {
+    return invoke("cmd_get_release_notes", { editorVersion, refresh });
+  }
+
+  // matching lines per installed version, newest first
+  export async function search_release_notes(
+    query: string
+  ): Promise<TauriTypes.ReleaseNotesMatch[]> {
+    return invoke("cmd_search_release_notes", { query });
+  }
+
+  // per top-level folder and per module, only changed folders are measured again
+  export async function get_editor_size_breakdown(
+    editorVersion: string
+  ): Promise<TauriTypes.EditorSize> {
+    return invoke("cmd_get_editor_size_breakdown", { editorVersion });
+  }
+
+  export async function resolve_editor(
+    editorVersion: string
+  ): Promise<TauriTypes.EditorResolution> {
+    return invoke("cmd_resolve_editor", { editorVersion });
+  }
+
+  export async function add_editor_search_path(
+    path: string
+  ): Promise<TauriTypes.UnityEditorInstall[]> {
+    return invoke("cmd_add_editor_search_path", { path });
+  }
+
+  export async function remove_editor_search_path(
+    path: string
+  ): Promise<TauriTypes.UnityEditorInstall[]> {
+    return invoke("cmd_remove_editor_search_path", { path });
+  }
+
+  export async function add_manual_editor(
+    path: string
+  ): Promise<TauriTypes.UnityEditorInstall> {
+    return invoke("cmd_add_manual_editor", { path });
+  }
+
+  export async function remove_manual_editor(
+    path: string
+  ): Promise<TauriTypes.UnityEditorInstall[]> {
+    return invoke("cmd_remove_manual_editor", { path });
+  }
+
+  // hub import
+
+  export async function import_from_hub(): Promise<TauriTypes.HubImportReport> {
+    return invoke("cmd_import_from_hub");
+  }
+
   // package
 
   export async function get_default_editor_packages(
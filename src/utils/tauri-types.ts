@@ -8,6 +8,17 @@ export namespace TauriTypes {
     isPinned: boolean;
     addedAt?: BigInt;
     lastOpenedAt?: BigInt;
+    totalOpenTime?: number;
+    sessionCount?: number;
+  }
+
+  export interface ProjectUsage {
+    name: string;
+    path: string;
+    sessionCount: number;
+    totalOpenTime: number;
+    averageSession: number;
+    lastOpenedAt: number;
   }
 
   export enum PrefsKey {
@@ -16,6 +27,12 @@ export namespace TauriTypes {
     HubAppDataPath = "HubAppDataPath",
     NewProjectPath = "NewProjectPath",
     ProjectSortType = "ProjectSortType",
+    EditorSearchPaths = "EditorSearchPaths",
+    ManualEditorPaths = "ManualEditorPaths",
+    LaunchProfiles = "LaunchProfiles",
+    DefaultLaunchProfile = "DefaultLaunchProfile",
+    ProjectLaunchProfiles = "ProjectLaunchProfiles",
+    ReleaseNotesUrl = "ReleaseNotesUrl",
   }
 
   export interface Prefs {
@@ -24,6 +41,28 @@ export namespace TauriTypes {
     hubEditorsPath?: string;
     hubAppdataPath?: string;
     projectSortType?: SortType;
+    editorSearchPaths: string[];
+    manualEditorPaths: string[];
+    launchProfiles: LaunchProfile[];
+    defaultLaunchProfile?: string;
+    projectLaunchProfiles: Record<string, string>;
+    releaseNotesUrl?: string;
+  }
+
+  export interface LaunchProfile {
+    name: string;
+    arguments: string[];
+    env: Record<string, string>;
+    projectPath?: string;
+  }
+
+  export interface HubImportReport {
+    addedProjects: string[];
+    duplicateProjects: string[];
+    missingProjects: string[];
+    addedEditorPaths: string[];
+    duplicateEditorPaths: string[];
+    missingEditorPaths: string[];
   }
 
   export interface UserCache {
@@ -36,10 +75,195 @@ export namespace TauriTypes {
     LastEditorVersion = "LastEditorVersion",
   }
 
+  export type VersionChange =
+    | "Exact"
+    | "PatchUpgrade"
+    | "PatchDowngrade"
+    | "Upgrade"
+    | "Downgrade";
+
+  export interface EditorCandidate {
+    version: string;
+    exePath: string;
+    change: VersionChange;
+  }
+
+  export interface EditorResolution {
+    requested: string;
+    editor: UnityEditorInstall;
+    change: VersionChange;
+    warning?: string;
+  }
+
+  export interface VersionSwitch {
+    id: number;
+    fromVersion: string;
+    fromRevision?: string;
+    toVersion: string;
+    toRevision?: string;
+    snapshotPath: string;
+    switchedAt: number;
+    rolledBackAt?: number;
+  }
+
+  export type TestPlatform = "EditMode" | "PlayMode";
+
+  export type JobKind =
+    | { type: "executeMethod"; method: string }
+    | { type: "runTests"; platform: TestPlatform; filter?: string }
+    | { type: "build"; target: string; method: string };
+
+  export interface JobRequest {
+    projectPath: string;
+    editorVersion?: string;
+    kind: JobKind;
+    launchProfile?: string;
+  }
+
+  export type JobStatus = "Running" | "Succeeded" | "TestsFailed" | "Failed";
+
+  export interface Job {
+    id: number;
+    projectPath: string;
+    editorVersion: string;
+    kind: JobKind;
+    arguments: string[];
+    logPath: string;
+    resultsPath?: string;
+    status: JobStatus;
+    exitCode?: number;
+    message?: string;
+    warning?: string;
+    startedAt: number;
+    finishedAt?: number;
+  }
+
+  export type JobEvent =
+    | { type: "started"; job: Job }
+    | { type: "log"; id: number; line: string }
+    | { type: "progress"; id: number; message: string }
+    | { type: "finished"; job: Job };
+
+  export type TestResult = "Passed" | "Failed" | "Skipped" | "Inconclusive";
+
+  export interface TestFailure {
+    message: string;
+    stackTrace?: string;
+  }
+
+  export interface TestCase {
+    name: string;
+    fullName: string;
+    result: TestResult;
+    duration: number;
+    failure?: TestFailure;
+  }
+
+  export interface TestSuite {
+    name: string;
+    fullName: string;
+    duration: number;
+    cases: TestCase[];
+  }
+
+  export interface TestRunReport {
+    id: number;
+    platform?: TestPlatform;
+    editorVersion: string;
+    result: TestResult;
+    total: number;
+    passed: number;
+    failed: number;
+    skipped: number;
+    inconclusive: number;
+    duration: number;
+    suites: TestSuite[];
+    finishedAt: number;
+  }
+
+  export interface TestRunComparison {
+    previousId?: number;
+    latestId: number;
+    newlyFailing: string[];
+    stillFailing: string[];
+    fixed: string[];
+    added: string[];
+    removed: string[];
+  }
+
+  export type ProcessSource = "Launched" | "ProcessTable" | "Lockfile";
+
+  export interface EditorProcess {
+    pid?: number;
+    projectPath: string;
+    editorVersion?: string;
+    source: ProcessSource;
+  }
+
+  // payload of the "editor-started" and "editor-exited" events
+  export interface EditorSession {
+    pid: number;
+    projectPath: string;
+    editorVersion: string;
+    logPath?: string;
+    startedAt: number;
+    endedAt?: number;
+    exitCode?: number;
+    crashed: boolean;
+  }
+
+  export type LogLineKind =
+    | "Info"
+    | "CompileError"
+    | "Warning"
+    | "Exception"
+    | "PackageError";
+
+  export interface LogLine {
+    number: number;
+    text: string;
+    kind: LogLineKind;
+    file?: string;
+    line?: number;
+    column?: number;
+  }
+
+  export interface LogSource {
+    name: string;
+    path: string;
+    exists: boolean;
+  }
+
+  // payload of the "editor-log" event
+  export interface LogEvent {
+    path: string;
+    lines: LogLine[];
+  }
+
+  export interface CrashReport {
+    id: number;
+    projectPath: string;
+    editorVersion: string;
+    pid: number;
+    exitCode?: number;
+    startedAt: number;
+    crashedAt: number;
+    archivePath: string;
+    files: string[];
+    logTail: string[];
+  }
+
+  export type OpenState =
+    | { state: "closed" }
+    | { state: "open"; process: EditorProcess }
+    | { state: "staleLock"; lockfile: string };
+
   export interface UnityEditorInstall {
     exePath: string;
     version: string;
+    revision?: string;
     modules: UnityEditorModule[];
+    versionWarning?: string;
 
     // extra
     diskSize?: LazyValue<number>;
@@ -54,6 +278,92 @@ export namespace TauriTypes {
     selected: boolean;
   }
 
+  export type ReleaseNotesSource =
+    | { type: "bundled"; path: string }
+    | { type: "mirror"; url: string };
+
+  export interface ReleaseNotes {
+    version: string;
+    source: ReleaseNotesSource;
+    text: string;
+    fetchedAt: number;
+  }
+
+  export interface ReleaseNotesMatch {
+    version: string;
+    lines: string[];
+  }
+
+  export interface ModuleArchive {
+    path: string;
+    id: string;
+    name: string;
+    category?: string;
+    destination?: string;
+  }
+
+  export interface InstallRequest {
+    archive: string;
+    modules: ModuleArchive[];
+  }
+
+  export interface InstallProgress {
+    archive: string;
+    step: number;
+    steps: number;
+    done: number;
+    total: number;
+  }
+
+  export interface UninstallProgress {
+    version: string;
+    removed: number;
+    total: number;
+  }
+
+  export interface Uninstall {
+    version: string;
+    installPath: string;
+    reassigned: VersionSwitch[];
+    removedFiles: number;
+    deleted: boolean;
+  }
+
+  export type SizeSectionKind = "Folder" | "Module";
+
+  export interface SizeSection {
+    path: string;
+    kind: SizeSectionKind;
+    size: number;
+    modified: number;
+  }
+
+  export interface EditorSize {
+    total: number;
+    sections: SizeSection[];
+    modified: number;
+    measuredAt: number;
+  }
+
+  export interface BuildTarget {
+    id: number;
+    name?: string;
+  }
+
+  export interface RequiredModule {
+    id: string;
+    name: string;
+  }
+
+  export interface ModuleCheck {
+    projectPath: string;
+    editorVersion: string;
+    buildTarget?: BuildTarget;
+    required: RequiredModule[];
+    missing: RequiredModule[];
+    modulesListed: boolean;
+  }
+
   export interface Page<T> {
     items: T[];
     index: number;
@@ -207,6 +517,56 @@ export namespace TauriTypes {
     nameFilter?: string;
     // sortBy?: SortType;
   }
+
+  export type HubErrorCode =
+    | "Io"
+    | "Other"
+    | "Json"
+    | "EditorNotFound"
+    | "EditorInUse"
+    | "EditorExists"
+    | "EditorInvalid"
+    | "VersionInvalid"
+    | "LaunchProfileNotFound"
+    | "PrefMissing"
+    | "PrefInvalid"
+    | "ManifestCorrupt"
+    | "SchemaTooNew"
+    | "UninstallIncomplete"
+    | "ArchiveUnsupported"
+    | "ExtractionFailed"
+    | "ModuleDestinationInvalid"
+    | "ProjectExists"
+    | "ProjectNotFound"
+    | "ProjectAlreadyOpen"
+    | "ProjectLockStale"
+    | "VersionSwitchNotFound"
+    | "VersionSwitchNotLatest"
+    | "SnapshotMissing"
+    | "CrashReportNotFound"
+    | "TestResultsUnreadable"
+    | "NoTestRuns"
+    | "TemplateInvalid"
+    | "GitCloneFailed"
+    | "ReleaseNotesFetchFailed"
+    | "ReleaseNotesNotFound"
+    | "StateLocked";
+
+  // every failed command rejects with this
+  export interface HubError {
+    code: HubErrorCode;
+    message: string;
+    details: Record<string, any> | null;
+  }
+
+  export function isHubError(error: unknown): error is HubError {
+    return (
+      typeof error === "object" &&
+      error !== null &&
+      "code" in error &&
+      "message" in error
+    );
+  }
 }
 
 export {};
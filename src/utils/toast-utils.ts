@@ -1,4 +1,5 @@
 import toast from "react-hot-toast";
+import { TauriTypes } from "./tauri-types";
 
 export function routeErrorToToast(
   error: Error | string | null | undefined | unknown
@@ -11,6 +12,12 @@ export function routeErrorToToast(
     error = new Error(error);
   }
 
+  if (TauriTypes.isHubError(error)) {
+    console.error(error);
+    toast.error(`Error: ${error.message}`);
+    return;
+  }
+
   if (error instanceof Error) {
     error = new Error(`Error: ${error.message}`);
   }
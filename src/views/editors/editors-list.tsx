@@ -6,11 +6,20 @@ import AsyncComponent from "../../components/async-component";
 import LoadingSpinner from "../../components/svg/loading-spinner";
 import EllipsisVertical from "../../components/svg/ellipsis-vertical";
 import { open } from "@tauri-apps/api/shell";
-import { Menu, Item, useContextMenu, TriggerEvent } from "react-contexify";
+import { listen } from "@tauri-apps/api/event";
+import {
+  Menu,
+  Item,
+  Submenu,
+  useContextMenu,
+  TriggerEvent,
+} from "react-contexify";
 import { convertBytes, groupBy } from "../../utils";
 import AsyncLazyValueComponent from "../../components/async-lazy-value-component";
 import { GlobalContext } from "../../context/global-context";
 import { routeErrorToToast } from "../../utils/toast-utils";
+import { ask, open as openDialog } from "@tauri-apps/api/dialog";
+import toast from "react-hot-toast";
 
 interface UnityEditorInstallGroup {
   version: string;
@@ -112,6 +121,44 @@ export default function EditorsList() {
     calculateDiskSizes().catch(routeErrorToToast);
   }, [calculatingEditorSize]);
 
+  async function installFromArchive() {
+    const archive = await openDialog({
+      multiple: false,
+      filters: [
+        {
+          name: "Editor archive",
+          extensions: ["xz", "txz", "gz", "tgz", "zip", "pkg"],
+        },
+      ],
+    });
+    if (!archive) return;
+
+    const toastId = toast.loading("Extracting editor");
+    const unlisten = await listen<TauriTypes.InstallProgress>(
+      "editor-install",
+      (event) => {
+        const { done, total } = event.payload;
+        const percent =
+          total > 0 ? ` (${Math.floor((done / total) * 100)}%)` : "";
+        toast.loading(`Extracting editor${percent}`, { id: toastId });
+      }
+    );
+
+    try {
+      const editor = await TauriRouter.install_editor_from_archives({
+        archive: archive as string,
+        modules: [],
+      });
+      toast.success(`Installed ${editor.version}`, { id: toastId });
+      await loadEditors();
+    } catch (e) {
+      toast.dismiss(toastId);
+      routeErrorToToast(e);
+    } finally {
+      unlisten();
+    }
+  }
+
   return (
     <>
       <AsyncComponent
@@ -173,10 +220,25 @@ export default function EditorsList() {
                 key={selectedGroup.version}
                 version={selectedGroup.version}
                 editors={selectedGroup.editors}
+                versions={[
+                  ...new Set(
+                    data.value.editorGroups.flatMap((x) =>
+                      x.editors.map((e) => e.version)
+                    )
+                  ),
+                ]}
+                onRemoved={loadEditors}
               />
             )}
             <p className="text-stone-400 text-sm select-none">
-              To get more editors, use the Unity Hub.
+              To get more editors, use the Unity Hub or{" "}
+              <span
+                className="underline underline-offset-2 text-sky-600 cursor-pointer"
+                onClick={() => installFromArchive().catch(routeErrorToToast)}
+              >
+                install from an archive
+              </span>
+              .
             </p>
           </div>
         </div>
@@ -188,20 +250,38 @@ export default function EditorsList() {
 function EditorGroup({
   version,
   editors,
+  versions,
+  onRemoved,
 }: {
   version: string;
   editors: TauriTypes.UnityEditorInstall[];
+  versions: string[];
+  onRemoved: () => void;
 }) {
   return (
     <div className="flex flex-col gap-4">
       {editors.map((e) => (
-        <Editor key={e.exePath} editor={e} />
+        <Editor
+          key={e.exePath}
+          editor={e}
+          versions={versions}
+          onRemoved={onRemoved}
+        />
       ))}
     </div>
   );
 }
 
-function Editor({ editor }: { editor: TauriTypes.UnityEditorInstall }) {
+function Editor({
+  editor,
+  versions,
+  onRemoved,
+}: {
+  editor: TauriTypes.UnityEditorInstall;
+  // every installed version, projects can be moved to any other one
+  versions: string[];
+  onRemoved: () => void;
+}) {
   const docsUrl = useMemo(() => {
     const trimmedF1Version = editor.version.endsWith("f1")
       ? editor.version.slice(0, -2)
@@ -221,7 +301,7 @@ function Editor({ editor }: { editor: TauriTypes.UnityEditorInstall }) {
     });
   }
 
-  function handleItemClick({ id, event, _ }: any) {
+  function handleItemClick({ id, event, data }: any) {
     event.stopPropagation();
     hideAll();
 
@@ -232,6 +312,51 @@ function Editor({ editor }: { editor: TauriTypes.UnityEditorInstall }) {
       case "changelog":
         open(docsUrl);
         break;
+      case "uninstall":
+        uninstall(data).catch(routeErrorToToast);
+        break;
+    }
+  }
+
+  async function uninstall(reassignTo?: string) {
+    const moving = reassignTo
+      ? ` Projects using it are moved to ${reassignTo}.`
+      : "";
+    const confirmed = await ask(
+      `Delete ${editor.exePath} from disk? This can't be undone.${moving}`,
+      { title: editor.version, type: "warning" }
+    );
+    if (!confirmed) return;
+
+    const toastId = toast.loading(`Uninstalling ${editor.version}`);
+    const unlisten = await listen<TauriTypes.UninstallProgress>(
+      "editor-uninstall",
+      (event) => {
+        const { removed, total } = event.payload;
+        const percent =
+          total > 0 ? ` (${Math.floor((removed / total) * 100)}%)` : "";
+        toast.loading(`Uninstalling ${editor.version}${percent}`, {
+          id: toastId,
+        });
+      }
+    );
+
+    try {
+      await TauriRouter.uninstall_editor(editor.version, reassignTo);
+      toast.success(`Uninstalled ${editor.version}`, { id: toastId });
+      onRemoved();
+    } catch (e) {
+      toast.dismiss(toastId);
+      // the projects have to move to another editor first
+      if (TauriTypes.isHubError(e) && e.code === "EditorInUse") {
+        toast.error(
+          `${e.message}: ${e.details?.projects.join(", ")}. Close them, or uninstall and move them to another version.`
+        );
+      } else {
+        routeErrorToToast(e);
+      }
+    } finally {
+      unlisten();
     }
   }
 
@@ -278,6 +403,11 @@ function Editor({ editor }: { editor: TauriTypes.UnityEditorInstall }) {
         </button>
       </div>
       <p className="text-sm text-stone-500 select-none">{editor.exePath}</p>
+      {editor.versionWarning && (
+        <p className="text-sm text-amber-500 select-none">
+          {editor.versionWarning}
+        </p>
+      )}
       <span className="text-sm text-stone-500 select-none">
         <AsyncLazyValueComponent
           loading={<LoadingSpinner width={18} height={18} />}
@@ -321,6 +451,26 @@ function Editor({ editor }: { editor: TauriTypes.UnityEditorInstall }) {
         <Item id="changelog" onClick={handleItemClick}>
           Open Changelog
         </Item>
+        <Item id="uninstall" onClick={handleItemClick}>
+          Uninstall
+        </Item>
+        <Submenu
+          label="Uninstall and Move Projects To"
+          disabled={!versions.some((x) => x !== editor.version)}
+        >
+          {versions
+            .filter((x) => x !== editor.version)
+            .map((x) => (
+              <Item
+                key={x}
+                id="uninstall"
+                data={x}
+                onClick={handleItemClick}
+              >
+                {x}
+              </Item>
+            ))}
+        </Submenu>
       </Menu>
     </div>
   );
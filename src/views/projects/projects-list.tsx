@@ -25,6 +25,7 @@ import Box from "../../components/svg/box";
 import Pin from "../../components/svg/pin";
 import Sort from "../../components/svg/sort";
 import toast from "react-hot-toast";
+import { ask } from "@tauri-apps/api/dialog";
 import { routeErrorToToast } from "../../utils/toast-utils";
 import { GlobalContext } from "../../context/global-context";
 
@@ -367,24 +368,63 @@ function Project({
 
     setIsOpening(true);
 
-    try {
-      const is_open_already = await TauriRouter.is_open_in_editor(
-        project.path,
-        project.version
+    // a failed check shouldn't block opening, the open call reports real problems
+    const check = await TauriRouter.check_project_modules(
+      project.path,
+      project.version
+    ).catch(() => null);
+    if (check && check.missing.length > 0) {
+      const names = check.missing.map((x) => x.name).join(", ");
+      const open = await ask(
+        `${check.editorVersion} is missing ${names} for the ${check.buildTarget?.name} build target. Open anyway?`,
+        { title: project.name, type: "warning" }
       );
-
-      if (is_open_already) {
-        toast.error("Project is already open!");
-        await new Promise((resolve) => setTimeout(resolve, 500));
+      if (!open) {
         setIsOpening(false);
         return;
       }
+    }
 
-      await TauriRouter.open_project_in_editor(project.path, project.version);
+    try {
+      const resolution = await TauriRouter.open_project_in_editor(
+        project.path,
+        project.version
+      );
+      if (resolution.warning) {
+        toast(resolution.warning);
+      }
       props.reloadPage();
       await new Promise((resolve) => setTimeout(resolve, 4000));
     } catch (e) {
-      routeErrorToToast(e);
+      if (TauriTypes.isHubError(e) && e.code === "ProjectAlreadyOpen") {
+        // bring the running editor forward instead of starting another
+        toast.error("Project is already open!");
+        TauriRouter.focus_project_editor(project.path).catch(() => {});
+      } else if (TauriTypes.isHubError(e) && e.code === "ProjectLockStale") {
+        const remove = await ask(
+          "An editor that is no longer running left this project locked. Remove the lock?",
+          { title: project.name, type: "warning" }
+        );
+        if (remove) {
+          await TauriRouter.remove_stale_lock(project.path).catch(
+            routeErrorToToast
+          );
+        }
+      } else if (
+        // nothing close enough is installed, point at the editors that are
+        TauriTypes.isHubError(e) &&
+        e.code === "EditorNotFound" &&
+        e.details?.candidates?.length > 0
+      ) {
+        const versions = e.details.candidates
+          .map((x: TauriTypes.EditorCandidate) => x.version)
+          .join(", ");
+        toast.error(
+          `${e.message}. Change the project's version to one of: ${versions}`
+        );
+      } else {
+        routeErrorToToast(e);
+      }
       await new Promise((resolve) => setTimeout(resolve, 500));
     }
 